@@ -0,0 +1,326 @@
+/**
+ * The number of frequency bands that the spectrum is split into.
+ */
+pub const BAND_COUNT: usize = 16;
+
+/**
+ * The range of frequencies in hertz that the bands cover, which are spaced out logarithmically like pitch is heard.
+ */
+const LOWEST_BAND_FREQUENCY: f32 = 40.0;
+const HIGHEST_BAND_FREQUENCY: f32 = 16000.0;
+
+/**
+ * Frequencies below this in hertz count as bass, which is what beats are detected in.
+ */
+const BASS_FREQUENCY: f32 = 150.0;
+
+/**
+ * The quietest level in decibels that a band can show, which is where its value bottoms out at 0.
+ */
+const MINIMUM_DECIBELS: f32 = -60.0;
+
+/**
+ * How many seconds of bass energy a beat is compared against, and the shortest time between beats.
+ */
+const BEAT_HISTORY_DURATION: f32 = 1.0;
+const MINIMUM_BEAT_INTERVAL: f32 = 0.25;
+
+/**
+ * What has been heard so far, as it's handed to scenes.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AudioFeatures {
+	/**
+	 * How loud each frequency band is, from 0 to 1, going from the lowest frequencies to the highest.
+	 */
+	pub bands: [f32; BAND_COUNT],
+	/**
+	 * The root mean square of the most recent samples.
+	 */
+	pub level: f32,
+	/**
+	 * The number of beats detected so far and when the last one was.
+	 */
+	pub beat_count: u32,
+	pub last_beat_instant: Option<std::time::Instant>,
+}
+
+/**
+ * Turns a stream of mono samples into audio features. The spectrum is taken from a window of the most recent samples
+ * each time more samples arrive, and a beat is detected whenever the bass suddenly gets louder than it has been over
+ * the last second.
+ */
+pub struct AudioAnalyzer {
+	sample_rate: f32,
+	fft_size: usize,
+	/**
+	 * The most recent samples, from oldest to newest.
+	 */
+	samples: std::collections::VecDeque<f32>,
+	window: Vec<f32>,
+	/**
+	 * The range of FFT bins that goes into each band.
+	 */
+	band_bin_ranges: Vec<std::ops::Range<usize>>,
+	bass_bin_range: std::ops::Range<usize>,
+	/**
+	 * How much of a band's previous value is kept each time it gets quieter, so bands fall smoothly instead of
+	 * flickering.
+	 */
+	smoothing: f32,
+	beat_sensitivity: f32,
+	bass_energy_history: std::collections::VecDeque<f32>,
+	seconds_since_beat: f32,
+	features: AudioFeatures,
+}
+
+impl AudioAnalyzer {
+	/**
+	 * Create an analyzer for samples at the given rate. The FFT size is rounded up to a power of two.
+	 */
+	pub fn new(sample_rate: u32, fft_size: u32, smoothing: f32, beat_sensitivity: f32) -> Self {
+		let sample_rate = sample_rate.max(1) as f32;
+		let fft_size = (fft_size.max(64) as usize).next_power_of_two();
+		// A Hann window keeps the edges of the window from smearing energy across the whole spectrum.
+		let window = (0..fft_size)
+			.map(|sample_index| {
+				0.5 - 0.5
+					* (2.0 * std::f32::consts::PI * sample_index as f32 / fft_size as f32).cos()
+			})
+			.collect();
+
+		let bin_frequency = sample_rate / fft_size as f32;
+		let bin_count = fft_size / 2;
+		let frequency_to_bin =
+			|frequency: f32| ((frequency / bin_frequency).round() as usize).clamp(1, bin_count);
+		let highest_frequency = HIGHEST_BAND_FREQUENCY.min(0.5 * sample_rate);
+		let band_bin_ranges = (0..BAND_COUNT)
+			.map(|band_index| {
+				let band_frequency = |band_edge_index: usize| {
+					LOWEST_BAND_FREQUENCY
+						* (highest_frequency / LOWEST_BAND_FREQUENCY)
+							.powf(band_edge_index as f32 / BAND_COUNT as f32)
+				};
+				let start_bin = frequency_to_bin(band_frequency(band_index));
+				// Low bands can be narrower than a single bin, so every band gets at least one.
+				let end_bin = frequency_to_bin(band_frequency(band_index + 1)).max(start_bin + 1);
+				start_bin..end_bin.min(bin_count + 1)
+			})
+			.collect();
+
+		Self {
+			sample_rate,
+			fft_size,
+			samples: std::collections::VecDeque::from(vec![0.0; fft_size]),
+			window,
+			band_bin_ranges,
+			bass_bin_range: 1..frequency_to_bin(BASS_FREQUENCY).max(2),
+			smoothing: smoothing.clamp(0.0, 1.0),
+			beat_sensitivity,
+			bass_energy_history: std::collections::VecDeque::new(),
+			seconds_since_beat: f32::INFINITY,
+			features: AudioFeatures::default(),
+		}
+	}
+
+	pub fn features(&self) -> AudioFeatures {
+		self.features
+	}
+
+	/**
+	 * Take in the newest mono samples, from -1 to 1, and analyze the window that ends with them.
+	 */
+	pub fn push_samples(&mut self, new_samples: &[f32]) {
+		if new_samples.is_empty() {
+			return;
+		}
+		for &sample in new_samples {
+			self.samples.pop_front();
+			self.samples.push_back(sample);
+		}
+		self.features.level = (new_samples
+			.iter()
+			.map(|sample| sample * sample)
+			.sum::<f32>()
+			/ new_samples.len() as f32)
+			.sqrt();
+
+		let mut spectrum = self
+			.samples
+			.iter()
+			.zip(&self.window)
+			.map(|(sample, window)| [sample * window, 0.0])
+			.collect::<Vec<_>>();
+		fft(&mut spectrum);
+		// A sine wave with an amplitude of 1 peaks at a quarter of the FFT size once it has been through the window, so
+		// the magnitudes are scaled to make that peak 1.
+		let amplitude_scale = 4.0 / self.fft_size as f32;
+		let amplitudes = spectrum[..=self.fft_size / 2]
+			.iter()
+			.map(|[real, imaginary]| (real * real + imaginary * imaginary).sqrt() * amplitude_scale)
+			.collect::<Vec<_>>();
+
+		for (band, bin_range) in self.features.bands.iter_mut().zip(&self.band_bin_ranges) {
+			let amplitude = amplitudes[bin_range.clone()]
+				.iter()
+				.fold(0.0_f32, |maximum, amplitude| maximum.max(*amplitude));
+			let decibels = 20.0 * amplitude.max(f32::MIN_POSITIVE).log10();
+			let value = ((decibels - MINIMUM_DECIBELS) / -MINIMUM_DECIBELS).clamp(0.0, 1.0);
+			*band = value.max(*band * self.smoothing);
+		}
+
+		self.detect_beat(&amplitudes, new_samples.len() as f32 / self.sample_rate);
+	}
+
+	fn detect_beat(&mut self, amplitudes: &[f32], elapsed_seconds: f32) {
+		let bass_energy = amplitudes[self.bass_bin_range.clone()]
+			.iter()
+			.map(|amplitude| amplitude * amplitude)
+			.sum::<f32>();
+		let history_length = (BEAT_HISTORY_DURATION / elapsed_seconds).ceil().max(1.0) as usize;
+		let average_bass_energy = if self.bass_energy_history.is_empty() {
+			0.0
+		} else {
+			self.bass_energy_history.iter().sum::<f32>() / self.bass_energy_history.len() as f32
+		};
+		self.seconds_since_beat += elapsed_seconds;
+		// Near silence never counts as a beat, however much louder it is than what came before.
+		let is_beat = bass_energy > self.beat_sensitivity * average_bass_energy
+			&& bass_energy > 1e-4
+			&& self.seconds_since_beat >= MINIMUM_BEAT_INTERVAL;
+		if is_beat {
+			self.seconds_since_beat = 0.0;
+			self.features.beat_count = self.features.beat_count.wrapping_add(1);
+			self.features.last_beat_instant = Some(std::time::Instant::now());
+		}
+		self.bass_energy_history.push_back(bass_energy);
+		while self.bass_energy_history.len() > history_length {
+			self.bass_energy_history.pop_front();
+		}
+	}
+}
+
+/**
+ * Transform complex values into their spectrum in place with an iterative radix-2 FFT. The number of values has to
+ * be a power of two.
+ */
+fn fft(values: &mut [[f32; 2]]) {
+	let value_count = values.len();
+	// Put the values into bit-reversed order so that the butterflies can work on neighboring runs of values.
+	let mut reversed_index = 0;
+	for index in 1..value_count {
+		let mut bit = value_count >> 1;
+		while reversed_index & bit != 0 {
+			reversed_index ^= bit;
+			bit >>= 1;
+		}
+		reversed_index |= bit;
+		if index < reversed_index {
+			values.swap(index, reversed_index);
+		}
+	}
+
+	let mut run_length = 2;
+	while run_length <= value_count {
+		let angle = -2.0 * std::f32::consts::PI / run_length as f32;
+		for run_start in (0..value_count).step_by(run_length) {
+			for offset in 0..run_length / 2 {
+				let [twiddle_real, twiddle_imaginary] =
+					[(angle * offset as f32).cos(), (angle * offset as f32).sin()];
+				let [even_real, even_imaginary] = values[run_start + offset];
+				let [odd_real, odd_imaginary] = values[run_start + offset + run_length / 2];
+				let product = [
+					odd_real * twiddle_real - odd_imaginary * twiddle_imaginary,
+					odd_real * twiddle_imaginary + odd_imaginary * twiddle_real,
+				];
+				values[run_start + offset] = [even_real + product[0], even_imaginary + product[1]];
+				values[run_start + offset + run_length / 2] =
+					[even_real - product[0], even_imaginary - product[1]];
+			}
+		}
+		run_length *= 2;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::AudioAnalyzer;
+
+	const SAMPLE_RATE: u32 = 44100;
+	const FFT_SIZE: u32 = 2048;
+	const CHUNK_LENGTH: usize = 1024;
+
+	fn sine(frequency: f32, amplitude: f32, frame_range: std::ops::Range<usize>) -> Vec<f32> {
+		frame_range
+			.map(|frame_index| {
+				amplitude
+					* (2.0 * std::f32::consts::PI * frequency * frame_index as f32
+						/ SAMPLE_RATE as f32)
+						.sin()
+			})
+			.collect()
+	}
+
+	/**
+	 * Push the samples in chunks the size of what the audio input reads at a time.
+	 */
+	fn push_in_chunks(analyzer: &mut AudioAnalyzer, samples: &[f32]) {
+		for chunk in samples.chunks(CHUNK_LENGTH) {
+			analyzer.push_samples(chunk);
+		}
+	}
+
+	#[test]
+	fn peaks_in_the_band_of_a_tone() {
+		let mut analyzer = AudioAnalyzer::new(SAMPLE_RATE, FFT_SIZE, 0.0, 1.5);
+		push_in_chunks(&mut analyzer, &sine(440.0, 1.0, 0..4 * FFT_SIZE as usize));
+		let features = analyzer.features();
+
+		let tone_bin = (440.0 * FFT_SIZE as f32 / SAMPLE_RATE as f32).round() as usize;
+		let tone_band_index = analyzer
+			.band_bin_ranges
+			.iter()
+			.position(|bin_range| bin_range.contains(&tone_bin))
+			.unwrap();
+		let loudest_band_index = (0..features.bands.len())
+			.max_by(|&a, &b| features.bands[a].total_cmp(&features.bands[b]))
+			.unwrap();
+		assert_eq!(loudest_band_index, tone_band_index);
+		assert!(features.bands[tone_band_index] > 0.95);
+		assert!((features.level - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+	}
+
+	#[test]
+	fn counts_beats_when_the_bass_pulses() {
+		let mut analyzer = AudioAnalyzer::new(SAMPLE_RATE, FFT_SIZE, 0.0, 1.5);
+		// Half a second of a 60 Hz tone followed by half a second of silence, four times over.
+		let pulse_length = SAMPLE_RATE as usize / 2;
+		for pulse_index in 0..4 {
+			let pulse_start = 2 * pulse_index * pulse_length;
+			push_in_chunks(
+				&mut analyzer,
+				&sine(60.0, 0.8, pulse_start..pulse_start + pulse_length),
+			);
+			push_in_chunks(&mut analyzer, &vec![0.0; pulse_length]);
+		}
+		let features = analyzer.features();
+		assert!(features.beat_count >= 4);
+		assert!(features.last_beat_instant.is_some());
+	}
+
+	#[test]
+	fn counts_a_steady_tone_as_one_beat() {
+		let mut analyzer = AudioAnalyzer::new(SAMPLE_RATE, FFT_SIZE, 0.0, 1.5);
+		push_in_chunks(&mut analyzer, &sine(60.0, 0.8, 0..2 * SAMPLE_RATE as usize));
+		assert_eq!(analyzer.features().beat_count, 1);
+	}
+
+	#[test]
+	fn counts_no_beats_in_silence() {
+		let mut analyzer = AudioAnalyzer::new(SAMPLE_RATE, FFT_SIZE, 0.0, 1.5);
+		push_in_chunks(&mut analyzer, &vec![0.0; SAMPLE_RATE as usize]);
+		let features = analyzer.features();
+		assert_eq!(features.beat_count, 0);
+		assert!(features.bands.iter().all(|&band| band == 0.0));
+	}
+}
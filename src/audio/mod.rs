@@ -0,0 +1,259 @@
+mod analyzer;
+mod pcm;
+
+pub use analyzer::AudioFeatures;
+
+/**
+ * How long to wait at a time for samples to arrive in a FIFO before checking whether they're still wanted.
+ */
+const FIFO_POLL_TIMEOUT_MILLISECONDS: libc::c_int = 250;
+
+/**
+ * Listens to a stream of PCM samples on a background thread and keeps the latest analysis of it ready for scenes.
+ * The samples can come from a FIFO, such as one fed by `pw-cat --record`, `parec` or MPD's fifo output, or from a file.
+ * Files are played back in real time and start over when they end, while FIFOs are read as fast as samples arrive and
+ * are opened again when the program writing to them stops.
+ */
+pub struct AudioInput {
+	features: std::sync::Arc<std::sync::Mutex<AudioFeatures>>,
+}
+
+impl AudioInput {
+	pub fn new(
+		source: std::path::PathBuf,
+		audio_configuration: &crate::config::AudioConfig,
+	) -> Self {
+		let features = std::sync::Arc::new(std::sync::Mutex::new(AudioFeatures::default()));
+		let thread_features = features.clone();
+		let audio_configuration = audio_configuration.clone();
+		let spawn_result = std::thread::Builder::new()
+			.name("audio input".to_owned())
+			.spawn(move || Self::run(&source, &audio_configuration, &thread_features));
+		if let Err(error) = spawn_result {
+			log::warn!("Could not start the audio input: {}", error);
+		}
+		Self { features }
+	}
+
+	/**
+	 * Get the latest analysis of what has been heard.
+	 */
+	pub fn features(&self) -> AudioFeatures {
+		self.features
+			.lock()
+			.map(|features| *features)
+			.unwrap_or_default()
+	}
+
+	fn run(
+		source: &std::path::Path,
+		audio_configuration: &crate::config::AudioConfig,
+		features: &std::sync::Arc<std::sync::Mutex<AudioFeatures>>,
+	) {
+		use std::os::unix::fs::FileTypeExt;
+
+		let raw_format = pcm::PcmFormat {
+			sample_format: audio_configuration.sample_format,
+			sample_rate: audio_configuration.sample_rate,
+			channel_count: audio_configuration.channel_count,
+		};
+		let is_fifo = std::fs::metadata(source)
+			.map(|metadata| metadata.file_type().is_fifo())
+			.unwrap_or(false);
+		let mut analyzer: Option<analyzer::AudioAnalyzer> = None;
+		// The input has been dropped once nothing else is holding onto the features.
+		while std::sync::Arc::strong_count(features) > 1 {
+			let pcm_stream = if is_fifo {
+				match Self::wait_for_fifo(source, features) {
+					Ok(Some(file)) => pcm::PcmStream::from_file(file, source, raw_format),
+					Ok(None) => return,
+					Err(error) => Err(error),
+				}
+			} else {
+				pcm::PcmStream::open(source, raw_format)
+			};
+			let mut pcm_stream = match pcm_stream {
+				Ok(pcm_stream) => pcm_stream,
+				Err(error) => {
+					log::warn!("Could not start listening to audio: {}", error);
+					return;
+				}
+			};
+			let sample_rate = pcm_stream.format.sample_rate.max(1);
+			let analyzer = analyzer.get_or_insert_with(|| {
+				analyzer::AudioAnalyzer::new(
+					sample_rate,
+					audio_configuration.fft_size,
+					audio_configuration.smoothing,
+					audio_configuration.beat_sensitivity,
+				)
+			});
+			// Samples are analyzed in small hops so that the features keep up with the music.
+			let hop_frame_count = (sample_rate / 100).max(1) as usize;
+
+			let start_instant = std::time::Instant::now();
+			let mut frame_count = 0;
+			loop {
+				let samples = match pcm_stream.read_mono_samples(hop_frame_count) {
+					Ok(samples) => samples,
+					Err(error) => {
+						log::warn!("Could not read audio from {}: {}", source.display(), error);
+						return;
+					}
+				};
+				if samples.is_empty() {
+					break;
+				}
+				analyzer.push_samples(&samples);
+				if let Ok(mut features) = features.lock() {
+					*features = analyzer.features();
+				}
+				if std::sync::Arc::strong_count(features) <= 1 {
+					return;
+				}
+				// Files would otherwise be read all at once, so they are held back to the rate they would play at.
+				frame_count += samples.len();
+				if !is_fifo {
+					let playback_instant = start_instant
+						+ std::time::Duration::from_secs_f64(
+							frame_count as f64 / sample_rate as f64,
+						);
+					std::thread::sleep(
+						playback_instant.saturating_duration_since(std::time::Instant::now()),
+					);
+				}
+			}
+			if !is_fifo && frame_count == 0 {
+				log::warn!(
+					"There are no samples in the audio file {}",
+					source.display()
+				);
+				return;
+			}
+		}
+	}
+
+	/**
+	 * Open a FIFO and wait for samples to arrive in it. Opening a FIFO normally blocks until a program opens it for
+	 * writing, which would keep an input that has been replaced, such as after the configuration is reloaded, waiting
+	 * around to compete with its replacement for samples. So it's opened without blocking and then polled, giving up
+	 * once the input has been dropped, in which case nothing is returned.
+	 */
+	fn wait_for_fifo(
+		source: &std::path::Path,
+		features: &std::sync::Arc<std::sync::Mutex<AudioFeatures>>,
+	) -> Result<Option<std::fs::File>, String> {
+		use std::os::unix::fs::OpenOptionsExt;
+		use std::os::unix::io::AsRawFd;
+
+		let failed =
+			|error: std::io::Error| format!("Could not open {}: {}", source.display(), error);
+		'open: while std::sync::Arc::strong_count(features) > 1 {
+			let file = std::fs::OpenOptions::new()
+				.read(true)
+				.custom_flags(libc::O_NONBLOCK)
+				.open(source)
+				.map_err(failed)?;
+			while std::sync::Arc::strong_count(features) > 1 {
+				let mut poll_file_descriptor = libc::pollfd {
+					fd: file.as_raw_fd(),
+					events: libc::POLLIN,
+					revents: 0,
+				};
+				if unsafe {
+					libc::poll(&mut poll_file_descriptor, 1, FIFO_POLL_TIMEOUT_MILLISECONDS)
+				} < 0
+				{
+					let error = std::io::Error::last_os_error();
+					if error.kind() == std::io::ErrorKind::Interrupted {
+						continue;
+					}
+					return Err(failed(error));
+				}
+				if poll_file_descriptor.revents & libc::POLLIN != 0 {
+					// Reading goes back to blocking now that samples are coming, so that the stream waits for the
+					// rest of them.
+					let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+					if flags < 0
+						|| unsafe {
+							libc::fcntl(file.as_raw_fd(), libc::F_SETFL, flags & !libc::O_NONBLOCK)
+						} < 0
+					{
+						return Err(failed(std::io::Error::last_os_error()));
+					}
+					return Ok(Some(file));
+				}
+				// A program that stopped writing before it wrote anything leaves the FIFO hung up until it's opened
+				// again.
+				if poll_file_descriptor.revents & libc::POLLHUP != 0 {
+					continue 'open;
+				}
+			}
+		}
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{AudioFeatures, AudioInput};
+
+	/**
+	 * Make a FIFO at a temporary path, returning the path so that the test can remove it.
+	 */
+	fn make_fifo(name: &str) -> std::path::PathBuf {
+		let path = std::env::temp_dir().join(format!("audio-{}-{}", std::process::id(), name));
+		let _ = std::fs::remove_file(&path);
+		let c_path =
+			std::ffi::CString::new(std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()))
+				.unwrap();
+		assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+		path
+	}
+
+	#[test]
+	fn stops_waiting_for_a_fifo_once_the_input_is_dropped() {
+		let path = make_fifo("dropped");
+		let features = std::sync::Arc::new(std::sync::Mutex::new(AudioFeatures::default()));
+		let waiting = {
+			let path = path.clone();
+			let features = features.clone();
+			std::thread::spawn(move || {
+				AudioInput::wait_for_fifo(&path, &features).map(|file| file.is_some())
+			})
+		};
+		std::thread::sleep(std::time::Duration::from_millis(100));
+		drop(features);
+		let result = waiting.join().unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(result, Ok(false));
+	}
+
+	#[test]
+	fn waits_for_samples_to_arrive_in_a_fifo() {
+		use std::io::{Read, Write};
+
+		let path = make_fifo("samples");
+		let features = std::sync::Arc::new(std::sync::Mutex::new(AudioFeatures::default()));
+		let waiting = {
+			let path = path.clone();
+			let features = features.clone();
+			std::thread::spawn(move || AudioInput::wait_for_fifo(&path, &features))
+		};
+		std::thread::sleep(std::time::Duration::from_millis(100));
+		let mut writer = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+		writer.write_all(&[1, 2]).unwrap();
+		let mut file = waiting.join().unwrap().unwrap().unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		// The rest of the samples are waited for rather than cut short by the FIFO having been opened without blocking.
+		let finishing = std::thread::spawn(move || {
+			std::thread::sleep(std::time::Duration::from_millis(100));
+			writer.write_all(&[3, 4]).unwrap();
+		});
+		let mut bytes = [0; 4];
+		file.read_exact(&mut bytes).unwrap();
+		finishing.join().unwrap();
+		assert_eq!(bytes, [1, 2, 3, 4]);
+	}
+}
@@ -0,0 +1,344 @@
+use std::io::Read;
+
+/**
+ * How a stream of PCM samples is laid out.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct PcmFormat {
+	pub sample_format: crate::config::AudioSampleFormat,
+	pub sample_rate: u32,
+	pub channel_count: u16,
+}
+
+impl PcmFormat {
+	fn bytes_per_frame(&self) -> usize {
+		let bytes_per_sample = match self.sample_format {
+			crate::config::AudioSampleFormat::S16Le => 2,
+			crate::config::AudioSampleFormat::F32Le => 4,
+		};
+		bytes_per_sample * self.channel_count.max(1) as usize
+	}
+}
+
+/**
+ * A source of PCM samples, which is either raw samples in the configured format or a WAV file whose header gives its
+ * format.
+ */
+pub struct PcmStream {
+	reader: Box<dyn Read + Send>,
+	pub format: PcmFormat,
+	frame_bytes: Vec<u8>,
+}
+
+impl PcmStream {
+	/**
+	 * Open a FIFO or a file of samples. Anything that doesn't start with a WAV header is read as raw samples in the
+	 * given format.
+	 */
+	pub fn open(path: &std::path::Path, raw_format: PcmFormat) -> Result<Self, String> {
+		let file = std::fs::File::open(path)
+			.map_err(|error| format!("Could not open {}: {}", path.display(), error))?;
+		Self::from_file(file, path, raw_format)
+	}
+
+	/**
+	 * Start reading from a FIFO or a file that is already open, whose path is only used to explain what went wrong.
+	 */
+	pub fn from_file(
+		file: std::fs::File,
+		path: &std::path::Path,
+		raw_format: PcmFormat,
+	) -> Result<Self, String> {
+		let mut reader = std::io::BufReader::new(file);
+
+		// The start of the stream is read to look for a WAV header. Raw samples can't be sought back over since they
+		// might be coming from a FIFO, so when there's no header, the bytes that were read are put back in front.
+		let mut header = [0; 12];
+		let header_length = read_up_to(&mut reader, &mut header)
+			.map_err(|error| format!("Could not read {}: {}", path.display(), error))?;
+		let is_wav =
+			header_length == header.len() && &header[0..4] == b"RIFF" && &header[8..12] == b"WAVE";
+		let (reader, format): (Box<dyn Read + Send>, PcmFormat) = if is_wav {
+			let format = read_wav_chunks(&mut reader).map_err(|error| {
+				format!("Could not read the WAV file {}: {}", path.display(), error)
+			})?;
+			(Box::new(reader), format)
+		} else {
+			(
+				Box::new(std::io::Cursor::new(header[..header_length].to_vec()).chain(reader)),
+				raw_format,
+			)
+		};
+
+		Ok(Self {
+			reader,
+			format,
+			frame_bytes: Vec::new(),
+		})
+	}
+
+	/**
+	 * Read up to the given number of frames, mixing each one down into a single sample from -1 to 1. Returns fewer
+	 * samples than were asked for only at the end of the stream.
+	 */
+	pub fn read_mono_samples(&mut self, frame_count: usize) -> std::io::Result<Vec<f32>> {
+		let bytes_per_frame = self.format.bytes_per_frame();
+		self.frame_bytes.resize(frame_count * bytes_per_frame, 0);
+		let byte_count = read_up_to(&mut self.reader, &mut self.frame_bytes)?;
+		let channel_count = self.format.channel_count.max(1) as usize;
+		Ok(
+			self.frame_bytes[..byte_count - byte_count % bytes_per_frame]
+				.chunks_exact(bytes_per_frame)
+				.map(|frame| {
+					let sum = match self.format.sample_format {
+						crate::config::AudioSampleFormat::S16Le => frame
+							.chunks_exact(2)
+							.map(|sample| {
+								i16::from_le_bytes([sample[0], sample[1]]) as f32 / 32768.0
+							})
+							.sum::<f32>(),
+						crate::config::AudioSampleFormat::F32Le => frame
+							.chunks_exact(4)
+							.map(|sample| {
+								f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]])
+							})
+							.sum::<f32>(),
+					};
+					sum / channel_count as f32
+				})
+				.collect(),
+		)
+	}
+}
+
+/**
+ * Fill as much of the buffer as possible, stopping early only at the end of the stream.
+ */
+fn read_up_to(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+	let mut length = 0;
+	while length < buffer.len() {
+		match reader.read(&mut buffer[length..]) {
+			Ok(0) => break,
+			Ok(read_length) => length += read_length,
+			Err(error) if error.kind() == std::io::ErrorKind::Interrupted => (),
+			Err(error) => return Err(error),
+		}
+	}
+	Ok(length)
+}
+
+/**
+ * Read the chunks of a WAV file that come after its RIFF header up to the start of its samples, returning the format
+ * of the samples.
+ */
+fn read_wav_chunks(reader: &mut impl Read) -> Result<PcmFormat, String> {
+	let mut format = None;
+	loop {
+		let mut chunk_header = [0; 8];
+		reader
+			.read_exact(&mut chunk_header)
+			.map_err(|error| format!("the file ended before its samples: {}", error))?;
+		let chunk_size = u32::from_le_bytes([
+			chunk_header[4],
+			chunk_header[5],
+			chunk_header[6],
+			chunk_header[7],
+		]) as usize;
+		match &chunk_header[0..4] {
+			b"fmt " => {
+				let mut chunk = vec![0; chunk_size + chunk_size % 2];
+				reader
+					.read_exact(&mut chunk)
+					.map_err(|error| error.to_string())?;
+				if chunk.len() < 16 {
+					return Err("its format chunk is too short".to_owned());
+				}
+				let format_tag = u16::from_le_bytes([chunk[0], chunk[1]]);
+				let channel_count = u16::from_le_bytes([chunk[2], chunk[3]]);
+				let sample_rate = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+				let bits_per_sample = u16::from_le_bytes([chunk[14], chunk[15]]);
+				// Extensible WAV files keep their actual format tag further into the chunk, so the sample size is what
+				// tells integers and floats apart for them.
+				let sample_format = match (format_tag, bits_per_sample) {
+					(1 | 0xFFFE, 16) => crate::config::AudioSampleFormat::S16Le,
+					(3 | 0xFFFE, 32) => crate::config::AudioSampleFormat::F32Le,
+					_ => {
+						return Err(format!(
+							"its samples are in format {} with {} bits, but only 16 bit integer and 32 bit float samples \
+							 are supported",
+							format_tag, bits_per_sample
+						))
+					}
+				};
+				format = Some(PcmFormat {
+					sample_format,
+					sample_rate,
+					channel_count,
+				});
+			}
+			b"data" => {
+				return format.ok_or_else(|| "its samples come before its format chunk".to_owned());
+			}
+			_ => {
+				// Chunks are padded to an even number of bytes.
+				let skipped_length = (chunk_size + chunk_size % 2) as u64;
+				let copied_length =
+					std::io::copy(&mut reader.take(skipped_length), &mut std::io::sink())
+						.map_err(|error| error.to_string())?;
+				if copied_length < skipped_length {
+					return Err("the file ended before its samples".to_owned());
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{PcmFormat, PcmStream};
+
+	const SAMPLE_RATE: u32 = 44100;
+	const FRAME_COUNT: usize = 4410;
+	const AMPLITUDE: f32 = 0.5;
+
+	fn sine_sample(frame_index: usize) -> f32 {
+		AMPLITUDE
+			* (2.0 * std::f32::consts::PI * 440.0 * frame_index as f32 / SAMPLE_RATE as f32).sin()
+	}
+
+	fn raw_format() -> PcmFormat {
+		PcmFormat {
+			sample_format: crate::config::AudioSampleFormat::F32Le,
+			sample_rate: 8000,
+			channel_count: 1,
+		}
+	}
+
+	/**
+	 * Write a file of samples to a temporary path, returning the path so that the test can remove it.
+	 */
+	fn write_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+		let path = std::env::temp_dir().join(format!("pcm-{}-{}", std::process::id(), name));
+		std::fs::write(&path, contents).unwrap();
+		path
+	}
+
+	/**
+	 * Build a WAV file out of a format chunk and the sample bytes, with a list chunk in between that has to be skipped.
+	 */
+	fn wav_file(
+		format_tag: u16,
+		channel_count: u16,
+		bits_per_sample: u16,
+		samples: &[u8],
+	) -> Vec<u8> {
+		let block_align = channel_count * bits_per_sample / 8;
+		let mut format_chunk = Vec::new();
+		format_chunk.extend_from_slice(&format_tag.to_le_bytes());
+		format_chunk.extend_from_slice(&channel_count.to_le_bytes());
+		format_chunk.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+		format_chunk.extend_from_slice(&(SAMPLE_RATE * block_align as u32).to_le_bytes());
+		format_chunk.extend_from_slice(&block_align.to_le_bytes());
+		format_chunk.extend_from_slice(&bits_per_sample.to_le_bytes());
+
+		let mut chunks = Vec::new();
+		for (chunk_id, chunk) in [
+			(b"fmt ", &format_chunk[..]),
+			(b"LIST", &b"INFOodd"[..]),
+			(b"data", samples),
+		] {
+			chunks.extend_from_slice(chunk_id);
+			chunks.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+			chunks.extend_from_slice(chunk);
+			if chunk.len() % 2 == 1 && chunk_id != b"data" {
+				chunks.push(0);
+			}
+		}
+		let mut file = Vec::new();
+		file.extend_from_slice(b"RIFF");
+		file.extend_from_slice(&(4 + chunks.len() as u32).to_le_bytes());
+		file.extend_from_slice(b"WAVE");
+		file.extend_from_slice(&chunks);
+		file
+	}
+
+	fn assert_sine(samples: &[f32], tolerance: f32) {
+		assert_eq!(samples.len(), FRAME_COUNT);
+		for (frame_index, sample) in samples.iter().enumerate() {
+			assert!((sample - sine_sample(frame_index)).abs() < tolerance);
+		}
+		let peak = samples
+			.iter()
+			.fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+		assert!((peak - AMPLITUDE).abs() < 1e-3);
+	}
+
+	#[test]
+	fn reads_16_bit_wav_files() {
+		let samples = (0..FRAME_COUNT)
+			.flat_map(|frame_index| {
+				((sine_sample(frame_index) * 32767.0).round() as i16).to_le_bytes()
+			})
+			.collect::<Vec<_>>();
+		let path = write_file("16-bit.wav", &wav_file(1, 1, 16, &samples));
+		let mut pcm_stream = PcmStream::open(&path, raw_format()).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		assert!(matches!(
+			pcm_stream.format.sample_format,
+			crate::config::AudioSampleFormat::S16Le
+		));
+		assert_eq!(pcm_stream.format.sample_rate, SAMPLE_RATE);
+		assert_eq!(pcm_stream.format.channel_count, 1);
+		assert_sine(&pcm_stream.read_mono_samples(FRAME_COUNT).unwrap(), 1e-4);
+		assert!(pcm_stream.read_mono_samples(1).unwrap().is_empty());
+	}
+
+	#[test]
+	fn reads_float_wav_files_mixed_down_to_mono() {
+		// The right channel is silent, so mixing the channels down halves the sine.
+		let samples = (0..FRAME_COUNT)
+			.flat_map(|frame_index| {
+				let mut frame = Vec::new();
+				frame.extend_from_slice(&(2.0 * sine_sample(frame_index)).to_le_bytes());
+				frame.extend_from_slice(&0.0_f32.to_le_bytes());
+				frame
+			})
+			.collect::<Vec<_>>();
+		let path = write_file("float.wav", &wav_file(3, 2, 32, &samples));
+		let mut pcm_stream = PcmStream::open(&path, raw_format()).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		assert!(matches!(
+			pcm_stream.format.sample_format,
+			crate::config::AudioSampleFormat::F32Le
+		));
+		assert_eq!(pcm_stream.format.sample_rate, SAMPLE_RATE);
+		assert_eq!(pcm_stream.format.channel_count, 2);
+		assert_sine(&pcm_stream.read_mono_samples(FRAME_COUNT).unwrap(), 1e-6);
+	}
+
+	#[test]
+	fn reads_files_without_a_header_in_the_raw_format() {
+		let samples = (0..FRAME_COUNT)
+			.flat_map(|frame_index| sine_sample(frame_index).to_le_bytes())
+			.collect::<Vec<_>>();
+		let path = write_file("raw", &samples);
+		let mut pcm_stream = PcmStream::open(&path, raw_format()).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(pcm_stream.format.sample_rate, 8000);
+		// The bytes that were read while looking for a header are still read as samples.
+		let mut samples = pcm_stream.read_mono_samples(2).unwrap();
+		samples.extend(pcm_stream.read_mono_samples(FRAME_COUNT).unwrap());
+		assert_sine(&samples, 1e-6);
+	}
+
+	#[test]
+	fn rejects_unsupported_wav_formats() {
+		let path = write_file("8-bit.wav", &wav_file(1, 1, 8, &[128; 16]));
+		let result = PcmStream::open(&path, raw_format());
+		std::fs::remove_file(&path).unwrap();
+		assert!(result.is_err());
+	}
+}
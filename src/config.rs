@@ -0,0 +1,1303 @@
+use std::path::PathBuf;
+
+/**
+ * Settings that are read from the configuration file when the demo starts.
+ * Anything that is missing from the file keeps its default value.
+ */
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Config {
+	/**
+	 * The scene that is shown. Without one, the bouncing cube scene is shown, or the starfield scene in low power
+	 * mode.
+	 */
+	pub scene: Option<SceneKind>,
+	pub render: RenderConfig,
+	pub tonemapping: TonemappingConfig,
+	pub bloom: BloomConfig,
+	/**
+	 * The effects that are applied to every frame after it has been tonemapped, in order.
+	 */
+	pub post_processing: Vec<PostProcessPassConfig>,
+	pub shadertoy: ShadertoyConfig,
+	pub particles: ParticlesConfig,
+	pub game_of_life: GameOfLifeConfig,
+	pub fluid: FluidConfig,
+	pub boids: BoidsConfig,
+	pub raymarching: RaymarchingConfig,
+	pub fractal: FractalConfig,
+	pub terrain: TerrainConfig,
+	pub starfield: StarfieldConfig,
+	pub slideshow: SlideshowConfig,
+	pub clock: ClockConfig,
+	pub system_stats: SystemStatsConfig,
+	pub audio: AudioConfig,
+	pub control: ControlConfig,
+	pub instance: InstanceConfig,
+	pub state: StateConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SceneKind {
+	#[default]
+	BouncingCube,
+	HelloWorldTriangle,
+	Shadertoy,
+	Particles,
+	GameOfLife,
+	Fluid,
+	Boids,
+	Raymarching,
+	Fractal,
+	Terrain,
+	Starfield,
+	Slideshow,
+}
+
+impl SceneKind {
+	/**
+	 * Every scene, in the order that they are cycled through.
+	 */
+	pub const ALL: [Self; 12] = [
+		Self::BouncingCube,
+		Self::HelloWorldTriangle,
+		Self::Shadertoy,
+		Self::Particles,
+		Self::GameOfLife,
+		Self::Fluid,
+		Self::Boids,
+		Self::Raymarching,
+		Self::Fractal,
+		Self::Terrain,
+		Self::Starfield,
+		Self::Slideshow,
+	];
+
+	/**
+	 * Get the scene with the name that it has in the configuration file, such as `bouncing_cube`.
+	 */
+	pub fn from_name(name: &str) -> Result<Self, String> {
+		use serde::de::IntoDeserializer;
+		<Self as serde::Deserialize>::deserialize(name.into_deserializer())
+			.map_err(|error: serde::de::value::Error| error.to_string())
+	}
+
+	/**
+	 * Get the scene that comes after this one, going back to the first scene after the last one.
+	 */
+	pub fn next(self) -> Self {
+		let index = Self::ALL
+			.iter()
+			.position(|scene_kind| *scene_kind == self)
+			.unwrap_or(0);
+		Self::ALL[(index + 1) % Self::ALL.len()]
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RenderConfig {
+	/**
+	 * The number of samples per pixel used when rendering scenes: one of 1, 2, 4 or 8.
+	 * If the adapter doesn't support this many samples, fewer are used instead.
+	 */
+	pub sample_count: u32,
+	/**
+	 * Whether to save power by asking for a low power adapter and drawing frames no faster than the low power frame
+	 * rate.
+	 */
+	pub low_power: bool,
+	/**
+	 * The most frames per second that are drawn in low power mode.
+	 */
+	pub low_power_frame_rate: f32,
+	/**
+	 * Whether screenshots are copied straight out of the window's surface rather than drawn into a texture of their
+	 * own as well. This saves drawing captured frames twice, but there's no way to check beforehand whether the surface
+	 * can be copied from, and the background fails to start if it can't, so it's only worth turning on for backends
+	 * such as Vulkan that allow it. It only takes effect when the background starts.
+	 */
+	pub copy_surface: bool,
+}
+
+impl Default for RenderConfig {
+	fn default() -> Self {
+		Self {
+			sample_count: 4,
+			low_power: false,
+			low_power_frame_rate: 10.0,
+			copy_surface: false,
+		}
+	}
+}
+
+/**
+ * The curves that can map high dynamic range colors into the displayable range.
+ */
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TonemappingOperator {
+	Reinhard,
+	Aces,
+	Agx,
+}
+
+#[derive(Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct TonemappingConfig {
+	pub operator: TonemappingOperator,
+	/**
+	 * The amount that scene colors are scaled by before they are tonemapped.
+	 */
+	pub exposure: f32,
+}
+
+impl Default for TonemappingConfig {
+	fn default() -> Self {
+		Self {
+			operator: TonemappingOperator::Aces,
+			exposure: 1.0,
+		}
+	}
+}
+
+#[derive(Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct BloomConfig {
+	/**
+	 * Whether scenes that support bloom get it.
+	 */
+	pub enabled: bool,
+	/**
+	 * How bright a color has to be before it starts to bloom. With no threshold everything blooms a little, in
+	 * proportion to how bright it is.
+	 */
+	pub threshold: f32,
+	/**
+	 * How strongly the bloom is added on top of the scene.
+	 */
+	pub intensity: f32,
+	/**
+	 * How far each upsampling step spreads the bloom, in texture coordinates.
+	 */
+	pub radius: f32,
+}
+
+impl Default for BloomConfig {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			threshold: 0.0,
+			intensity: 0.04,
+			radius: 0.005,
+		}
+	}
+}
+
+/**
+ * A single effect in the post-processing chain, written as a [[post_processing]] table whose `effect` key names the
+ * effect and whose other keys configure it.
+ */
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "effect", rename_all = "snake_case")]
+pub enum PostProcessPassConfig {
+	Vignette(VignetteConfig),
+	ColorGrading(ColorGradingConfig),
+	FilmGrain(FilmGrainConfig),
+	ChromaticAberration(ChromaticAberrationConfig),
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct VignetteConfig {
+	/**
+	 * How dark the edges of the screen get, from 0 for not at all to 1 for completely black.
+	 */
+	pub intensity: f32,
+	/**
+	 * How far from the center of the screen the vignette is fully dark, where the screen height is 1.
+	 */
+	pub radius: f32,
+	/**
+	 * How far inside of the radius the vignette starts to fade in.
+	 */
+	pub softness: f32,
+}
+
+impl Default for VignetteConfig {
+	fn default() -> Self {
+		Self {
+			intensity: 0.5,
+			radius: 0.9,
+			softness: 0.5,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ColorGradingConfig {
+	/**
+	 * The path of the .cube file holding the 3D lookup table to grade with.
+	 */
+	pub lookup_table: PathBuf,
+	/**
+	 * How much of the graded color is mixed into the original color.
+	 */
+	pub intensity: f32,
+}
+
+impl Default for ColorGradingConfig {
+	fn default() -> Self {
+		Self {
+			lookup_table: PathBuf::new(),
+			intensity: 1.0,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FilmGrainConfig {
+	pub intensity: f32,
+	/**
+	 * The width and height of each grain in pixels.
+	 */
+	pub grain_size: f32,
+	/**
+	 * How many times per second the grain pattern changes.
+	 */
+	pub frames_per_second: f32,
+}
+
+impl Default for FilmGrainConfig {
+	fn default() -> Self {
+		Self {
+			intensity: 0.05,
+			grain_size: 1.5,
+			frames_per_second: 24.0,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ChromaticAberrationConfig {
+	/**
+	 * How far apart the red and blue channels are pulled at the corners of the screen, in texture coordinates.
+	 */
+	pub strength: f32,
+}
+
+impl Default for ChromaticAberrationConfig {
+	fn default() -> Self {
+		Self { strength: 0.005 }
+	}
+}
+
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ShadertoyConfig {
+	/**
+	 * The path of the shader file defining the mainImage function to run, in WGSL, or in GLSL like on Shadertoy when
+	 * the file ends in .frag or .glsl. Without one, a default shader is shown.
+	 */
+	pub shader: Option<PathBuf>,
+	/**
+	 * What iChannel0 through iChannel3 read from, in order. Channels that aren't listed are black.
+	 */
+	pub channels: Vec<ShadertoyChannelConfig>,
+	/**
+	 * How image channels are sampled outside of their edges and at glancing angles.
+	 */
+	pub channel_sampler: ShadertoyChannelSampler,
+	/**
+	 * Whether image channels are decoded from sRGB into linear colors when they are sampled. Shadertoy itself leaves
+	 * them as they are stored. Radiance HDR and OpenEXR images are always linear.
+	 */
+	pub srgb_channels: bool,
+}
+
+/**
+ * The input bound to a Shadertoy channel, written as "none", "previous_frame", or { image = "path" }.
+ */
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShadertoyChannelConfig {
+	None,
+	Image(PathBuf),
+	/**
+	 * The frame that the shader rendered last, which lets shaders carry state between frames.
+	 */
+	PreviousFrame,
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShadertoyChannelSampler {
+	#[default]
+	Repeat,
+	Clamp,
+	/**
+	 * Repeat, and filter anisotropically so that images stay sharp when they are seen at glancing angles.
+	 */
+	Anisotropic,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ParticlesConfig {
+	pub particle_count: u32,
+	/**
+	 * The width and height of each particle's sprite in pixels.
+	 */
+	pub particle_size: f32,
+	/**
+	 * How bright each particle is. Overlapping particles add up, so this should be lower with more particles.
+	 */
+	pub brightness: f32,
+	/**
+	 * How quickly particles lose their speed, as the fraction of it that is lost per second.
+	 */
+	pub damping: f32,
+	/**
+	 * The points that pull particles towards them, of which there can be up to 8.
+	 */
+	pub attractors: Vec<AttractorConfig>,
+	/**
+	 * Whether there is an extra attractor at the cursor while it's over the window.
+	 */
+	pub follow_cursor: bool,
+	pub cursor_strength: f32,
+}
+
+impl Default for ParticlesConfig {
+	fn default() -> Self {
+		Self {
+			particle_count: 200_000,
+			particle_size: 3.0,
+			brightness: 0.1,
+			damping: 0.1,
+			attractors: vec![
+				AttractorConfig {
+					position: [-0.6, 0.0],
+					strength: 0.5,
+				},
+				AttractorConfig {
+					position: [0.6, 0.0],
+					strength: 0.5,
+				},
+			],
+			follow_cursor: false,
+			cursor_strength: 1.0,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct AttractorConfig {
+	/**
+	 * Where the attractor is, where the screen spans from -1 at the bottom to 1 at the top and is centered
+	 * horizontally on 0.
+	 */
+	pub position: [f32; 2],
+	/**
+	 * How strongly particles are pulled in. Negative strengths push particles away instead.
+	 */
+	pub strength: f32,
+}
+
+impl Default for AttractorConfig {
+	fn default() -> Self {
+		Self {
+			position: [0.0, 0.0],
+			strength: 1.0,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct GameOfLifeConfig {
+	/**
+	 * The rule in B/S notation, such as "B36/S23" for HighLife. Without one, the pattern's rule is used if it has one,
+	 * and otherwise Conway's Game of Life (B3/S23) is.
+	 */
+	pub rule: Option<String>,
+	/**
+	 * The number of cells across and down the grid, which wraps around at its edges.
+	 */
+	pub grid_resolution: [u32; 2],
+	pub ticks_per_second: f32,
+	/**
+	 * The path of an .rle file holding the pattern that gets placed in the middle of the grid. Without one, the grid
+	 * is seeded with random noise. Either way, the grid is seeded again with random noise whenever it stops changing.
+	 */
+	pub pattern: Option<PathBuf>,
+	/**
+	 * The fraction of cells that start out alive when seeding with random noise.
+	 */
+	pub random_density: f32,
+}
+
+impl Default for GameOfLifeConfig {
+	fn default() -> Self {
+		Self {
+			rule: None,
+			grid_resolution: [256, 144],
+			ticks_per_second: 15.0,
+			pattern: None,
+			random_density: 0.3,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FluidConfig {
+	/**
+	 * The number of cells across and down the simulation grid. This doesn't depend on the size of the screen, so
+	 * smaller grids can be used to save power.
+	 */
+	pub grid_resolution: [u32; 2],
+	/**
+	 * The number of emitters that drift around adding dye, of which there can be up to 8.
+	 */
+	pub emitter_count: u32,
+	/**
+	 * How fast emitters drift, in grid heights per second.
+	 */
+	pub drift_speed: f32,
+	/**
+	 * How hard emitters push the fluid, in grid heights per second squared.
+	 */
+	pub emitter_force: f32,
+	/**
+	 * How far the push and dye of an emitter reaches, as a fraction of the grid's height. It never reaches less than a
+	 * cell.
+	 */
+	pub emitter_radius: f32,
+	/**
+	 * How much dye emitters add per second.
+	 */
+	pub dye_intensity: f32,
+	/**
+	 * How quickly the fluid's velocity spreads into neighboring cells, in cells squared per second.
+	 */
+	pub viscosity: f32,
+	/**
+	 * How quickly dye spreads into neighboring cells, in cells squared per second.
+	 */
+	pub dye_diffusion: f32,
+	/**
+	 * The fraction of the fluid's velocity that is lost per second.
+	 */
+	pub velocity_dissipation: f32,
+	/**
+	 * The fraction of the dye that fades away per second.
+	 */
+	pub dye_dissipation: f32,
+	/**
+	 * The number of Jacobi iterations used to solve for pressure, where more iterations make the fluid swirl more
+	 * convincingly at the cost of speed.
+	 */
+	pub pressure_iteration_count: u32,
+	/**
+	 * Whether the cursor stirs the fluid and adds dye while it's over the window.
+	 */
+	pub follow_cursor: bool,
+}
+
+impl Default for FluidConfig {
+	fn default() -> Self {
+		Self {
+			grid_resolution: [256, 144],
+			emitter_count: 3,
+			drift_speed: 0.15,
+			emitter_force: 0.5,
+			emitter_radius: 0.03,
+			dye_intensity: 1.5,
+			viscosity: 0.1,
+			dye_diffusion: 0.0,
+			velocity_dissipation: 0.2,
+			dye_dissipation: 0.4,
+			pressure_iteration_count: 40,
+			follow_cursor: false,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct BoidsConfig {
+	pub boid_count: u32,
+	/**
+	 * How strongly boids steer away from neighbors that are closer than the separation distance.
+	 */
+	pub separation_weight: f32,
+	/**
+	 * How strongly boids steer towards the average heading of their neighbors.
+	 */
+	pub alignment_weight: f32,
+	/**
+	 * How strongly boids steer towards the center of their neighbors.
+	 */
+	pub cohesion_weight: f32,
+	/**
+	 * How far away other boids can be while still counting as neighbors, where the room spans from -1 to 1 vertically.
+	 */
+	pub perception_radius: f32,
+	/**
+	 * How close neighbors have to be before boids steer away from them.
+	 */
+	pub separation_distance: f32,
+	pub minimum_speed: f32,
+	pub maximum_speed: f32,
+	/**
+	 * The length of each boid from its tail to its tip.
+	 */
+	pub boid_size: f32,
+}
+
+impl Default for BoidsConfig {
+	fn default() -> Self {
+		Self {
+			boid_count: 4096,
+			separation_weight: 1.5,
+			alignment_weight: 1.0,
+			cohesion_weight: 1.0,
+			perception_radius: 0.15,
+			separation_distance: 0.05,
+			minimum_speed: 0.2,
+			maximum_speed: 0.6,
+			boid_size: 0.03,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RaymarchingConfig {
+	/**
+	 * The path of a TOML file describing the primitives to draw. Without one, a default composition is shown.
+	 */
+	pub composition: Option<PathBuf>,
+	/**
+	 * The vertical field of view of the camera in degrees.
+	 */
+	pub field_of_view: f32,
+	/**
+	 * How far the camera orbits from the origin horizontally.
+	 */
+	pub camera_distance: f32,
+	pub camera_height: f32,
+	/**
+	 * How fast the camera orbits around the origin, in degrees per second.
+	 */
+	pub orbit_speed: f32,
+	/**
+	 * The direction pointing towards the sun, where y is up.
+	 */
+	pub sun_direction: [f32; 3],
+	/**
+	 * How sharp the edges of shadows are, where smaller values give softer shadows.
+	 */
+	pub shadow_sharpness: f32,
+	/**
+	 * The most steps that a ray takes before giving up on hitting anything, which trades detail for speed.
+	 */
+	pub maximum_step_count: u32,
+}
+
+impl Default for RaymarchingConfig {
+	fn default() -> Self {
+		Self {
+			composition: None,
+			field_of_view: 60.0,
+			camera_distance: 4.5,
+			camera_height: 1.2,
+			orbit_speed: 6.0,
+			sun_direction: [0.6, 0.8, -0.4],
+			shadow_sharpness: 12.0,
+			maximum_step_count: 128,
+		}
+	}
+}
+
+/**
+ * The fractals that the fractal scene can zoom into.
+ */
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FractalKind {
+	Mandelbrot,
+	Julia,
+}
+
+/**
+ * The color schemes that escaping points can be colored with.
+ */
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FractalPalette {
+	Rainbow,
+	Sunset,
+	Ocean,
+	Ember,
+	Grayscale,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FractalConfig {
+	pub kind: FractalKind,
+	/**
+	 * The constant c of the Julia set for z² + c, as its real and imaginary parts.
+	 */
+	pub julia_constant: [f64; 2],
+	/**
+	 * The points that are zoomed into one after another, as their real and imaginary parts. Points on the boundary of
+	 * the set stay interesting the deepest. Without any, built in targets for the kind of fractal are used.
+	 */
+	pub targets: Vec<[f64; 2]>,
+	/**
+	 * How fast the view zooms in, where the view shrinks by a factor of e every 1 / zoom_speed seconds.
+	 */
+	pub zoom_speed: f32,
+	/**
+	 * The palettes that are cycled through, switching to the next one with every target.
+	 */
+	pub palettes: Vec<FractalPalette>,
+	/**
+	 * How fast the colors shift through the palette, in palette lengths per second.
+	 */
+	pub color_cycle_speed: f32,
+	/**
+	 * The number of iterations used at the start of a zoom, which grows by the iterations per zoom depth every time
+	 * the view shrinks by a factor of e, up to the maximum iteration count.
+	 */
+	pub initial_iteration_count: u32,
+	pub iterations_per_zoom_depth: f32,
+	pub maximum_iteration_count: u32,
+}
+
+impl Default for FractalConfig {
+	fn default() -> Self {
+		Self {
+			kind: FractalKind::Mandelbrot,
+			julia_constant: [-0.8, 0.156],
+			targets: Vec::new(),
+			zoom_speed: 0.25,
+			palettes: vec![
+				FractalPalette::Sunset,
+				FractalPalette::Ocean,
+				FractalPalette::Rainbow,
+				FractalPalette::Ember,
+			],
+			color_cycle_speed: 0.05,
+			initial_iteration_count: 200,
+			iterations_per_zoom_depth: 40.0,
+			maximum_iteration_count: 2000,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct TerrainConfig {
+	/**
+	 * The width of a square chunk of terrain, and the distance between the points that the flight path curves through.
+	 */
+	pub chunk_size: f32,
+	/**
+	 * The number of squares along each side of a chunk's grid of triangles.
+	 */
+	pub chunk_resolution: u32,
+	/**
+	 * How many chunks away from the camera's chunk the terrain is kept loaded in every direction. The fog hides the
+	 * terrain by the time it reaches this far.
+	 */
+	pub view_distance: u32,
+	/**
+	 * The most chunks that are generated in a single frame, which keeps streaming from causing stutters.
+	 */
+	pub chunks_generated_per_frame: u32,
+	/**
+	 * The size of the largest hills in the noise that the terrain is made from.
+	 */
+	pub feature_size: f32,
+	pub octave_count: u32,
+	/**
+	 * The height of the highest possible peaks.
+	 */
+	pub height_scale: f32,
+	/**
+	 * How high above the terrain the camera flies.
+	 */
+	pub flight_altitude: f32,
+	pub flight_speed: f32,
+	/**
+	 * The direction pointing towards the sun.
+	 */
+	pub sun_direction: [f32; 3],
+}
+
+impl Default for TerrainConfig {
+	fn default() -> Self {
+		Self {
+			chunk_size: 32.0,
+			chunk_resolution: 64,
+			view_distance: 6,
+			chunks_generated_per_frame: 4,
+			feature_size: 96.0,
+			octave_count: 6,
+			height_scale: 24.0,
+			flight_altitude: 12.0,
+			flight_speed: 12.0,
+			sun_direction: [0.4, 0.6, 0.3],
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct StarfieldConfig {
+	pub star_count: u32,
+	/**
+	 * The number of layers that the stars are spread across. Nearer layers have bigger and brighter stars that drift
+	 * past faster than those in farther layers.
+	 */
+	pub layer_count: u32,
+	/**
+	 * The colors that stars are randomly given.
+	 */
+	pub star_colors: Vec<[f32; 3]>,
+	pub star_brightness: f32,
+	/**
+	 * The radius of the stars in the nearest layer, in pixels.
+	 */
+	pub star_size: f32,
+	/**
+	 * How fast the nearest layer drifts across the screen, in screen widths per second. Zero keeps the view still.
+	 */
+	pub drift_velocity: [f32; 2],
+	/**
+	 * The two colors that the nebula is blended from.
+	 */
+	pub nebula_colors: [[f32; 3]; 2],
+	pub nebula_brightness: f32,
+	/**
+	 * The average number of seconds between shooting stars. Zero turns them off.
+	 */
+	pub shooting_star_interval: f32,
+}
+
+impl Default for StarfieldConfig {
+	fn default() -> Self {
+		Self {
+			star_count: 2000,
+			layer_count: 3,
+			star_colors: vec![
+				[1.0, 1.0, 1.0],
+				[0.7, 0.8, 1.0],
+				[1.0, 0.9, 0.7],
+				[1.0, 0.7, 0.5],
+			],
+			star_brightness: 2.0,
+			star_size: 2.5,
+			drift_velocity: [0.004, 0.001],
+			nebula_colors: [[0.35, 0.1, 0.5], [0.05, 0.3, 0.45]],
+			nebula_brightness: 0.4,
+			shooting_star_interval: 12.0,
+		}
+	}
+}
+
+/**
+ * How images whose aspect ratio differs from the screen's are shown.
+ */
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlideshowFitMode {
+	/**
+	 * Cover the whole screen, cropping the image.
+	 */
+	Fill,
+	/**
+	 * Show the whole image, leaving black bars around it.
+	 */
+	Fit,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SlideshowConfig {
+	/**
+	 * The directory that JPEG, PNG and WebP images are shown from. Without one, the Pictures directory in the home
+	 * directory is used.
+	 */
+	pub directory: Option<PathBuf>,
+	pub fit_mode: SlideshowFitMode,
+	/**
+	 * How many seconds each image is shown for before the next one starts fading in.
+	 */
+	pub display_duration: f32,
+	pub crossfade_duration: f32,
+	/**
+	 * How far the view zooms in or out over the time an image is shown, as a fraction of the image's size.
+	 */
+	pub zoom_amount: f32,
+}
+
+impl Default for SlideshowConfig {
+	fn default() -> Self {
+		Self {
+			directory: None,
+			fit_mode: SlideshowFitMode::Fill,
+			display_duration: 10.0,
+			crossfade_duration: 2.0,
+			zoom_amount: 0.15,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ClockConfig {
+	/**
+	 * Whether the time and date are shown over the scene.
+	 */
+	pub enabled: bool,
+	/**
+	 * The strftime-style formats of the time and of the date shown under it. An empty format hides that line. The
+	 * clock only changes once a minute, so formats with seconds in them are only accurate to the minute.
+	 */
+	pub time_format: String,
+	pub date_format: String,
+	/**
+	 * The TTF or OTF font that the clock is drawn with. Without one, the system's default sans serif font is used.
+	 */
+	pub font: Option<PathBuf>,
+	/**
+	 * The point of the screen that the clock is placed at, which is also the point of the clock that is placed there.
+	 */
+	pub anchor: crate::scene::utilities::text::TextAnchor,
+	/**
+	 * How far the clock is moved in from its anchor towards the middle of the screen, in pixels.
+	 */
+	pub margin: [f32; 2],
+	/**
+	 * The heights of the time and date fonts in pixels.
+	 */
+	pub time_size: f32,
+	pub date_size: f32,
+	/**
+	 * The linear RGBA color of the text.
+	 */
+	pub color: [f32; 4],
+	/**
+	 * A copy of the text that is drawn behind it, moved by the shadow offset in pixels. A transparent shadow color hides
+	 * the shadow.
+	 */
+	pub shadow_color: [f32; 4],
+	pub shadow_offset: [f32; 2],
+	/**
+	 * How far the outline around the text reaches in pixels, where 0 leaves the text without an outline.
+	 */
+	pub outline_width: f32,
+	pub outline_color: [f32; 4],
+}
+
+impl Default for ClockConfig {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			time_format: "%H:%M".to_owned(),
+			date_format: "%A, %B %-d".to_owned(),
+			font: None,
+			anchor: crate::scene::utilities::text::TextAnchor::BottomRight,
+			margin: [48.0, 48.0],
+			time_size: 96.0,
+			date_size: 32.0,
+			color: [1.0, 1.0, 1.0, 0.9],
+			shadow_color: [0.0, 0.0, 0.0, 0.5],
+			shadow_offset: [3.0, 3.0],
+			outline_width: 0.0,
+			outline_color: [0.0, 0.0, 0.0, 1.0],
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SystemStatsConfig {
+	/**
+	 * Whether CPU, memory, network and disk statistics are shown over the scene.
+	 */
+	pub enabled: bool,
+	/**
+	 * Where the proc file system is read from, which can be pointed at a directory of fake files for testing.
+	 */
+	pub procfs_root: PathBuf,
+	/**
+	 * The paths of the file systems whose disk usage is shown.
+	 */
+	pub disk_paths: Vec<PathBuf>,
+	/**
+	 * How many seconds pass between each time the statistics are read, regardless of the frame rate.
+	 */
+	pub sampling_interval: f32,
+	/**
+	 * How many samples the graphs go back.
+	 */
+	pub history_length: u32,
+	/**
+	 * The TTF or OTF font that the statistics are drawn with. Without one, the system's default sans serif font is
+	 * used.
+	 */
+	pub font: Option<PathBuf>,
+	/**
+	 * The point of the screen that the panel is placed at, which is also the point of the panel that is placed there.
+	 */
+	pub anchor: crate::scene::utilities::text::TextAnchor,
+	/**
+	 * How far the panel is moved in from its anchor towards the middle of the screen, in pixels.
+	 */
+	pub margin: [f32; 2],
+	/**
+	 * The width of the panel and the height of its font, in pixels.
+	 */
+	pub width: f32,
+	pub text_size: f32,
+	/**
+	 * The linear RGBA colors of the text, the graphs and the panel behind them.
+	 */
+	pub text_color: [f32; 4],
+	pub graph_color: [f32; 4],
+	pub background_color: [f32; 4],
+}
+
+impl Default for SystemStatsConfig {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			procfs_root: PathBuf::from("/proc"),
+			disk_paths: vec![PathBuf::from("/")],
+			sampling_interval: 1.0,
+			history_length: 60,
+			font: None,
+			anchor: crate::scene::utilities::text::TextAnchor::TopLeft,
+			margin: [32.0, 32.0],
+			width: 400.0,
+			text_size: 16.0,
+			text_color: [1.0, 1.0, 1.0, 0.9],
+			graph_color: [0.3, 0.7, 1.0, 0.8],
+			background_color: [0.0, 0.0, 0.0, 0.4],
+		}
+	}
+}
+
+/**
+ * The layouts that raw PCM samples can be read in.
+ */
+#[derive(Clone, Copy, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioSampleFormat {
+	/**
+	 * Signed 16 bit little endian integers.
+	 */
+	S16Le,
+	/**
+	 * 32 bit little endian floats.
+	 */
+	F32Le,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct AudioConfig {
+	/**
+	 * The FIFO or file that samples are read from, which scenes can react to. Without one, there is no audio input.
+	 */
+	pub source: Option<PathBuf>,
+	/**
+	 * The layout of raw samples. WAV files are read in whatever layout their header gives instead.
+	 */
+	pub sample_format: AudioSampleFormat,
+	pub sample_rate: u32,
+	pub channel_count: u16,
+	/**
+	 * The number of samples that each spectrum is taken over, which is rounded up to a power of two. Bigger sizes tell
+	 * low frequencies apart better but react more slowly.
+	 */
+	pub fft_size: u32,
+	/**
+	 * How much of a frequency band's previous value is kept every hundredth of a second as it gets quieter, from 0 to
+	 * 1.
+	 */
+	pub smoothing: f32,
+	/**
+	 * How many times louder the bass has to get than its average over the last second to count as a beat.
+	 */
+	pub beat_sensitivity: f32,
+}
+
+impl Default for AudioConfig {
+	fn default() -> Self {
+		Self {
+			source: None,
+			sample_format: AudioSampleFormat::S16Le,
+			sample_rate: 44100,
+			channel_count: 2,
+			fft_size: 2048,
+			smoothing: 0.85,
+			beat_sensitivity: 1.5,
+		}
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ControlConfig {
+	/**
+	 * Whether to listen for commands from `custom_background ctl` on the control socket.
+	 */
+	pub enabled: bool,
+	/**
+	 * Where the control socket is made. Without one, it's custom_background.sock inside of $XDG_RUNTIME_DIR, which
+	 * only the user can get into.
+	 */
+	pub socket_path: Option<PathBuf>,
+	/**
+	 * The port that the HTTP API listens on at 127.0.0.1. Without one, there is no HTTP API.
+	 */
+	pub http_port: Option<u16>,
+}
+
+impl Default for ControlConfig {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			socket_path: None,
+			http_port: None,
+		}
+	}
+}
+
+impl ControlConfig {
+	/**
+	 * Get the path of the control socket, if there is anywhere to put it.
+	 */
+	pub fn socket_path(&self) -> Option<PathBuf> {
+		self.socket_path
+			.clone()
+			.or_else(|| runtime_path("custom_background.sock"))
+	}
+}
+
+/**
+ * What a second launch does when the background is already running.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceConflict {
+	/**
+	 * Leave the running background alone and exit. Arguments, such as in `custom_background next`, are forwarded to it
+	 * as a control command whatever this is set to.
+	 */
+	Forward,
+	/**
+	 * Shut the running background down and take its place.
+	 */
+	Replace,
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct InstanceConfig {
+	/**
+	 * What a second launch does, which `--replace` turns into replacing the running background.
+	 */
+	pub on_conflict: InstanceConflict,
+	/**
+	 * The file that is locked while the background runs so that only one runs at a time. Without one, it's
+	 * custom_background.lock inside of $XDG_RUNTIME_DIR.
+	 */
+	pub lock_path: Option<PathBuf>,
+}
+
+impl Default for InstanceConfig {
+	fn default() -> Self {
+		Self {
+			on_conflict: InstanceConflict::Forward,
+			lock_path: None,
+		}
+	}
+}
+
+impl InstanceConfig {
+	/**
+	 * Get the path of the lock file, if there is anywhere to put it.
+	 */
+	pub fn lock_path(&self) -> Option<PathBuf> {
+		self.lock_path
+			.clone()
+			.or_else(|| runtime_path("custom_background.lock"))
+	}
+}
+
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct StateConfig {
+	/**
+	 * Whether scenes carry on where they left off when the background restarts. The state file is only read when the
+	 * background starts, so this and the path can't change while it runs.
+	 */
+	pub enabled: bool,
+	/**
+	 * How many seconds pass between each time the state is saved while the background runs, in case it crashes. Zero
+	 * only saves it when the background shuts down.
+	 */
+	pub save_interval: f32,
+	/**
+	 * Where the state is saved. Without a path, it's custom_background/state.json inside of the XDG state directory.
+	 */
+	pub path: Option<PathBuf>,
+}
+
+impl Default for StateConfig {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			save_interval: 60.0,
+			path: None,
+		}
+	}
+}
+
+impl StateConfig {
+	/**
+	 * Get the path of the state file, if there is anywhere to put it.
+	 */
+	pub fn path(&self) -> Option<PathBuf> {
+		self.path.clone().or_else(|| {
+			std::env::var_os("XDG_STATE_HOME")
+				.map(PathBuf::from)
+				.filter(|state_directory| state_directory.is_absolute())
+				.or_else(|| {
+					std::env::var_os("HOME")
+						.map(|home| PathBuf::from(home).join(".local").join("state"))
+				})
+				.map(|state_directory| state_directory.join("custom_background").join("state.json"))
+		})
+	}
+}
+
+/**
+ * Get the path of a file inside of $XDG_RUNTIME_DIR, which only the user can get into.
+ */
+fn runtime_path(file_name: &str) -> Option<PathBuf> {
+	std::env::var_os("XDG_RUNTIME_DIR")
+		.map(PathBuf::from)
+		.filter(|runtime_directory| runtime_directory.is_absolute())
+		.map(|runtime_directory| runtime_directory.join(file_name))
+}
+
+impl Config {
+	/**
+	 * Get the path of the configuration file, which is custom_background/config.toml inside of the XDG config
+	 * directory.
+	 */
+	pub fn path() -> Option<PathBuf> {
+		std::env::var_os("XDG_CONFIG_HOME")
+			.map(PathBuf::from)
+			.filter(|config_directory| config_directory.is_absolute())
+			.or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+			.map(|config_directory| {
+				config_directory
+					.join("custom_background")
+					.join("config.toml")
+			})
+	}
+
+	/**
+	 * Get the scene that should be shown.
+	 */
+	pub fn scene_kind(&self) -> SceneKind {
+		self.scene.unwrap_or(if self.render.low_power {
+			SceneKind::Starfield
+		} else {
+			SceneKind::default()
+		})
+	}
+
+	/**
+	 * Load the configuration file. If there is no configuration file or it can't be read, the defaults are used.
+	 */
+	pub fn load() -> Self {
+		Self::load_with_overrides(&[]).unwrap_or_else(|error| {
+			log::warn!("{}, so the defaults are used", error);
+			Self::default()
+		})
+	}
+
+	/**
+	 * Load the configuration file with some of its values replaced. Each override is a dotted key, such as
+	 * `starfield.star_brightness`, along with the value that it is set to. Keys that don't exist are ignored just like
+	 * they are in the file. A missing configuration file counts as an empty one.
+	 */
+	pub fn load_with_overrides(overrides: &[(String, toml::Value)]) -> Result<Self, String> {
+		let mut table = match Self::path() {
+			Some(path) => match std::fs::read_to_string(&path) {
+				Ok(contents) => toml::from_str(&contents).map_err(|error| {
+					format!("The config file {} is invalid: {}", path.display(), error)
+				})?,
+				Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+					toml::value::Table::new()
+				}
+				Err(error) => {
+					return Err(format!(
+						"Could not read config file {}: {}",
+						path.display(),
+						error
+					))
+				}
+			},
+			None => toml::value::Table::new(),
+		};
+		for (key, value) in overrides {
+			set_value(&mut table, key, value.clone())?;
+		}
+		toml::Value::Table(table)
+			.try_into()
+			.map_err(|error| format!("The config is invalid: {}", error))
+	}
+}
+
+/**
+ * Set the value at a dotted key in a table, making any tables along the way that don't exist yet.
+ */
+fn set_value(table: &mut toml::value::Table, key: &str, value: toml::Value) -> Result<(), String> {
+	let (parent_keys, last_key) = match key.rsplit_once('.') {
+		Some((parent_keys, last_key)) => (Some(parent_keys), last_key),
+		None => (None, key),
+	};
+	let mut parent_table = table;
+	for parent_key in parent_keys
+		.into_iter()
+		.flat_map(|parent_keys| parent_keys.split('.'))
+	{
+		parent_table = parent_table
+			.entry(parent_key)
+			.or_insert_with(|| toml::Value::Table(toml::value::Table::new()))
+			.as_table_mut()
+			.ok_or_else(|| format!("{} is not a table in the config", parent_key))?;
+	}
+	if last_key.is_empty() {
+		return Err(format!("{:?} is not a valid config key", key));
+	}
+	parent_table.insert(last_key.to_owned(), value);
+	Ok(())
+}
@@ -0,0 +1,274 @@
+use std::io::{Read, Write};
+
+/**
+ * The most bytes that are read from the body of a request.
+ */
+const MAXIMUM_BODY_LENGTH: u64 = 64 * 1024;
+
+/**
+ * How many frame timings can wait to be sent to an event stream before newer ones are dropped.
+ */
+const FRAME_TIMING_BUFFER_LENGTH: usize = 256;
+
+/**
+ * Serves the same commands as the control socket over HTTP with JSON responses, bound to 127.0.0.1 so that only
+ * programs on this machine can reach it. The endpoints are:
+ *
+ * - `GET /status`: the state of the background
+ * - `GET /scenes`: the scenes that can be switched to and the current one
+ * - `PUT /scene`: switch to the scene named in the body
+ * - `POST /scene/next`: switch to the next scene
+ * - `POST /pause`, `POST /resume` and `POST /reload`
+ * - `GET /parameters/<key>`: the value in use for a dotted config key
+ * - `PUT /parameters/<key>`: set a config value to the body, written as it would be in the config file
+ * - `GET /frame.png`: the next frame as a PNG
+ * - `GET /events/frame-timings`: a stream of server-sent events with the timing of every frame
+ *
+ * Commands that have nothing to report answer with 204 No Content, and failed commands answer with an `error` in the
+ * body.
+ */
+pub struct ControlHttpServer {
+	server: std::sync::Arc<tiny_http::Server>,
+}
+
+impl ControlHttpServer {
+	pub fn new(port: u16, control_sender: crate::control::ControlSender) -> Result<Self, String> {
+		let server = std::sync::Arc::new(
+			tiny_http::Server::http(("127.0.0.1", port))
+				.map_err(|error| format!("could not listen on 127.0.0.1:{}: {}", port, error))?,
+		);
+		let thread_server = server.clone();
+		std::thread::Builder::new()
+			.name("control http".to_owned())
+			.spawn(move || {
+				for request in thread_server.incoming_requests() {
+					let control_sender = control_sender.clone();
+					// Event streams last as long as the client keeps them open, so every request gets its own thread.
+					let spawn_result = std::thread::Builder::new()
+						.name("control http request".to_owned())
+						.spawn(move || Self::handle_request(request, port, &control_sender));
+					if let Err(error) = spawn_result {
+						log::warn!("Could not handle an HTTP request: {}", error);
+					}
+				}
+			})
+			.map_err(|error| error.to_string())?;
+		Ok(Self { server })
+	}
+
+	fn handle_request(
+		mut request: tiny_http::Request,
+		port: u16,
+		control_sender: &crate::control::ControlSender,
+	) {
+		if !Self::is_request_allowed(&request, port) {
+			Self::respond(
+				request,
+				error_response(403, "Requests from web pages aren't allowed"),
+			);
+			return;
+		}
+		let path = request
+			.url()
+			.split('?')
+			.next()
+			.unwrap_or_default()
+			.to_owned();
+		let method = request.method().clone();
+		let response = match (&method, path.as_str()) {
+			(tiny_http::Method::Get, "/status") => {
+				command_response(control_sender.send(crate::control::ControlCommand::Status))
+			}
+			(tiny_http::Method::Get, "/scenes") => {
+				command_response(control_sender.send(crate::control::ControlCommand::ListScenes))
+			}
+			(tiny_http::Method::Put, "/scene") => {
+				// The name can be sent either as it is or as a JSON string.
+				let command = read_body(&mut request).and_then(|body| {
+					crate::config::SceneKind::from_name(body.trim().trim_matches('"'))
+						.map(crate::control::ControlCommand::SwitchScene)
+				});
+				match command {
+					Ok(command) => command_response(control_sender.send(command)),
+					Err(error) => error_response(400, &error),
+				}
+			}
+			(tiny_http::Method::Post, "/scene/next") => {
+				command_response(control_sender.send(crate::control::ControlCommand::NextScene))
+			}
+			(tiny_http::Method::Post, "/pause") => {
+				command_response(control_sender.send(crate::control::ControlCommand::Pause))
+			}
+			(tiny_http::Method::Post, "/resume") => {
+				command_response(control_sender.send(crate::control::ControlCommand::Resume))
+			}
+			(tiny_http::Method::Post, "/reload") => {
+				command_response(control_sender.send(crate::control::ControlCommand::ReloadConfig))
+			}
+			(tiny_http::Method::Get, "/frame.png") => Self::frame_response(control_sender),
+			(tiny_http::Method::Get, "/events/frame-timings") => {
+				Self::stream_frame_timings(request, control_sender);
+				return;
+			}
+			(_, path) => match path.strip_prefix("/parameters/") {
+				Some(key) if method == tiny_http::Method::Get => command_response(
+					control_sender.send(crate::control::ControlCommand::GetParameter {
+						key: key.to_owned(),
+					}),
+				),
+				Some(key) if method == tiny_http::Method::Put => {
+					let key = key.to_owned();
+					match read_body(&mut request) {
+						Ok(body) => command_response(control_sender.send(
+							crate::control::ControlCommand::SetParameter {
+								key,
+								value: crate::control::parse_value(body.trim()),
+							},
+						)),
+						Err(error) => error_response(400, &error),
+					}
+				}
+				Some(_) => {
+					error_response(405, "Parameters can only be read with GET or set with PUT")
+				}
+				None => error_response(404, &format!("There is nothing at {}", path)),
+			},
+		};
+		Self::respond(request, response);
+	}
+
+	fn respond(
+		request: tiny_http::Request,
+		response: tiny_http::Response<std::io::Cursor<Vec<u8>>>,
+	) {
+		// The client hanging up before it gets its response isn't worth warning about.
+		let _ = request.respond(response);
+	}
+
+	/**
+	 * Check that a request came from a program rather than a web page. Browsers let any page send requests to
+	 * localhost, but they mark them with an Origin header, and pages that get around that by rebinding their own host
+	 * name to 127.0.0.1 still send that host name.
+	 */
+	fn is_request_allowed(request: &tiny_http::Request, port: u16) -> bool {
+		let allowed_hosts = [format!("127.0.0.1:{}", port), format!("localhost:{}", port)];
+		let mut is_host_allowed = false;
+		for header in request.headers() {
+			if header.field.equiv("Origin") {
+				return false;
+			}
+			if header.field.equiv("Host") {
+				is_host_allowed = allowed_hosts
+					.iter()
+					.any(|allowed_host| header.value.as_str().eq_ignore_ascii_case(allowed_host));
+			}
+		}
+		is_host_allowed
+	}
+
+	fn frame_response(
+		control_sender: &crate::control::ControlSender,
+	) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+		let (png_sender, png_receiver) = std::sync::mpsc::channel();
+		if let Err(error) =
+			control_sender.send(crate::control::ControlCommand::CaptureFrame(png_sender))
+		{
+			return error_response(500, &error);
+		}
+		// The frame is sent before the command is answered, so it's already waiting by now.
+		match png_receiver.try_recv() {
+			Ok(png) => tiny_http::Response::from_data(png).with_header(content_type("image/png")),
+			Err(_) => error_response(500, "The frame wasn't captured"),
+		}
+	}
+
+	/**
+	 * Send the timing of every frame as a server-sent event until the client goes away.
+	 */
+	fn stream_frame_timings(
+		request: tiny_http::Request,
+		control_sender: &crate::control::ControlSender,
+	) {
+		let (frame_timing_sender, frame_timing_receiver) =
+			std::sync::mpsc::sync_channel(FRAME_TIMING_BUFFER_LENGTH);
+		let subscribe_result = control_sender.send(
+			crate::control::ControlCommand::SubscribeFrameTimings(frame_timing_sender),
+		);
+		if let Err(error) = subscribe_result {
+			Self::respond(request, error_response(500, &error));
+			return;
+		}
+		// The stream is written straight to the connection so that each event goes out as soon as its frame is drawn.
+		// It only ends when the client hangs up or the window goes away, so there's nothing to report when it does.
+		let _ = write_event_stream(request.into_writer(), frame_timing_receiver);
+	}
+}
+
+impl Drop for ControlHttpServer {
+	fn drop(&mut self) {
+		self.server.unblock();
+	}
+}
+
+fn write_event_stream(
+	mut writer: Box<dyn Write + Send>,
+	frame_timing_receiver: std::sync::mpsc::Receiver<crate::control::FrameTiming>,
+) -> std::io::Result<()> {
+	write!(
+		writer,
+		"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n"
+	)?;
+	writer.flush()?;
+	for frame_timing in frame_timing_receiver {
+		writeln!(
+			writer,
+			"data: {}\n",
+			serde_json::to_string(&frame_timing).unwrap_or_default()
+		)?;
+		writer.flush()?;
+	}
+	Ok(())
+}
+
+fn read_body(request: &mut tiny_http::Request) -> Result<String, String> {
+	let mut body = String::new();
+	request
+		.as_reader()
+		.take(MAXIMUM_BODY_LENGTH)
+		.read_to_string(&mut body)
+		.map_err(|error| format!("Could not read the body of the request: {}", error))?;
+	Ok(body)
+}
+
+fn content_type(content_type: &str) -> tiny_http::Header {
+	tiny_http::Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()).unwrap()
+}
+
+fn json_response(
+	status_code: u16,
+	value: &serde_json::Value,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+	tiny_http::Response::from_string(value.to_string())
+		.with_status_code(status_code)
+		.with_header(content_type("application/json"))
+}
+
+fn error_response(status_code: u16, error: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+	json_response(status_code, &serde_json::json!({ "error": error }))
+}
+
+/**
+ * Turn the response to a command into an HTTP response. Commands that fail are taken to have been asked for
+ * something that can't be done, rather than the server having broken.
+ */
+fn command_response(
+	response: crate::control::ControlResponse,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+	match response {
+		Ok(serde_json::Value::Null) => {
+			tiny_http::Response::from_data(Vec::new()).with_status_code(204)
+		}
+		Ok(value) => json_response(200, &value),
+		Err(error) => error_response(400, &error),
+	}
+}
@@ -0,0 +1,427 @@
+pub mod http;
+pub mod socket;
+
+use std::io::{BufRead, Write};
+
+/**
+ * How long to wait for the window to answer a command before giving up on it.
+ */
+const RESPONSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/**
+ * What `custom_background ctl` prints when it isn't given a command.
+ */
+const CLIENT_USAGE: &str = "Usage: custom_background ctl <command>
+
+Commands:
+  status                 Print the state of the background as JSON
+  scenes                 List the scenes that can be switched to
+  scene <name>           Switch to a scene, such as bouncing_cube or starfield
+  next                   Switch to the next scene
+  pause                  Stop the scene from moving
+  resume                 Let the scene move again
+  toggle                 Pause the scene if it's moving and resume it otherwise
+  reload                 Load the config file again
+  get <key>              Print a config value, such as `get starfield.star_brightness`
+  set <key> <value>      Set a config value until the next restart, such as `set starfield.star_brightness 3`
+  screenshot [path]      Save the next frame as a PNG
+  quit                   Shut the background down";
+
+/**
+ * Something that can be asked of the running background while it's running.
+ */
+#[derive(Clone, Debug)]
+pub enum ControlCommand {
+	Status,
+	ListScenes,
+	SwitchScene(crate::config::SceneKind),
+	NextScene,
+	Pause,
+	Resume,
+	TogglePause,
+	ReloadConfig,
+	/**
+	 * Get the value that is in use for a dotted key of the configuration, including values that were left at their
+	 * defaults.
+	 */
+	GetParameter {
+		key: String,
+	},
+	/**
+	 * Override a value from the configuration file, given by its dotted key, and apply the configuration again.
+	 */
+	SetParameter {
+		key: String,
+		value: toml::Value,
+	},
+	/**
+	 * Save the next frame into a PNG file, which goes into the pictures directory when there is no path.
+	 */
+	Screenshot(Option<std::path::PathBuf>),
+	/**
+	 * Encode the next frame as a PNG and send it through the sender, for clients that want the image itself rather
+	 * than a file.
+	 */
+	CaptureFrame(std::sync::mpsc::Sender<Vec<u8>>),
+	/**
+	 * Send the timing of every frame from now on through the sender, until its receiver is dropped. Timings are
+	 * dropped rather than queued up while the sender is full.
+	 */
+	SubscribeFrameTimings(std::sync::mpsc::SyncSender<FrameTiming>),
+	/**
+	 * Close the window and exit once the command has been answered.
+	 */
+	Shutdown,
+}
+
+/**
+ * How long a frame took, which is sent to subscribers after every frame.
+ */
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct FrameTiming {
+	/**
+	 * The time in seconds since the previous frame started.
+	 */
+	pub frame_interval: f32,
+	/**
+	 * The time in seconds that updating and drawing the frame took on the CPU.
+	 */
+	pub draw_duration: f32,
+}
+
+impl ControlCommand {
+	/**
+	 * Parse a command written as it is passed to `custom_background ctl`, such as `scene starfield`.
+	 */
+	pub fn parse(line: &str) -> Result<Self, String> {
+		let line = line.trim();
+		let (name, argument) = line
+			.split_once(char::is_whitespace)
+			.map_or((line, ""), |(name, argument)| (name, argument.trim()));
+		let without_argument = |command: Self| {
+			if argument.is_empty() {
+				Ok(command)
+			} else {
+				Err(format!("{} doesn't take an argument", name))
+			}
+		};
+		match name {
+			"status" => without_argument(Self::Status),
+			"scenes" => without_argument(Self::ListScenes),
+			"scene" => crate::config::SceneKind::from_name(argument).map(Self::SwitchScene),
+			"next" => without_argument(Self::NextScene),
+			"pause" => without_argument(Self::Pause),
+			"resume" => without_argument(Self::Resume),
+			"toggle" => without_argument(Self::TogglePause),
+			"reload" => without_argument(Self::ReloadConfig),
+			"get" if !argument.is_empty() => Ok(Self::GetParameter {
+				key: argument.to_owned(),
+			}),
+			"get" => Err("get needs a key".to_owned()),
+			"set" => {
+				let (key, value) = argument
+					.split_once(char::is_whitespace)
+					.ok_or_else(|| "set needs a key and a value".to_owned())?;
+				Ok(Self::SetParameter {
+					key: key.to_owned(),
+					value: parse_value(value.trim()),
+				})
+			}
+			"screenshot" => Ok(Self::Screenshot(
+				(!argument.is_empty()).then(|| std::path::PathBuf::from(argument)),
+			)),
+			"quit" => without_argument(Self::Shutdown),
+			"" => Err("No command was given".to_owned()),
+			_ => Err(format!("Unknown command {:?}", name)),
+		}
+	}
+}
+
+/**
+ * Read a value the way it would be written in the configuration file. Anything that isn't valid TOML is taken as a
+ * string, so that paths and names don't have to be quoted.
+ */
+fn parse_value(text: &str) -> toml::Value {
+	toml::from_str::<toml::value::Table>(&format!("value = {}", text))
+		.ok()
+		.and_then(|mut table| table.remove("value"))
+		.unwrap_or_else(|| toml::Value::String(text.to_owned()))
+}
+
+/**
+ * What the window answers a command with: a JSON value on success, which is null for commands that have nothing to
+ * report, or an explanation of what went wrong.
+ */
+pub type ControlResponse = Result<serde_json::Value, String>;
+
+/**
+ * A command on its way to the window along with where its response goes.
+ */
+pub struct ControlRequest {
+	pub command: ControlCommand,
+	response_sender: std::sync::mpsc::Sender<ControlResponse>,
+}
+
+impl ControlRequest {
+	pub fn respond(self, response: ControlResponse) {
+		// The client might have given up waiting, in which case there's no one left to tell.
+		let _ = self.response_sender.send(response);
+	}
+}
+
+/**
+ * Hands commands from other threads to the window and waits for its responses.
+ */
+#[derive(Clone)]
+pub struct ControlSender {
+	request_sender: std::sync::mpsc::Sender<ControlRequest>,
+	event_loop_proxy: winit::event_loop::EventLoopProxy<()>,
+}
+
+impl ControlSender {
+	pub fn send(&self, command: ControlCommand) -> ControlResponse {
+		let (response_sender, response_receiver) = std::sync::mpsc::channel();
+		self.request_sender
+			.send(ControlRequest {
+				command,
+				response_sender,
+			})
+			.map_err(|_| "The background is shutting down".to_owned())?;
+		// The event loop might be asleep until the next frame in low power mode, so it's woken up to handle the command
+		// right away.
+		let _ = self.event_loop_proxy.send_event(());
+		response_receiver
+			.recv_timeout(RESPONSE_TIMEOUT)
+			.map_err(|_| "The background didn't respond in time".to_owned())?
+	}
+}
+
+/**
+ * Make the channel that commands are sent to the window through. The window should take every request out of the
+ * receiver whenever the event loop gets a user event.
+ */
+pub fn channel(
+	event_loop: &winit::event_loop::EventLoop<()>,
+) -> (ControlSender, std::sync::mpsc::Receiver<ControlRequest>) {
+	let (request_sender, request_receiver) = std::sync::mpsc::channel();
+	(
+		ControlSender {
+			request_sender,
+			event_loop_proxy: event_loop.create_proxy(),
+		},
+		request_receiver,
+	)
+}
+
+/**
+ * Send a command given as command line arguments to the running background over its control socket and print the
+ * response. Returns the exit code of the process.
+ */
+pub fn run_client(
+	arguments: &[String],
+	control_configuration: &crate::config::ControlConfig,
+) -> i32 {
+	if arguments.is_empty() {
+		eprintln!("{}", CLIENT_USAGE);
+		return 2;
+	}
+	let line = arguments.join(" ");
+	// Commands are checked here too so that mistakes are reported even when the background isn't running.
+	if let Err(error) = ControlCommand::parse(&line) {
+		eprintln!("{}\n\n{}", error, CLIENT_USAGE);
+		return 2;
+	}
+	let socket_path = match control_configuration.socket_path() {
+		Some(socket_path) => socket_path,
+		None => {
+			eprintln!(
+				"There is nowhere to find the control socket since $XDG_RUNTIME_DIR isn't set"
+			);
+			return 1;
+		}
+	};
+
+	let response_line =
+		std::os::unix::net::UnixStream::connect(&socket_path).and_then(|mut stream| {
+			writeln!(stream, "{}", line)?;
+			let mut response_line = String::new();
+			std::io::BufReader::new(stream).read_line(&mut response_line)?;
+			Ok(response_line)
+		});
+	let response_line = match response_line {
+		Ok(response_line) => response_line,
+		Err(error) => {
+			eprintln!(
+				"Could not talk to the background over {}: {}",
+				socket_path.display(),
+				error
+			);
+			return 1;
+		}
+	};
+	match serde_json::from_str::<socket::SocketResponse>(&response_line) {
+		Ok(response) if response.ok => {
+			if !response.result.is_null() {
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&response.result).unwrap_or_default()
+				);
+			}
+			0
+		}
+		Ok(response) => {
+			eprintln!("{}", response.error.unwrap_or_default());
+			1
+		}
+		Err(error) => {
+			eprintln!("The background sent back an invalid response: {}", error);
+			1
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse_value, ControlCommand};
+
+	/**
+	 * Commands can't be compared directly since some of them hold channels, so they're compared by how they're
+	 * printed instead.
+	 */
+	fn assert_parses(line: &str, expected_command: ControlCommand) {
+		match ControlCommand::parse(line) {
+			Ok(command) => assert_eq!(
+				format!("{:?}", command),
+				format!("{:?}", expected_command),
+				"{:?}",
+				line
+			),
+			Err(error) => panic!("{:?} didn't parse: {}", line, error),
+		}
+	}
+
+	#[test]
+	fn parses_every_command() {
+		let commands = [
+			("status", ControlCommand::Status),
+			("scenes", ControlCommand::ListScenes),
+			(
+				"scene starfield",
+				ControlCommand::SwitchScene(crate::config::SceneKind::Starfield),
+			),
+			("next", ControlCommand::NextScene),
+			("pause", ControlCommand::Pause),
+			("resume", ControlCommand::Resume),
+			("toggle", ControlCommand::TogglePause),
+			("reload", ControlCommand::ReloadConfig),
+			(
+				"get starfield.star_brightness",
+				ControlCommand::GetParameter {
+					key: "starfield.star_brightness".to_owned(),
+				},
+			),
+			(
+				"set starfield.star_brightness 3",
+				ControlCommand::SetParameter {
+					key: "starfield.star_brightness".to_owned(),
+					value: toml::Value::Integer(3),
+				},
+			),
+			("screenshot", ControlCommand::Screenshot(None)),
+			(
+				"screenshot /tmp/frame.png",
+				ControlCommand::Screenshot(Some("/tmp/frame.png".into())),
+			),
+			("quit", ControlCommand::Shutdown),
+		];
+		for (line, expected_command) in commands {
+			assert_parses(line, expected_command);
+		}
+	}
+
+	#[test]
+	fn ignores_surrounding_and_repeated_whitespace() {
+		assert_parses("  status\n", ControlCommand::Status);
+		assert_parses(
+			"set\tfluid.viscosity   0.5 ",
+			ControlCommand::SetParameter {
+				key: "fluid.viscosity".to_owned(),
+				value: toml::Value::Float(0.5),
+			},
+		);
+		// Everything after the key is the value, spaces included.
+		assert_parses(
+			"set clock.format %H:%M %p",
+			ControlCommand::SetParameter {
+				key: "clock.format".to_owned(),
+				value: toml::Value::String("%H:%M %p".to_owned()),
+			},
+		);
+	}
+
+	#[test]
+	fn rejects_missing_extra_and_unknown_arguments() {
+		let invalid_lines = [
+			"",
+			"   ",
+			"dance",
+			"STATUS",
+			"status now",
+			"scenes all",
+			"next 2",
+			"pause 5",
+			"resume now",
+			"toggle it",
+			"reload config.toml",
+			"quit now",
+			"scene",
+			"scene no_such_scene",
+			"get",
+			"set",
+			"set starfield.star_brightness",
+			"set starfield.star_brightness   ",
+		];
+		for line in invalid_lines {
+			assert!(ControlCommand::parse(line).is_err(), "{:?} parsed", line);
+		}
+	}
+
+	#[test]
+	fn parses_values_as_toml_or_falls_back_to_strings() {
+		let values = [
+			("2", toml::Value::Integer(2)),
+			("-1", toml::Value::Integer(-1)),
+			("0.25", toml::Value::Float(0.25)),
+			("true", toml::Value::Boolean(true)),
+			("\"quoted\"", toml::Value::String("quoted".to_owned())),
+			("'1 2'", toml::Value::String("1 2".to_owned())),
+			(
+				"[1, 2]",
+				toml::Value::Array(vec![toml::Value::Integer(1), toml::Value::Integer(2)]),
+			),
+			(
+				"{ scene = \"starfield\" }",
+				toml::Value::Table(
+					[(
+						"scene".to_owned(),
+						toml::Value::String("starfield".to_owned()),
+					)]
+					.into_iter()
+					.collect(),
+				),
+			),
+			// Anything that isn't valid TOML is taken as it is.
+			("starfield", toml::Value::String("starfield".to_owned())),
+			(
+				"/home/me/pictures",
+				toml::Value::String("/home/me/pictures".to_owned()),
+			),
+			("1 2", toml::Value::String("1 2".to_owned())),
+			("[1, 2", toml::Value::String("[1, 2".to_owned())),
+			("\"unclosed", toml::Value::String("\"unclosed".to_owned())),
+		];
+		for (text, expected_value) in values {
+			assert_eq!(parse_value(text), expected_value, "{:?}", text);
+		}
+	}
+}
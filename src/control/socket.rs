@@ -0,0 +1,123 @@
+use std::io::{BufRead, Write};
+
+/**
+ * What is written back over the socket for each command, as a single line of JSON.
+ */
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct SocketResponse {
+	pub ok: bool,
+	#[serde(default)]
+	pub result: serde_json::Value,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl From<crate::control::ControlResponse> for SocketResponse {
+	fn from(response: crate::control::ControlResponse) -> Self {
+		match response {
+			Ok(result) => Self {
+				ok: true,
+				result,
+				error: None,
+			},
+			Err(error) => Self {
+				ok: false,
+				result: serde_json::Value::Null,
+				error: Some(error),
+			},
+		}
+	}
+}
+
+/**
+ * Listens on a Unix domain socket for commands, one per line, and answers each one with a line of JSON. Every
+ * connection is handled on its own thread so that a slow client can't hold up the others. The socket is removed
+ * when this is dropped.
+ */
+pub struct ControlSocket {
+	socket_path: std::path::PathBuf,
+}
+
+impl ControlSocket {
+	pub fn new(
+		socket_path: std::path::PathBuf,
+		control_sender: crate::control::ControlSender,
+	) -> Result<Self, String> {
+		// A socket that is left over from a background that didn't shut down cleanly would keep the new one from
+		// being made, but one that still answers belongs to a background that is running.
+		if socket_path.exists() {
+			if std::os::unix::net::UnixStream::connect(&socket_path).is_ok() {
+				return Err(format!(
+					"another background is already listening on {}",
+					socket_path.display()
+				));
+			}
+			std::fs::remove_file(&socket_path).map_err(|error| {
+				format!(
+					"could not remove the old socket {}: {}",
+					socket_path.display(),
+					error
+				)
+			})?;
+		}
+		let listener = std::os::unix::net::UnixListener::bind(&socket_path)
+			.map_err(|error| format!("could not listen on {}: {}", socket_path.display(), error))?;
+		std::thread::Builder::new()
+			.name("control socket".to_owned())
+			.spawn(move || {
+				for stream in listener.incoming() {
+					match stream {
+						Ok(stream) => {
+							let control_sender = control_sender.clone();
+							let spawn_result = std::thread::Builder::new()
+								.name("control connection".to_owned())
+								.spawn(move || Self::handle_connection(stream, &control_sender));
+							if let Err(error) = spawn_result {
+								log::warn!("Could not handle a control connection: {}", error);
+							}
+						}
+						Err(error) => {
+							log::warn!("Could not accept a control connection: {}", error)
+						}
+					}
+				}
+			})
+			.map_err(|error| error.to_string())?;
+		Ok(Self { socket_path })
+	}
+
+	fn handle_connection(
+		stream: std::os::unix::net::UnixStream,
+		control_sender: &crate::control::ControlSender,
+	) {
+		let mut writer = match stream.try_clone() {
+			Ok(writer) => writer,
+			Err(error) => {
+				log::warn!("Could not answer a control connection: {}", error);
+				return;
+			}
+		};
+		for line in std::io::BufReader::new(stream).lines() {
+			let line = match line {
+				Ok(line) => line,
+				Err(_) => return,
+			};
+			if line.trim().is_empty() {
+				continue;
+			}
+			let response = crate::control::ControlCommand::parse(&line)
+				.and_then(|command| control_sender.send(command));
+			let response_line =
+				serde_json::to_string(&SocketResponse::from(response)).unwrap_or_default();
+			if writeln!(writer, "{}", response_line).is_err() {
+				return;
+			}
+		}
+	}
+}
+
+impl Drop for ControlSocket {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_file(&self.socket_path);
+	}
+}
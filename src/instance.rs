@@ -0,0 +1,107 @@
+use std::io::{Read, Seek, Write};
+use std::os::unix::io::AsRawFd;
+
+/**
+ * How long to wait for a background that is being replaced to shut down.
+ */
+const REPLACE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/**
+ * How long to wait between attempts to take the lock while a background that is being replaced shuts down.
+ */
+const REPLACE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/**
+ * Why the lock file couldn't be locked.
+ */
+pub enum InstanceLockError {
+	/**
+	 * Another background holds the lock. Its process ID is written in the lock file, unless it's still writing it.
+	 */
+	AlreadyRunning(Option<libc::pid_t>),
+	Failed(String),
+}
+
+/**
+ * An exclusive lock on the lock file, which is held for as long as the background runs so that only one runs at a
+ * time. The lock goes away along with the process, even if it crashes, so a lock file that is left behind doesn't keep
+ * the next background from starting.
+ */
+pub struct InstanceLock {
+	_file: std::fs::File,
+}
+
+impl InstanceLock {
+	pub fn acquire(lock_path: &std::path::Path) -> Result<Self, InstanceLockError> {
+		let failed = |error: std::io::Error| {
+			InstanceLockError::Failed(format!("could not lock {}: {}", lock_path.display(), error))
+		};
+		if let Some(directory) = lock_path.parent() {
+			std::fs::create_dir_all(directory).map_err(failed)?;
+		}
+		let mut file = std::fs::OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			// The process ID of a background that holds the lock has to be kept until it's read below.
+			.truncate(false)
+			.open(lock_path)
+			.map_err(failed)?;
+		if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+			let error = std::io::Error::last_os_error();
+			if error.kind() != std::io::ErrorKind::WouldBlock {
+				return Err(failed(error));
+			}
+			let mut contents = String::new();
+			let process_id = file
+				.read_to_string(&mut contents)
+				.ok()
+				.and_then(|_| contents.trim().parse().ok());
+			return Err(InstanceLockError::AlreadyRunning(process_id));
+		}
+		file.set_len(0).map_err(failed)?;
+		file.rewind().map_err(failed)?;
+		writeln!(file, "{}", std::process::id()).map_err(failed)?;
+		Ok(Self { _file: file })
+	}
+
+	/**
+	 * Ask the background that holds the lock to shut down with SIGTERM, and take the lock once it has.
+	 */
+	pub fn replace(
+		lock_path: &std::path::Path,
+		process_id: Option<libc::pid_t>,
+	) -> Result<Self, String> {
+		let process_id = process_id.ok_or_else(|| {
+			format!(
+				"the background that is running didn't write its process ID into {}",
+				lock_path.display()
+			)
+		})?;
+		if unsafe { libc::kill(process_id, libc::SIGTERM) } != 0 {
+			return Err(format!(
+				"could not stop the background that is running as process {}: {}",
+				process_id,
+				std::io::Error::last_os_error()
+			));
+		}
+		let deadline = std::time::Instant::now() + REPLACE_TIMEOUT;
+		loop {
+			match Self::acquire(lock_path) {
+				Ok(instance_lock) => return Ok(instance_lock),
+				Err(InstanceLockError::Failed(error)) => return Err(error),
+				Err(InstanceLockError::AlreadyRunning(_))
+					if std::time::Instant::now() < deadline =>
+				{
+					std::thread::sleep(REPLACE_POLL_INTERVAL)
+				}
+				Err(InstanceLockError::AlreadyRunning(_)) => {
+					return Err(format!(
+						"the background that is running as process {} didn't shut down in time",
+						process_id
+					))
+				}
+			}
+		}
+	}
+}
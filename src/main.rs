@@ -1,7 +1,29 @@
+mod audio;
+mod config;
+mod control;
+mod instance;
+mod overlay;
 mod scene;
+mod screenshot;
+mod signals;
+mod state;
 mod window;
 use winit::event_loop::EventLoop;
 
+/**
+ * What is printed for --help or an option that isn't known.
+ */
+const USAGE: &str = "Usage: custom_background [--replace] [<command>]
+       custom_background ctl <command>
+
+Starts the background, or sends a command to the one that is already running when given one.
+
+Options:
+  -h, --help             Print this help
+  --replace              Shut down the background that is already running and take its place
+
+Run `custom_background ctl` to list the commands.";
+
 fn main() {
 	env_logger::init();
 
@@ -9,9 +31,99 @@ fn main() {
 	// mode or debug mode. The window is a background window in release mode.
 	let is_background_window = !cfg!(debug_assertions);
 
+	let config = config::Config::load();
+
+	// `custom_background ctl <command>` sends a command to the background that is already running instead of starting
+	// another one.
+	let arguments = std::env::args().skip(1).collect::<Vec<_>>();
+	if arguments.first().map(String::as_str) == Some("ctl") {
+		std::process::exit(control::run_client(&arguments[1..], &config.control));
+	}
+
+	// Only one background runs at a time. Any arguments after the options are forwarded to the one that is running as
+	// a control command, and a launch without them either replaces it or leaves it alone. Options only come before the
+	// command so that values in it, such as negative numbers, aren't mistaken for them.
+	let option_count = arguments
+		.iter()
+		.take_while(|argument| argument.starts_with('-'))
+		.count();
+	let (options, command_arguments) = arguments.split_at(option_count);
+	if options
+		.iter()
+		.any(|option| option == "--help" || option == "-h")
+	{
+		println!("{}", USAGE);
+		return;
+	}
+	if let Some(option) = options.iter().find(|option| *option != "--replace") {
+		eprintln!("Unknown option {}\n\n{}", option, USAGE);
+		std::process::exit(2);
+	}
+	let is_replacing = options.iter().any(|option| option == "--replace")
+		|| config.instance.on_conflict == config::InstanceConflict::Replace;
+	let _instance_lock = match config.instance.lock_path() {
+		Some(lock_path) => match instance::InstanceLock::acquire(&lock_path) {
+			Ok(_) if !command_arguments.is_empty() => {
+				eprintln!(
+					"The background isn't running, so there's nothing to send {:?} to",
+					command_arguments.join(" ")
+				);
+				std::process::exit(1);
+			}
+			Ok(instance_lock) => Some(instance_lock),
+			Err(instance::InstanceLockError::AlreadyRunning(_))
+				if !command_arguments.is_empty() =>
+			{
+				std::process::exit(control::run_client(command_arguments, &config.control));
+			}
+			Err(instance::InstanceLockError::AlreadyRunning(process_id)) if is_replacing => {
+				match instance::InstanceLock::replace(&lock_path, process_id) {
+					Ok(instance_lock) => Some(instance_lock),
+					Err(error) => {
+						eprintln!("Could not replace the background: {}", error);
+						std::process::exit(1);
+					}
+				}
+			}
+			Err(instance::InstanceLockError::AlreadyRunning(process_id)) => {
+				eprintln!(
+					"The background is already running{}. Pass --replace to take its place, or a command to send to it.",
+					process_id.map_or_else(String::new, |process_id| format!(
+						" as process {}",
+						process_id
+					))
+				);
+				std::process::exit(1);
+			}
+			// Whether a background is running can't be told, so the command is sent in case one is.
+			Err(instance::InstanceLockError::Failed(_)) if !command_arguments.is_empty() => {
+				std::process::exit(control::run_client(command_arguments, &config.control));
+			}
+			Err(instance::InstanceLockError::Failed(error)) => {
+				log::warn!(
+					"{}, so another background might start alongside this one",
+					error
+				);
+				None
+			}
+		},
+		None if !command_arguments.is_empty() => {
+			std::process::exit(control::run_client(command_arguments, &config.control));
+		}
+		None => {
+			log::warn!(
+				"There is nowhere to put the lock file since $XDG_RUNTIME_DIR isn't set, so another background might start alongside this one"
+			);
+			None
+		}
+	};
+
 	// Create the window and let it run
 	let event_loop = EventLoop::new();
-	let demo_window =
-		pollster::block_on(window::DemoWindow::new(&event_loop, is_background_window));
+	let demo_window = pollster::block_on(window::DemoWindow::new(
+		&event_loop,
+		is_background_window,
+		config,
+	));
 	demo_window.run(event_loop);
 }
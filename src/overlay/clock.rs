@@ -0,0 +1,225 @@
+/**
+ * The space between the time and the date, as a fraction of the date's font size.
+ */
+const LINE_SPACING: f32 = 0.25;
+
+/**
+ * Shows the time with the date under it over the final output of whichever scene is shown. The text is only formatted
+ * again when the minute changes, and the window is told when that next happens so that it can draw a frame right on
+ * the minute even when it is otherwise drawing very few frames.
+ */
+pub struct ClockOverlay {
+	clock_configuration: crate::config::ClockConfig,
+	text_renderer: crate::scene::utilities::text::TextRenderer,
+	resolution: [f32; 2],
+	time_text: String,
+	date_text: String,
+	/**
+	 * The minute that the text was formatted for, counted from the Unix epoch.
+	 */
+	displayed_minute: Option<i64>,
+	next_update_instant: std::time::Instant,
+}
+
+impl ClockOverlay {
+	pub fn new(
+		device: &wgpu::Device,
+		format: wgpu::TextureFormat,
+		width: u32,
+		height: u32,
+		clock_configuration: &crate::config::ClockConfig,
+	) -> Result<Self, String> {
+		let font = crate::scene::utilities::text::load_font(clock_configuration.font.as_deref())?;
+		let mut text_renderer =
+			crate::scene::utilities::text::TextRenderer::new(device, format, font, "Clock overlay");
+		text_renderer.resize(width, height);
+
+		// Formatting a time with an invalid format panics, so invalid formats are replaced before they are ever used.
+		let default_configuration = crate::config::ClockConfig::default();
+		let mut clock_configuration = clock_configuration.clone();
+		clock_configuration.time_format = Self::validate_format(
+			&clock_configuration.time_format,
+			&default_configuration.time_format,
+		);
+		clock_configuration.date_format = Self::validate_format(
+			&clock_configuration.date_format,
+			&default_configuration.date_format,
+		);
+
+		Ok(Self {
+			clock_configuration,
+			text_renderer,
+			resolution: [width as f32, height as f32],
+			time_text: String::new(),
+			date_text: String::new(),
+			displayed_minute: None,
+			next_update_instant: std::time::Instant::now(),
+		})
+	}
+
+	fn validate_format(format: &str, default_format: &str) -> String {
+		let is_valid = chrono::format::StrftimeItems::new(format)
+			.all(|item| !matches!(item, chrono::format::Item::Error));
+		if is_valid {
+			format.to_owned()
+		} else {
+			log::warn!(
+				"The clock format \"{}\" is invalid, so \"{}\" is used instead",
+				format,
+				default_format
+			);
+			default_format.to_owned()
+		}
+	}
+
+	pub fn resize(&mut self, width: u32, height: u32) {
+		self.resolution = [width as f32, height as f32];
+		self.text_renderer.resize(width, height);
+	}
+
+	/**
+	 * Get when the text next has to change, which is the start of the next minute.
+	 */
+	pub fn next_update_instant(&self) -> std::time::Instant {
+		self.next_update_instant
+	}
+
+	/**
+	 * Format the time and date again if the minute has changed since they were last formatted.
+	 */
+	pub fn update(&mut self) {
+		let now_instant = std::time::Instant::now();
+		if now_instant < self.next_update_instant {
+			return;
+		}
+		let now = chrono::Local::now();
+		let minute = now.timestamp().div_euclid(60);
+		if self.displayed_minute != Some(minute) {
+			self.time_text = now
+				.format(&self.clock_configuration.time_format)
+				.to_string();
+			self.date_text = now
+				.format(&self.clock_configuration.date_format)
+				.to_string();
+			self.displayed_minute = Some(minute);
+		}
+		// The wall clock is checked again when this is reached, so waking up a little early only costs a frame.
+		let milliseconds_until_next_minute = 60_000 - now.timestamp_millis().rem_euclid(60_000);
+		self.next_update_instant =
+			now_instant + std::time::Duration::from_millis(milliseconds_until_next_minute as u64);
+	}
+
+	/**
+	 * Queue a line of text along with its shadow and outline, which are drawn behind it.
+	 */
+	fn queue_line(&mut self, text: &str, options: crate::scene::utilities::text::TextOptions) {
+		let shadow_color = self.clock_configuration.shadow_color;
+		if shadow_color[3] > 0.0 {
+			let shadow_offset = self.clock_configuration.shadow_offset;
+			self.text_renderer.queue_text(
+				text,
+				&crate::scene::utilities::text::TextOptions {
+					position: [
+						options.position[0] + shadow_offset[0],
+						options.position[1] + shadow_offset[1],
+					],
+					color: shadow_color,
+					..options.clone()
+				},
+			);
+		}
+		let outline_width = self.clock_configuration.outline_width;
+		if outline_width > 0.0 {
+			// The outline is made out of copies of the text moved out in each of eight directions.
+			for direction_index in 0..8 {
+				let angle = direction_index as f32 * std::f32::consts::FRAC_PI_4;
+				self.text_renderer.queue_text(
+					text,
+					&crate::scene::utilities::text::TextOptions {
+						position: [
+							options.position[0] + outline_width * angle.cos(),
+							options.position[1] + outline_width * angle.sin(),
+						],
+						color: self.clock_configuration.outline_color,
+						..options.clone()
+					},
+				);
+			}
+		}
+		self.text_renderer.queue_text(text, &options);
+	}
+
+	/**
+	 * Draw the clock over the existing contents of the output texture.
+	 */
+	pub fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		use crate::scene::utilities::text::{TextAnchor, TextOptions};
+
+		// Both lines are laid out as one block placed at the anchor, with each line aligned within it on the anchor's
+		// side.
+		let anchor_factors = self.clock_configuration.anchor.factors();
+		let line_anchor = match anchor_factors[0] {
+			factor if factor < 0.25 => TextAnchor::TopLeft,
+			factor if factor > 0.75 => TextAnchor::TopRight,
+			_ => TextAnchor::Top,
+		};
+		let anchor_position = crate::overlay::anchor_position(
+			self.clock_configuration.anchor,
+			self.clock_configuration.margin,
+			self.resolution,
+		);
+		let time_options = TextOptions {
+			size: self.clock_configuration.time_size,
+			color: self.clock_configuration.color,
+			anchor: line_anchor,
+			..TextOptions::default()
+		};
+		let date_options = TextOptions {
+			size: self.clock_configuration.date_size,
+			..time_options.clone()
+		};
+		let time_height = if self.time_text.is_empty() {
+			0.0
+		} else {
+			self.text_renderer
+				.measure_text(&self.time_text, &time_options)[1]
+		};
+		let date_height = if self.date_text.is_empty() {
+			0.0
+		} else {
+			self.text_renderer
+				.measure_text(&self.date_text, &date_options)[1]
+		};
+		let line_spacing = if time_height > 0.0 && date_height > 0.0 {
+			LINE_SPACING * self.clock_configuration.date_size
+		} else {
+			0.0
+		};
+		let block_top =
+			anchor_position[1] - anchor_factors[1] * (time_height + line_spacing + date_height);
+
+		let time_text = self.time_text.clone();
+		self.queue_line(
+			&time_text,
+			TextOptions {
+				position: [anchor_position[0], block_top],
+				..time_options
+			},
+		);
+		let date_text = self.date_text.clone();
+		self.queue_line(
+			&date_text,
+			TextOptions {
+				position: [anchor_position[0], block_top + time_height + line_spacing],
+				..date_options
+			},
+		);
+		self.text_renderer
+			.render(command_encoder, queue, output_texture_view);
+	}
+}
@@ -0,0 +1,18 @@
+pub mod clock;
+pub mod system_stats;
+
+/**
+ * Get the point on screen that an overlay is placed at, which is the anchor's point of the screen moved in towards
+ * the middle of the screen by the margin.
+ */
+pub fn anchor_position(
+	anchor: crate::scene::utilities::text::TextAnchor,
+	margin: [f32; 2],
+	resolution: [f32; 2],
+) -> [f32; 2] {
+	let anchor_factors = anchor.factors();
+	[
+		anchor_factors[0] * resolution[0] + (1.0 - 2.0 * anchor_factors[0]) * margin[0],
+		anchor_factors[1] * resolution[1] + (1.0 - 2.0 * anchor_factors[1]) * margin[1],
+	]
+}
@@ -0,0 +1,211 @@
+/**
+ * The most bars that can be drawn in a single frame.
+ */
+const MAXIMUM_BAR_COUNT: usize = 4096;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct BarInstance {
+	position: [f32; 2],
+	size: [f32; 2],
+	color: [f32; 4],
+}
+
+impl BarInstance {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+		0 => Float32x2,
+		1 => Float32x2,
+		2 => Float32x4,
+	];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Instance,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	resolution: [f32; 2],
+}
+
+/**
+ * Draws solid rectangles, which the overlay's background and sparklines are made out of. Like text, rectangles are
+ * queued up during a frame and then drawn all at once.
+ */
+pub struct BarRenderer {
+	render_pipeline: wgpu::RenderPipeline,
+	instance_buffer: wgpu::Buffer,
+	bar_instances: Vec<BarInstance>,
+	resolution: [f32; 2],
+	has_warned_about_bar_count: bool,
+}
+
+impl BarRenderer {
+	pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+		let shader_module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+			label: Some("System stats overlay bar shader"),
+			source: wgpu::ShaderSource::Wgsl(include_str!("bars.wgsl").into()),
+		});
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("System stats overlay bar pipeline layout"),
+				bind_group_layouts: &[],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::VERTEX,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("System stats overlay bar pipeline"),
+			layout: Some(&render_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[BarInstance::vertex_buffer_layout()],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: &shader_module,
+				entry_point: "fragment_stage",
+				targets: &[wgpu::ColorTargetState {
+					format,
+					blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+					write_mask: wgpu::ColorWrites::all(),
+				}],
+			}),
+			primitive: wgpu::PrimitiveState {
+				topology: wgpu::PrimitiveTopology::TriangleStrip,
+				..wgpu::PrimitiveState::default()
+			},
+			depth_stencil: None,
+			multisample: wgpu::MultisampleState::default(),
+			multiview: None,
+		});
+		let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("System stats overlay bar instance buffer"),
+			size: (MAXIMUM_BAR_COUNT * std::mem::size_of::<BarInstance>()) as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+
+		Self {
+			render_pipeline,
+			instance_buffer,
+			bar_instances: Vec::new(),
+			resolution: [1.0, 1.0],
+			has_warned_about_bar_count: false,
+		}
+	}
+
+	pub fn resize(&mut self, width: u32, height: u32) {
+		self.resolution = [width.max(1) as f32, height.max(1) as f32];
+	}
+
+	/**
+	 * Queue a rectangle given by its top left corner and size in pixels.
+	 */
+	pub fn queue_rectangle(&mut self, position: [f32; 2], size: [f32; 2], color: [f32; 4]) {
+		if self.bar_instances.len() >= MAXIMUM_BAR_COUNT {
+			if !self.has_warned_about_bar_count {
+				log::warn!(
+					"The system stats overlay is drawing more than {} bars, so some graphs will be missing",
+					MAXIMUM_BAR_COUNT
+				);
+				self.has_warned_about_bar_count = true;
+			}
+			return;
+		}
+		self.bar_instances.push(BarInstance {
+			position,
+			size,
+			color,
+		});
+	}
+
+	/**
+	 * Queue a graph with a bar for each value, where values go from 0 for an empty bar to 1 for a bar that fills the
+	 * graph's height. The graph is split into a fixed number of slots, and the values fill the rightmost ones so that
+	 * the newest value is always at the right edge.
+	 */
+	pub fn queue_sparkline(
+		&mut self,
+		values: &[f32],
+		slot_count: usize,
+		position: [f32; 2],
+		size: [f32; 2],
+		color: [f32; 4],
+	) {
+		let slot_count = slot_count.max(values.len()).max(1);
+		let slot_width = size[0] / slot_count as f32;
+		let first_slot_index = slot_count - values.len();
+		for (value_index, value) in values.iter().enumerate() {
+			let bar_height = value.clamp(0.0, 1.0) * size[1];
+			if bar_height <= 0.0 {
+				continue;
+			}
+			// Bars are a pixel narrower than their slots when there is room, so neighboring bars stay apart.
+			let bar_width = if slot_width > 2.0 {
+				slot_width - 1.0
+			} else {
+				slot_width
+			};
+			self.queue_rectangle(
+				[
+					position[0] + (first_slot_index + value_index) as f32 * slot_width,
+					position[1] + size[1] - bar_height,
+				],
+				[bar_width, bar_height],
+				color,
+			);
+		}
+	}
+
+	/**
+	 * Draw every rectangle that has been queued since the last call over the existing contents of the output texture.
+	 */
+	pub fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		if self.bar_instances.is_empty() {
+			return;
+		}
+		queue.write_buffer(
+			&self.instance_buffer,
+			0,
+			bytemuck::cast_slice(&self.bar_instances),
+		);
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("System stats overlay bar render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Load,
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::VERTEX,
+			0,
+			bytemuck::bytes_of(&PushConstantData {
+				resolution: self.resolution,
+			}),
+		);
+		render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+		render_pass.draw(0..4, 0..self.bar_instances.len() as u32);
+		drop(render_pass);
+
+		self.bar_instances.clear();
+	}
+}
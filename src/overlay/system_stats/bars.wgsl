@@ -0,0 +1,30 @@
+struct PushConstantData {
+	resolution: vec2<f32>,
+};
+var<push_constant> push_constant_data: PushConstantData;
+
+struct BarInput {
+	// The top left corner and size of the bar on screen, in pixels.
+	@location(0) position: vec2<f32>,
+	@location(1) size: vec2<f32>,
+	@location(2) color: vec4<f32>,
+};
+
+struct FragmentInput {
+	@builtin(position) clip_position: vec4<f32>,
+	@location(0) color: vec4<f32>,
+};
+
+// Each bar is drawn as a triangle strip of four vertices making a quad.
+@vertex
+fn vertex_stage(@builtin(vertex_index) vertex_index: u32, bar: BarInput) -> FragmentInput {
+	let corner = vec2<f32>(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+	let pixel_position = bar.position + corner * bar.size;
+	let position = pixel_position / push_constant_data.resolution * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+	return FragmentInput(vec4<f32>(position, 0.0, 1.0), bar.color);
+}
+
+@fragment
+fn fragment_stage(fragment: FragmentInput) -> @location(0) vec4<f32> {
+	return fragment.color;
+}
@@ -0,0 +1,281 @@
+mod bars;
+mod sampler;
+
+/**
+ * The height of each row of the panel, as a multiple of the font size.
+ */
+const ROW_HEIGHT: f32 = 1.6;
+
+/**
+ * The space between the edge of the panel and its contents, as a multiple of the font size.
+ */
+const PADDING: f32 = 0.5;
+
+/**
+ * The fraction of the panel's width that the graphs take up on its right side.
+ */
+const GRAPH_WIDTH: f32 = 0.4;
+
+/**
+ * A line of the panel, made of text on the left and a graph on the right.
+ */
+struct Row {
+	text: String,
+	/**
+	 * The heights of the graph's bars from 0 to 1.
+	 */
+	graph_values: Vec<f32>,
+	graph_slot_count: usize,
+}
+
+/**
+ * Shows a panel of CPU, memory, load, network and disk statistics over the final output of whichever scene is shown,
+ * with a graph of the recent history of each one. The statistics are sampled on a background thread at their own
+ * interval, so they are read just as often no matter how many frames are drawn.
+ */
+pub struct SystemStatsOverlay {
+	system_stats_configuration: crate::config::SystemStatsConfig,
+	sampler: sampler::SystemStatsSampler,
+	/**
+	 * The most recent samples, from oldest to newest.
+	 */
+	history: std::collections::VecDeque<sampler::SystemStats>,
+	text_renderer: crate::scene::utilities::text::TextRenderer,
+	bar_renderer: bars::BarRenderer,
+	resolution: [f32; 2],
+}
+
+impl SystemStatsOverlay {
+	pub fn new(
+		device: &wgpu::Device,
+		format: wgpu::TextureFormat,
+		width: u32,
+		height: u32,
+		system_stats_configuration: &crate::config::SystemStatsConfig,
+	) -> Result<Self, String> {
+		let font =
+			crate::scene::utilities::text::load_font(system_stats_configuration.font.as_deref())?;
+		let mut text_renderer = crate::scene::utilities::text::TextRenderer::new(
+			device,
+			format,
+			font,
+			"System stats overlay",
+		);
+		text_renderer.resize(width, height);
+		let mut bar_renderer = bars::BarRenderer::new(device, format);
+		bar_renderer.resize(width, height);
+		let sampler = sampler::SystemStatsSampler::new(
+			system_stats_configuration.procfs_root.clone(),
+			system_stats_configuration.disk_paths.clone(),
+			std::time::Duration::from_secs_f32(
+				system_stats_configuration.sampling_interval.max(0.1),
+			),
+		);
+
+		Ok(Self {
+			system_stats_configuration: system_stats_configuration.clone(),
+			sampler,
+			history: std::collections::VecDeque::new(),
+			text_renderer,
+			bar_renderer,
+			resolution: [width as f32, height as f32],
+		})
+	}
+
+	pub fn resize(&mut self, width: u32, height: u32) {
+		self.resolution = [width as f32, height as f32];
+		self.text_renderer.resize(width, height);
+		self.bar_renderer.resize(width, height);
+	}
+
+	/**
+	 * Take in any statistics that have been sampled since the last update.
+	 */
+	pub fn update(&mut self) {
+		let history_length = self.system_stats_configuration.history_length.max(1) as usize;
+		for system_stats in self.sampler.new_samples() {
+			self.history.push_back(system_stats);
+		}
+		while self.history.len() > history_length {
+			self.history.pop_front();
+		}
+	}
+
+	/**
+	 * Make a row for a value that has a history, with the text for the newest sample and a graph of every sample.
+	 */
+	fn history_row(&self, text: String, graph_value: impl Fn(&sampler::SystemStats) -> f32) -> Row {
+		Row {
+			text,
+			graph_values: self.history.iter().map(graph_value).collect(),
+			graph_slot_count: self.system_stats_configuration.history_length as usize,
+		}
+	}
+
+	fn rows(&self, latest: &sampler::SystemStats) -> Vec<Row> {
+		let core_count = latest.core_loads.len().max(1) as f32;
+		// Network rates have no upper limit, so their graphs are scaled to the highest rate in the history.
+		let maximum_receive_rate = self
+			.history
+			.iter()
+			.map(|system_stats| system_stats.network_receive_rate)
+			.fold(1024.0, f64::max);
+		let maximum_transmit_rate = self
+			.history
+			.iter()
+			.map(|system_stats| system_stats.network_transmit_rate)
+			.fold(1024.0, f64::max);
+
+		let mut rows = vec![
+			self.history_row(
+				format!("CPU {:.0}%", latest.total_cpu_load * 100.0),
+				|system_stats| system_stats.total_cpu_load,
+			),
+			Row {
+				text: format!("Cores {}", latest.core_loads.len()),
+				graph_values: latest.core_loads.clone(),
+				graph_slot_count: latest.core_loads.len(),
+			},
+			self.history_row(
+				format!(
+					"Memory {} / {}",
+					format_bytes(latest.memory_used as f64),
+					format_bytes(latest.memory_total as f64)
+				),
+				|system_stats| fraction(system_stats.memory_used, system_stats.memory_total),
+			),
+			self.history_row(
+				format!(
+					"Load {:.2} {:.2} {:.2}",
+					latest.load_average[0], latest.load_average[1], latest.load_average[2]
+				),
+				|system_stats| system_stats.load_average[0] / core_count,
+			),
+			self.history_row(
+				format!("Down {}/s", format_bytes(latest.network_receive_rate)),
+				|system_stats| (system_stats.network_receive_rate / maximum_receive_rate) as f32,
+			),
+			self.history_row(
+				format!("Up {}/s", format_bytes(latest.network_transmit_rate)),
+				|system_stats| (system_stats.network_transmit_rate / maximum_transmit_rate) as f32,
+			),
+		];
+		for (disk_index, disk_usage) in latest.disk_usages.iter().enumerate() {
+			rows.push(self.history_row(
+				format!(
+					"Disk {} {} / {}",
+					disk_usage.path.display(),
+					format_bytes(disk_usage.used as f64),
+					format_bytes(disk_usage.total as f64)
+				),
+				|system_stats| {
+					system_stats
+						.disk_usages
+						.get(disk_index)
+						.map_or(0.0, |disk_usage| {
+							fraction(disk_usage.used, disk_usage.total)
+						})
+				},
+			));
+		}
+		rows
+	}
+
+	/**
+	 * Draw the panel over the existing contents of the output texture.
+	 */
+	pub fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		// Nothing is shown until the first sample arrives.
+		let rows = match self.history.back() {
+			Some(latest) => self.rows(latest),
+			None => return,
+		};
+
+		let text_size = self.system_stats_configuration.text_size;
+		let row_height = ROW_HEIGHT * text_size;
+		let padding = PADDING * text_size;
+		let panel_size = [
+			self.system_stats_configuration.width,
+			2.0 * padding + rows.len() as f32 * row_height,
+		];
+		let anchor = self.system_stats_configuration.anchor;
+		let anchor_position = crate::overlay::anchor_position(
+			anchor,
+			self.system_stats_configuration.margin,
+			self.resolution,
+		);
+		let anchor_factors = anchor.factors();
+		let panel_position = [
+			anchor_position[0] - anchor_factors[0] * panel_size[0],
+			anchor_position[1] - anchor_factors[1] * panel_size[1],
+		];
+		self.bar_renderer.queue_rectangle(
+			panel_position,
+			panel_size,
+			self.system_stats_configuration.background_color,
+		);
+
+		let content_width = panel_size[0] - 2.0 * padding;
+		let graph_width = GRAPH_WIDTH * content_width;
+		for (row_index, row) in rows.iter().enumerate() {
+			let row_top = panel_position[1] + padding + row_index as f32 * row_height;
+			let row_middle = row_top + 0.5 * row_height;
+			self.text_renderer.queue_text(
+				&row.text,
+				&crate::scene::utilities::text::TextOptions {
+					position: [panel_position[0] + padding, row_middle],
+					size: text_size,
+					color: self.system_stats_configuration.text_color,
+					anchor: crate::scene::utilities::text::TextAnchor::Left,
+					maximum_width: None,
+				},
+			);
+			self.bar_renderer.queue_sparkline(
+				&row.graph_values,
+				row.graph_slot_count,
+				[
+					panel_position[0] + padding + content_width - graph_width,
+					row_middle - 0.5 * text_size,
+				],
+				[graph_width, text_size],
+				self.system_stats_configuration.graph_color,
+			);
+		}
+
+		self.bar_renderer
+			.render(command_encoder, queue, output_texture_view);
+		self.text_renderer
+			.render(command_encoder, queue, output_texture_view);
+	}
+}
+
+fn fraction(part: u64, total: u64) -> f32 {
+	if total == 0 {
+		0.0
+	} else {
+		part as f32 / total as f32
+	}
+}
+
+/**
+ * Format a number of bytes with the largest binary unit that keeps it at least 1.
+ */
+fn format_bytes(bytes: f64) -> String {
+	const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+	let mut value = bytes;
+	let mut unit_index = 0;
+	while value >= 1024.0 && unit_index < UNITS.len() - 1 {
+		value /= 1024.0;
+		unit_index += 1;
+	}
+	if unit_index == 0 {
+		format!("{:.0} {}", value, UNITS[unit_index])
+	} else {
+		format!("{:.1} {}", value, UNITS[unit_index])
+	}
+}
@@ -0,0 +1,459 @@
+use std::os::unix::ffi::OsStrExt;
+
+/**
+ * How much of a file system is in use, in bytes.
+ */
+#[derive(Clone, Debug)]
+pub struct DiskUsage {
+	pub path: std::path::PathBuf,
+	pub used: u64,
+	pub total: u64,
+}
+
+/**
+ * The state of the system over one sampling interval. Values that couldn't be read are left at zero or empty.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct SystemStats {
+	/**
+	 * How busy each core was over the interval, from 0 to 1.
+	 */
+	pub core_loads: Vec<f32>,
+	/**
+	 * How busy all of the cores together were over the interval, from 0 to 1.
+	 */
+	pub total_cpu_load: f32,
+	pub memory_used: u64,
+	pub memory_total: u64,
+	/**
+	 * The average number of runnable processes over the last 1, 5 and 15 minutes.
+	 */
+	pub load_average: [f32; 3],
+	/**
+	 * The bytes per second received and sent over the interval on every network interface except loopback.
+	 */
+	pub network_receive_rate: f64,
+	pub network_transmit_rate: f64,
+	pub disk_usages: Vec<DiskUsage>,
+}
+
+/**
+ * The time a CPU has spent busy and in total, in clock ticks since boot.
+ */
+#[derive(Clone, Copy, Default)]
+struct CpuTimes {
+	busy: u64,
+	total: u64,
+}
+
+/**
+ * Reads the system's statistics on a background thread at a fixed interval, independently of how often frames are
+ * drawn. Rates such as CPU load and network throughput are worked out from the change between one reading and the
+ * next, so the first statistics arrive one interval after the sampler starts.
+ */
+pub struct SystemStatsSampler {
+	receiver: std::sync::mpsc::Receiver<SystemStats>,
+}
+
+impl SystemStatsSampler {
+	/**
+	 * Start sampling. The procfs root is where the proc file system is read from, which is normally /proc.
+	 */
+	pub fn new(
+		procfs_root: std::path::PathBuf,
+		disk_paths: Vec<std::path::PathBuf>,
+		sampling_interval: std::time::Duration,
+	) -> Self {
+		let (sender, receiver) = std::sync::mpsc::channel();
+		let spawn_result = std::thread::Builder::new()
+			.name("system stats sampler".to_owned())
+			.spawn(move || {
+				let mut sampling_state = SamplingState::new(procfs_root, disk_paths);
+				sampling_state.read_counters();
+				loop {
+					std::thread::sleep(sampling_interval);
+					// The overlay has been dropped once nothing is receiving statistics anymore.
+					if sender.send(sampling_state.sample()).is_err() {
+						return;
+					}
+				}
+			});
+		if let Err(error) = spawn_result {
+			log::warn!("Could not start the system stats sampler: {}", error);
+		}
+		Self { receiver }
+	}
+
+	/**
+	 * Get the statistics that have been sampled since the last call, from oldest to newest.
+	 */
+	pub fn new_samples(&self) -> impl Iterator<Item = SystemStats> + '_ {
+		self.receiver.try_iter()
+	}
+}
+
+/**
+ * The readings that the next sample's rates are measured from.
+ */
+struct SamplingState {
+	procfs_root: std::path::PathBuf,
+	disk_paths: Vec<std::path::PathBuf>,
+	/**
+	 * The times of all of the CPUs together followed by the times of each core.
+	 */
+	cpu_times: Vec<CpuTimes>,
+	network_bytes: [u64; 2],
+	instant: std::time::Instant,
+	/**
+	 * The files that couldn't be read, which are only warned about the first time.
+	 */
+	failed_files: std::collections::HashSet<&'static str>,
+}
+
+impl SamplingState {
+	fn new(procfs_root: std::path::PathBuf, disk_paths: Vec<std::path::PathBuf>) -> Self {
+		Self {
+			procfs_root,
+			disk_paths,
+			cpu_times: Vec::new(),
+			network_bytes: [0, 0],
+			instant: std::time::Instant::now(),
+			failed_files: std::collections::HashSet::new(),
+		}
+	}
+
+	/**
+	 * Read a file in the proc file system, warning the first time that it can't be read.
+	 */
+	fn read_procfs_file(&mut self, file_name: &'static str) -> Option<String> {
+		let path = self.procfs_root.join(file_name);
+		match std::fs::read_to_string(&path) {
+			Ok(contents) => Some(contents),
+			Err(error) => {
+				if self.failed_files.insert(file_name) {
+					log::warn!(
+						"Could not read {} for the system stats overlay: {}",
+						path.display(),
+						error
+					);
+				}
+				None
+			}
+		}
+	}
+
+	/**
+	 * Read the counters that rates are measured from, returning how much they changed since they were last read along
+	 * with the number of seconds that passed.
+	 */
+	fn read_counters(&mut self) -> (Vec<CpuTimes>, [u64; 2], f64) {
+		let cpu_times = self
+			.read_procfs_file("stat")
+			.map(|stat| Self::parse_cpu_times(&stat))
+			.unwrap_or_default();
+		let network_bytes = self
+			.read_procfs_file("net/dev")
+			.map(|net_dev| Self::parse_network_bytes(&net_dev))
+			.unwrap_or_default();
+		let instant = std::time::Instant::now();
+
+		let cpu_time_changes = if cpu_times.len() == self.cpu_times.len() {
+			cpu_times
+				.iter()
+				.zip(&self.cpu_times)
+				.map(|(cpu_times, previous_cpu_times)| CpuTimes {
+					busy: cpu_times.busy.saturating_sub(previous_cpu_times.busy),
+					total: cpu_times.total.saturating_sub(previous_cpu_times.total),
+				})
+				.collect()
+		} else {
+			Vec::new()
+		};
+		// Counters can go backwards when an interface goes away, which is treated as no traffic.
+		let network_byte_changes = [
+			network_bytes[0].saturating_sub(self.network_bytes[0]),
+			network_bytes[1].saturating_sub(self.network_bytes[1]),
+		];
+		let elapsed_seconds = (instant - self.instant).as_secs_f64();
+
+		self.cpu_times = cpu_times;
+		self.network_bytes = network_bytes;
+		self.instant = instant;
+		(cpu_time_changes, network_byte_changes, elapsed_seconds)
+	}
+
+	fn sample(&mut self) -> SystemStats {
+		let (cpu_time_changes, network_byte_changes, elapsed_seconds) = self.read_counters();
+		let cpu_loads = cpu_time_changes
+			.iter()
+			.map(|cpu_time_change| {
+				if cpu_time_change.total == 0 {
+					0.0
+				} else {
+					cpu_time_change.busy as f32 / cpu_time_change.total as f32
+				}
+			})
+			.collect::<Vec<_>>();
+		let (memory_used, memory_total) = self
+			.read_procfs_file("meminfo")
+			.map(|meminfo| Self::parse_memory(&meminfo))
+			.unwrap_or_default();
+		let load_average = self
+			.read_procfs_file("loadavg")
+			.map(|loadavg| Self::parse_load_average(&loadavg))
+			.unwrap_or_default();
+		let disk_usages = self
+			.disk_paths
+			.iter()
+			.filter_map(|disk_path| Self::disk_usage(disk_path))
+			.collect();
+
+		let rate = |byte_change: u64| {
+			if elapsed_seconds > 0.0 {
+				byte_change as f64 / elapsed_seconds
+			} else {
+				0.0
+			}
+		};
+		SystemStats {
+			total_cpu_load: cpu_loads.first().copied().unwrap_or(0.0),
+			core_loads: cpu_loads.into_iter().skip(1).collect(),
+			memory_used,
+			memory_total,
+			load_average,
+			network_receive_rate: rate(network_byte_changes[0]),
+			network_transmit_rate: rate(network_byte_changes[1]),
+			disk_usages,
+		}
+	}
+
+	/**
+	 * Parse the CPU lines of /proc/stat, which start with the line for every CPU together followed by a line for each
+	 * core. Time spent idle or waiting on IO counts as not busy.
+	 */
+	fn parse_cpu_times(stat: &str) -> Vec<CpuTimes> {
+		stat.lines()
+			.filter(|line| line.starts_with("cpu"))
+			.map(|line| {
+				// Only user, nice, system, idle, iowait, irq, softirq and steal are counted, since the guest times that
+				// follow are already included in the user times.
+				let times = line
+					.split_whitespace()
+					.skip(1)
+					.take(8)
+					.map(|time| time.parse::<u64>().unwrap_or(0))
+					.collect::<Vec<_>>();
+				let total = times.iter().sum::<u64>();
+				let idle = times.get(3).copied().unwrap_or(0) + times.get(4).copied().unwrap_or(0);
+				CpuTimes {
+					busy: total.saturating_sub(idle),
+					total,
+				}
+			})
+			.collect()
+	}
+
+	/**
+	 * Parse the memory in use and the total memory in bytes out of /proc/meminfo. Memory that is available to be
+	 * reclaimed, such as the page cache, doesn't count as being in use.
+	 */
+	fn parse_memory(meminfo: &str) -> (u64, u64) {
+		let field = |name: &str| {
+			meminfo
+				.lines()
+				.find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+				.and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+				.map(|kibibytes| kibibytes * 1024)
+		};
+		let total = field("MemTotal").unwrap_or(0);
+		let available = field("MemAvailable")
+			.or_else(|| field("MemFree"))
+			.unwrap_or(0);
+		(total.saturating_sub(available), total)
+	}
+
+	fn parse_load_average(loadavg: &str) -> [f32; 3] {
+		let mut load_average = [0.0; 3];
+		for (load, value) in load_average.iter_mut().zip(loadavg.split_whitespace()) {
+			*load = value.parse().unwrap_or(0.0);
+		}
+		load_average
+	}
+
+	/**
+	 * Parse the total bytes received and sent by every network interface except loopback out of /proc/net/dev.
+	 */
+	fn parse_network_bytes(net_dev: &str) -> [u64; 2] {
+		let mut network_bytes = [0, 0];
+		// The first two lines are headers.
+		for line in net_dev.lines().skip(2) {
+			let (interface, counters) = match line.split_once(':') {
+				Some(interface_and_counters) => interface_and_counters,
+				None => continue,
+			};
+			if interface.trim() == "lo" {
+				continue;
+			}
+			let counters = counters.split_whitespace().collect::<Vec<_>>();
+			// Received bytes are the first counter and sent bytes are the ninth.
+			if let (Some(received), Some(sent)) = (counters.first(), counters.get(8)) {
+				network_bytes[0] += received.parse::<u64>().unwrap_or(0);
+				network_bytes[1] += sent.parse::<u64>().unwrap_or(0);
+			}
+		}
+		network_bytes
+	}
+
+	fn disk_usage(path: &std::path::Path) -> Option<DiskUsage> {
+		let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+		let mut file_system_stats: libc::statvfs = unsafe { std::mem::zeroed() };
+		if unsafe { libc::statvfs(c_path.as_ptr(), &mut file_system_stats) } != 0 {
+			return None;
+		}
+		let fragment_size = file_system_stats.f_frsize as u64;
+		let total = file_system_stats.f_blocks as u64 * fragment_size;
+		let free = file_system_stats.f_bfree as u64 * fragment_size;
+		Some(DiskUsage {
+			path: path.to_owned(),
+			used: total.saturating_sub(free),
+			total,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SamplingState;
+
+	const STAT: &str = "\
+cpu  400 100 300 1000 200 0 0 0 500 0
+cpu0 300 0 100 500 100 0 0 0 500 0
+cpu1 100 100 200 500 100 0 0 0 0 0
+intr 12345 0 0
+ctxt 67890
+";
+
+	const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 9000000   100    0    0    0     0          0         0  9000000   100    0    0    0     0       0          0
+  eth0:    1000    10    0    0    0     0          0         0      200     2    0    0    0     0       0          0
+ wlan0:     500     5    0    0    0     0          0         0      300     3    0    0    0     0       0          0
+";
+
+	/**
+	 * A directory that stands in for /proc, which is removed once the test is done with it.
+	 */
+	struct ProcfsRoot(std::path::PathBuf);
+
+	impl ProcfsRoot {
+		fn new(name: &str) -> Self {
+			let path = std::env::temp_dir().join(format!(
+				"system-stats-procfs-{}-{}",
+				std::process::id(),
+				name
+			));
+			std::fs::create_dir_all(path.join("net")).unwrap();
+			Self(path)
+		}
+
+		fn write(&self, file_name: &str, contents: &str) {
+			std::fs::write(self.0.join(file_name), contents).unwrap();
+		}
+	}
+
+	impl Drop for ProcfsRoot {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_dir_all(&self.0);
+		}
+	}
+
+	#[test]
+	fn parses_total_and_per_core_cpu_times() {
+		let cpu_times = SamplingState::parse_cpu_times(STAT)
+			.iter()
+			.map(|cpu_times| (cpu_times.busy, cpu_times.total))
+			.collect::<Vec<_>>();
+		// The guest time in the ninth column isn't added again, and idle and iowait don't count as busy.
+		assert_eq!(cpu_times, vec![(800, 2000), (400, 1000), (400, 1000)]);
+	}
+
+	#[test]
+	fn parses_memory_with_available_memory() {
+		let meminfo =
+			"MemTotal:       16000 kB\nMemFree:         2000 kB\nMemAvailable:    6000 kB\n";
+		assert_eq!(
+			SamplingState::parse_memory(meminfo),
+			(10000 * 1024, 16000 * 1024)
+		);
+	}
+
+	#[test]
+	fn parses_memory_without_available_memory() {
+		// Kernels from before MemAvailable was added only have MemFree.
+		let meminfo =
+			"MemTotal:       16000 kB\nMemFree:         2000 kB\nBuffers:          500 kB\n";
+		assert_eq!(
+			SamplingState::parse_memory(meminfo),
+			(14000 * 1024, 16000 * 1024)
+		);
+	}
+
+	#[test]
+	fn parses_network_bytes_without_loopback() {
+		assert_eq!(SamplingState::parse_network_bytes(NET_DEV), [1500, 500]);
+	}
+
+	#[test]
+	fn samples_loads_and_rates_from_advancing_counters() {
+		let procfs_root = ProcfsRoot::new("sample");
+		procfs_root.write("stat", STAT);
+		procfs_root.write("net/dev", NET_DEV);
+		procfs_root.write(
+			"meminfo",
+			"MemTotal:       16000 kB\nMemAvailable:    6000 kB\n",
+		);
+		procfs_root.write("loadavg", "0.50 1.25 2.00 1/234 5678\n");
+		let mut sampling_state = SamplingState::new(procfs_root.0.clone(), Vec::new());
+		sampling_state.read_counters();
+
+		// Over the interval, the first core is busy for half of its time and the second for all of it.
+		procfs_root.write(
+			"stat",
+			"\
+cpu  550 100 300 1050 200 0 0 0 500 0
+cpu0 350 0 100 550 100 0 0 0 500 0
+cpu1 200 100 200 500 100 0 0 0 0 0
+",
+		);
+		procfs_root.write(
+			"net/dev",
+			&NET_DEV
+				.replace("  eth0:    1000", "  eth0:    5000")
+				.replace("      200     2", "     1200     2"),
+		);
+		sampling_state.instant -= std::time::Duration::from_secs(2);
+		let system_stats = sampling_state.sample();
+
+		assert_eq!(system_stats.core_loads, vec![0.5, 1.0]);
+		assert!((system_stats.total_cpu_load - 0.75).abs() < 1e-6);
+		assert_eq!(system_stats.memory_used, 10000 * 1024);
+		assert_eq!(system_stats.memory_total, 16000 * 1024);
+		assert_eq!(system_stats.load_average, [0.5, 1.25, 2.0]);
+		// The rates are a little under the change over 2 seconds, since the sample also took some time.
+		assert!((1900.0..=2000.0).contains(&system_stats.network_receive_rate));
+		assert!((475.0..=500.0).contains(&system_stats.network_transmit_rate));
+	}
+
+	#[test]
+	fn samples_nothing_from_missing_files() {
+		let procfs_root = ProcfsRoot::new("missing");
+		let mut sampling_state = SamplingState::new(procfs_root.0.clone(), Vec::new());
+		sampling_state.read_counters();
+		let system_stats = sampling_state.sample();
+		assert!(system_stats.core_loads.is_empty());
+		assert_eq!(system_stats.total_cpu_load, 0.0);
+		assert_eq!(system_stats.memory_total, 0);
+		assert_eq!(system_stats.network_receive_rate, 0.0);
+	}
+}
@@ -0,0 +1,23 @@
+struct Boid {
+	// Only the xyz components are used, the w components pad the vectors to their alignment.
+	position: vec4<f32>,
+	velocity: vec4<f32>,
+};
+
+// Get the rotation that turns the mesh of a boid, which points along z with its fin along y, so that it points along
+// the boid's velocity with its fin pointing towards the ceiling of the room.
+fn boid_rotation(boid: Boid) -> mat3x3<f32> {
+	let speed = length(boid.velocity.xyz);
+	var forward = vec3<f32>(0.0, 0.0, 1.0);
+	if (speed > 0.0) {
+		forward = boid.velocity.xyz / speed;
+	}
+	// The ceiling of the room is towards negative y.
+	var up_reference = vec3<f32>(0.0, -1.0, 0.0);
+	if (abs(forward.y) > 0.99) {
+		up_reference = vec3<f32>(1.0, 0.0, 0.0);
+	}
+	let right = normalize(cross(up_reference, forward));
+	let up = cross(forward, right);
+	return mat3x3<f32>(right, up, forward);
+}
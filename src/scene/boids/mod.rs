@@ -0,0 +1,840 @@
+use rand::Rng;
+use wgpu::util::DeviceExt;
+
+/**
+ * The number of boids or cells that each workgroup of the simulation shader handles, apart from the single workgroup
+ * that scans the cells.
+ */
+const WORKGROUP_SIZE: u32 = 64;
+
+/**
+ * The most cells that the spatial grid can have along each axis, which keeps the grid small when the perception radius
+ * is tiny compared to the room.
+ */
+const MAXIMUM_GRID_SIZE: u32 = 64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Boid {
+	position: [f32; 4],
+	velocity: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct BoidVertex {
+	position: [f32; 3],
+	normal: [f32; 3],
+}
+
+impl BoidVertex {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+		wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Vertex,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SimulationPushConstants {
+	bounds: [f32; 3],
+	boid_count: u32,
+	grid_size: [u32; 3],
+	cell_size: f32,
+	perception_radius: f32,
+	separation_distance: f32,
+	separation_weight: f32,
+	alignment_weight: f32,
+	cohesion_weight: f32,
+	minimum_speed: f32,
+	maximum_speed: f32,
+	dt: f32,
+}
+
+/**
+ * The grid that boids are sorted into every frame so that each boid only has to look at the boids in the cells around
+ * its own to find its neighbors.
+ */
+struct SpatialGrid {
+	grid_size: [u32; 3],
+	cell_size: f32,
+	bind_group: wgpu::BindGroup,
+}
+
+impl SpatialGrid {
+	/**
+	 * Create a grid spanning the room with cells that are at least as large as the perception radius.
+	 */
+	fn new(
+		device: &wgpu::Device,
+		bind_group_layout: &wgpu::BindGroupLayout,
+		bounds: [f32; 3],
+		boids_configuration: &crate::config::BoidsConfig,
+	) -> Self {
+		let largest_bound = bounds.iter().copied().fold(0.0, f32::max);
+		let cell_size = boids_configuration
+			.perception_radius
+			.max(2.0 * largest_bound / MAXIMUM_GRID_SIZE as f32);
+		let grid_size = bounds.map(|bound| ((2.0 * bound / cell_size).ceil() as u32).max(1));
+		let cell_count = grid_size.iter().product::<u32>();
+
+		let create_buffer = |name: &str, element_size: usize, element_count: u32| {
+			device.create_buffer(&wgpu::BufferDescriptor {
+				label: Some(&format!("Boids scene {} buffer", name)),
+				size: (element_size * element_count.max(1) as usize) as wgpu::BufferAddress,
+				usage: wgpu::BufferUsages::STORAGE,
+				mapped_at_creation: false,
+			})
+		};
+		let buffers = [
+			create_buffer("cell count", std::mem::size_of::<u32>(), cell_count),
+			create_buffer("cell start", std::mem::size_of::<u32>(), cell_count),
+			create_buffer(
+				"boid cell",
+				std::mem::size_of::<[u32; 2]>(),
+				boids_configuration.boid_count,
+			),
+			create_buffer(
+				"sorted boid index",
+				std::mem::size_of::<u32>(),
+				boids_configuration.boid_count,
+			),
+		];
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Boids scene spatial grid bind group"),
+			layout: bind_group_layout,
+			entries: &[0, 1, 2, 3].map(|binding| wgpu::BindGroupEntry {
+				binding,
+				resource: buffers[binding as usize].as_entire_binding(),
+			}),
+		});
+		Self {
+			grid_size,
+			cell_size,
+			bind_group,
+		}
+	}
+
+	fn cell_count(&self) -> u32 {
+		self.grid_size.iter().product()
+	}
+}
+
+/**
+ * Simulates a flock of boids on the GPU inside the room of the bouncing cube scene. Every boid steers away from
+ * neighbors that are too close, towards the heading of its neighbors and towards their center, and each of these
+ * rules is weighted by the configuration. The boids are drawn as darts that point where they're heading, lit and
+ * shadowed by the same lights as the bouncing cube.
+ */
+pub struct BoidsScene {
+	boids_configuration: crate::config::BoidsConfig,
+	room_model: crate::scene::bouncing_cube::bouncing_cube_model::BouncingCubeSceneInformation,
+	simulation_push_constants: SimulationPushConstants,
+	boid_buffers: crate::scene::utilities::compute::PingPongBuffers,
+	spatial_grid_bind_group_layout: wgpu::BindGroupLayout,
+	spatial_grid: SpatialGrid,
+	clear_cells_pipeline: wgpu::ComputePipeline,
+	count_boids_pipeline: wgpu::ComputePipeline,
+	scan_cells_pipeline: wgpu::ComputePipeline,
+	scatter_boids_pipeline: wgpu::ComputePipeline,
+	simulation_pipeline: wgpu::ComputePipeline,
+	boid_vertex_buffer: wgpu::Buffer,
+	boid_vertex_count: u32,
+	/**
+	 * Bind groups that let the render pipelines read either of the boid buffers.
+	 */
+	boid_render_bind_groups: [wgpu::BindGroup; 2],
+	boid_render_pipeline: wgpu::RenderPipeline,
+	boid_shadow_pipeline: wgpu::RenderPipeline,
+	quad_transforms: [glam::Mat4; 11],
+	quad_vertex_buffer: wgpu::Buffer,
+	quad_index_buffer: wgpu::Buffer,
+	wall_instance_buffer: wgpu::Buffer,
+	wall_render_pipeline: wgpu::RenderPipeline,
+	audio_uniform_buffer: wgpu::Buffer,
+	audio_bind_group: wgpu::BindGroup,
+	audio_uniforms: crate::scene::utilities::audio::AudioUniforms,
+	camera_uniform_buffer: wgpu::Buffer,
+	camera_bind_group: wgpu::BindGroup,
+	light_information_buffer: wgpu::Buffer,
+	light_information_bind_group: wgpu::BindGroup,
+	shadow_map_texture_views: Vec<wgpu::TextureView>,
+	shadow_map_bind_group: wgpu::BindGroup,
+	multisampled_color_target: crate::scene::utilities::render_target::MultisampledColorTarget,
+	depth_texture: crate::scene::utilities::texture::Texture,
+}
+
+impl BoidsScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		boids_configuration: &crate::config::BoidsConfig,
+	) -> Self {
+		// The room, its camera and its lights come from the bouncing cube scene, whose cube is simply never drawn.
+		let room_model =
+			crate::scene::bouncing_cube::bouncing_cube_model::BouncingCubeSceneInformation::new(
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+			);
+		let bounds = room_model.scene_bounds;
+
+		// Scatter the boids around the room, heading in random directions.
+		let mut random_number_generator = rand::thread_rng();
+		let boids = (0..boids_configuration.boid_count)
+			.map(|_| {
+				let position = bounds
+					.map(|bound| random_number_generator.gen_range(-0.8 * bound..=0.8 * bound));
+				let direction = glam::Vec3::new(
+					random_number_generator.gen_range(-1.0..=1.0),
+					random_number_generator.gen_range(-1.0..=1.0),
+					random_number_generator.gen_range(-1.0..=1.0),
+				)
+				.normalize_or_zero();
+				let velocity = direction
+					* random_number_generator.gen_range(
+						boids_configuration.minimum_speed..=boids_configuration.maximum_speed,
+					);
+				Boid {
+					position: [position[0], position[1], position[2], 0.0],
+					velocity: [velocity.x, velocity.y, velocity.z, 0.0],
+				}
+			})
+			.collect::<Vec<_>>();
+		let boid_buffers = crate::scene::utilities::compute::PingPongBuffers::new(
+			device,
+			"Boids scene boid",
+			bytemuck::cast_slice(&boids),
+			wgpu::BufferUsages::empty(),
+		);
+
+		// Create the spatial grid and the pipelines that sort boids into it and then simulate them.
+		let storage_buffer_layout_entry = |binding| wgpu::BindGroupLayoutEntry {
+			binding,
+			visibility: wgpu::ShaderStages::COMPUTE,
+			ty: wgpu::BindingType::Buffer {
+				ty: wgpu::BufferBindingType::Storage { read_only: false },
+				has_dynamic_offset: false,
+				min_binding_size: None,
+			},
+			count: None,
+		};
+		let spatial_grid_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Boids scene spatial grid bind group layout"),
+				entries: &[0, 1, 2, 3].map(storage_buffer_layout_entry),
+			});
+		let spatial_grid = SpatialGrid::new(
+			device,
+			&spatial_grid_bind_group_layout,
+			bounds,
+			boids_configuration,
+		);
+		let simulation_shader_module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+			label: Some("Boids scene simulation shader"),
+			source: wgpu::ShaderSource::Wgsl(
+				(include_str!("boid.wgsl").to_owned() + include_str!("simulate.wgsl")).into(),
+			),
+		});
+		let simulation_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Boids scene simulation pipeline layout"),
+				bind_group_layouts: &[
+					boid_buffers.bind_group_layout(),
+					&spatial_grid_bind_group_layout,
+				],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::COMPUTE,
+					range: 0..std::mem::size_of::<SimulationPushConstants>() as u32,
+				}],
+			});
+		let [clear_cells_pipeline, count_boids_pipeline, scan_cells_pipeline, scatter_boids_pipeline, simulation_pipeline] =
+			[
+				"clear_cells",
+				"count_boids",
+				"scan_cells",
+				"scatter_boids",
+				"simulate",
+			]
+			.map(|entry_point| {
+				crate::scene::utilities::compute::create_compute_pipeline(
+					device,
+					&format!("Boids scene {} pipeline", entry_point),
+					&simulation_shader_module,
+					entry_point,
+					&simulation_pipeline_layout,
+				)
+			});
+
+		// Create the buffers and bind groups shared by everything that is drawn in the room.
+		let (camera_uniform_buffer, camera_bind_group_layout, camera_bind_group) = room_model
+			.scene_camera
+			.create_bind_group(device, "Boids scene");
+		let (
+			light_information_buffer,
+			light_information_bind_group_layout,
+			light_information_bind_group,
+		) = crate::scene::bouncing_cube::create_light_information_bind_group(device, "Boids scene");
+		let (shadow_map, shadow_map_texture_views) = crate::scene::bouncing_cube::create_shadow_map(
+			device,
+			room_model.lights.len() as u32,
+			"Boids scene",
+		);
+		let (shadow_map_bind_group_layout, shadow_map_bind_group) = shadow_map.create_bind_group(
+			device,
+			"Boids scene shadow",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let lit_push_constant_ranges = [wgpu::PushConstantRange {
+			stages: wgpu::ShaderStages::FRAGMENT,
+			range: 0..std::mem::size_of::<crate::scene::bouncing_cube::PushConstantData>() as u32,
+		}];
+		let depth_stencil_state = wgpu::DepthStencilState {
+			format: crate::scene::utilities::texture::Texture::DEPTH_FORMAT,
+			depth_write_enabled: true,
+			depth_compare: wgpu::CompareFunction::Less,
+			stencil: wgpu::StencilState::default(),
+			bias: wgpu::DepthBiasState::default(),
+		};
+		let color_target_states = [wgpu::ColorTargetState {
+			format: render_target_configuration.format,
+			blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+			write_mask: wgpu::ColorWrites::all(),
+		}];
+
+		// Create the pipeline for the walls of the room, which are drawn the same way as in the bouncing cube scene.
+		let quad_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("Boids scene quad vertex buffer"),
+			contents: bytemuck::cast_slice(&[
+				[-0.5f32, 0.5],
+				[-0.5, -0.5],
+				[0.5, -0.5],
+				[0.5, 0.5],
+			]),
+			usage: wgpu::BufferUsages::VERTEX,
+		});
+		let quad_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("Boids scene quad index buffer"),
+			contents: bytemuck::cast_slice(&[0u16, 1, 2, 0, 2, 3]),
+			usage: wgpu::BufferUsages::INDEX,
+		});
+		let wall_instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Boids scene wall instance buffer"),
+			size: (room_model.wall_quads.len()
+				* std::mem::size_of::<crate::scene::bouncing_cube::InstanceData>())
+				as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::VERTEX,
+			mapped_at_creation: false,
+		});
+		let (audio_uniform_buffer, audio_bind_group_layout, audio_bind_group) =
+			crate::scene::utilities::audio::create_audio_bind_group(
+				device,
+				"Boids scene",
+				wgpu::ShaderStages::VERTEX,
+			);
+		let wall_shader_module = crate::scene::bouncing_cube::create_lit_shader_module(
+			device,
+			"Boids scene wall shader",
+			&crate::scene::bouncing_cube::quad_vertex_source(),
+		);
+		let wall_render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Boids scene wall pipeline layout"),
+				bind_group_layouts: &[
+					&camera_bind_group_layout,
+					&light_information_bind_group_layout,
+					&shadow_map_bind_group_layout,
+					&audio_bind_group_layout,
+				],
+				push_constant_ranges: &lit_push_constant_ranges,
+			});
+		let wall_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("Boids scene wall pipeline"),
+			layout: Some(&wall_render_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &wall_shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[
+					crate::scene::bouncing_cube::QuadVertex::vertex_buffer_layout(),
+					crate::scene::bouncing_cube::InstanceData::vertex_buffer_layout(),
+				],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: &wall_shader_module,
+				entry_point: "fragment_stage",
+				targets: &color_target_states,
+			}),
+			primitive: wgpu::PrimitiveState::default(),
+			depth_stencil: Some(depth_stencil_state.clone()),
+			multisample: render_target_configuration.multisample_state(),
+			multiview: None,
+		});
+
+		// Create the pipelines for the boids, which read their positions and velocities straight from the simulation.
+		let boid_vertices = Self::boid_vertices(boids_configuration.boid_size);
+		let boid_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("Boids scene boid vertex buffer"),
+			contents: bytemuck::cast_slice(&boid_vertices),
+			usage: wgpu::BufferUsages::VERTEX,
+		});
+		let boid_render_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Boids scene boid render bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::VERTEX,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Storage { read_only: true },
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let boid_render_bind_groups = [0, 1].map(|buffer_index| {
+			device.create_bind_group(&wgpu::BindGroupDescriptor {
+				label: Some(&format!(
+					"Boids scene boid render bind group {}",
+					buffer_index
+				)),
+				layout: &boid_render_bind_group_layout,
+				entries: &[wgpu::BindGroupEntry {
+					binding: 0,
+					resource: boid_buffers.buffers()[buffer_index].as_entire_binding(),
+				}],
+			})
+		});
+		let boid_shader_module = crate::scene::bouncing_cube::create_lit_shader_module(
+			device,
+			"Boids scene boid shader",
+			&(include_str!("boid.wgsl").to_owned() + include_str!("render.wgsl")),
+		);
+		let boid_render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Boids scene boid pipeline layout"),
+				bind_group_layouts: &[
+					&camera_bind_group_layout,
+					&light_information_bind_group_layout,
+					&shadow_map_bind_group_layout,
+					&boid_render_bind_group_layout,
+				],
+				push_constant_ranges: &lit_push_constant_ranges,
+			});
+		let boid_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("Boids scene boid pipeline"),
+			layout: Some(&boid_render_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &boid_shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[BoidVertex::vertex_buffer_layout()],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: &boid_shader_module,
+				entry_point: "fragment_stage",
+				targets: &color_target_states,
+			}),
+			primitive: wgpu::PrimitiveState::default(),
+			depth_stencil: Some(depth_stencil_state),
+			multisample: render_target_configuration.multisample_state(),
+			multiview: None,
+		});
+		let boid_shadow_shader_module =
+			device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+				label: Some("Boids scene boid shadow shader"),
+				source: wgpu::ShaderSource::Wgsl(
+					(include_str!("boid.wgsl").to_owned() + include_str!("shadow.wgsl")).into(),
+				),
+			});
+		let boid_shadow_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Boids scene boid shadow pipeline layout"),
+				bind_group_layouts: &[&boid_render_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::VERTEX,
+					range: 0
+						..std::mem::size_of::<crate::scene::bouncing_cube::ShadowPushConstantData>()
+							as u32,
+				}],
+			});
+		let boid_shadow_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("Boids scene boid shadow pipeline"),
+			layout: Some(&boid_shadow_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &boid_shadow_shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[BoidVertex::vertex_buffer_layout()],
+			},
+			fragment: None,
+			primitive: wgpu::PrimitiveState {
+				unclipped_depth: true,
+				..wgpu::PrimitiveState::default()
+			},
+			depth_stencil: Some(wgpu::DepthStencilState {
+				format: crate::scene::utilities::texture::Texture::DEPTH_FORMAT,
+				depth_write_enabled: true,
+				depth_compare: wgpu::CompareFunction::Less,
+				stencil: wgpu::StencilState::default(),
+				bias: wgpu::DepthBiasState {
+					constant: 2,
+					slope_scale: 2.0,
+					clamp: 0.0,
+				},
+			}),
+			multisample: wgpu::MultisampleState::default(),
+			multiview: None,
+		});
+
+		let multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Boids scene",
+			);
+		let depth_texture = crate::scene::utilities::texture::Texture::create_depth_texture(
+			device,
+			render_target_configuration.width,
+			render_target_configuration.height,
+			render_target_configuration.sample_count,
+			"Boids scene",
+		);
+
+		Self {
+			boids_configuration: boids_configuration.clone(),
+			room_model,
+			simulation_push_constants: SimulationPushConstants {
+				bounds,
+				boid_count: boids_configuration.boid_count,
+				grid_size: spatial_grid.grid_size,
+				cell_size: spatial_grid.cell_size,
+				perception_radius: boids_configuration.perception_radius,
+				separation_distance: boids_configuration.separation_distance,
+				separation_weight: boids_configuration.separation_weight,
+				alignment_weight: boids_configuration.alignment_weight,
+				cohesion_weight: boids_configuration.cohesion_weight,
+				minimum_speed: boids_configuration.minimum_speed,
+				maximum_speed: boids_configuration.maximum_speed,
+				dt: 0.0,
+			},
+			boid_buffers,
+			spatial_grid_bind_group_layout,
+			spatial_grid,
+			clear_cells_pipeline,
+			count_boids_pipeline,
+			scan_cells_pipeline,
+			scatter_boids_pipeline,
+			simulation_pipeline,
+			boid_vertex_buffer,
+			boid_vertex_count: boid_vertices.len() as u32,
+			boid_render_bind_groups,
+			boid_render_pipeline,
+			boid_shadow_pipeline,
+			quad_transforms: crate::scene::bouncing_cube::quad_transforms(),
+			quad_vertex_buffer,
+			quad_index_buffer,
+			wall_instance_buffer,
+			wall_render_pipeline,
+			audio_uniform_buffer,
+			audio_bind_group,
+			audio_uniforms: crate::scene::utilities::audio::AudioUniforms::default(),
+			camera_uniform_buffer,
+			camera_bind_group,
+			light_information_buffer,
+			light_information_bind_group,
+			shadow_map_texture_views,
+			shadow_map_bind_group,
+			multisampled_color_target,
+			depth_texture,
+		}
+	}
+
+	/**
+	 * Make the flat shaded triangles of a dart that points along z with a fin along y, scaled to the given length.
+	 */
+	fn boid_vertices(boid_size: f32) -> Vec<BoidVertex> {
+		let tip = glam::Vec3::new(0.0, 0.0, 0.6);
+		let left = glam::Vec3::new(-0.3, 0.0, -0.4);
+		let right = glam::Vec3::new(0.3, 0.0, -0.4);
+		let fin = glam::Vec3::new(0.0, 0.2, -0.4);
+		let center = (tip + left + right + fin) / 4.0;
+		[
+			[tip, left, fin],
+			[tip, fin, right],
+			[tip, right, left],
+			[left, right, fin],
+		]
+		.iter()
+		.flat_map(|&triangle| {
+			// Wind every triangle so that its normal points away from the center of the dart.
+			let mut normal = (triangle[1] - triangle[0])
+				.cross(triangle[2] - triangle[0])
+				.normalize();
+			if normal.dot(triangle[0] - center) < 0.0 {
+				normal = -normal;
+			}
+			triangle.map(|corner| BoidVertex {
+				position: (corner * boid_size).into(),
+				normal: normal.into(),
+			})
+		})
+		.collect()
+	}
+}
+
+impl crate::scene::Scene for BoidsScene {
+	fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.room_model.resize(
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		);
+		// The room gets wider or narrower, so the grid has to be remade to cover it.
+		self.spatial_grid = SpatialGrid::new(
+			device,
+			&self.spatial_grid_bind_group_layout,
+			self.room_model.scene_bounds,
+			&self.boids_configuration,
+		);
+		self.simulation_push_constants.bounds = self.room_model.scene_bounds;
+		self.simulation_push_constants.grid_size = self.spatial_grid.grid_size;
+		self.simulation_push_constants.cell_size = self.spatial_grid.cell_size;
+		self.multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Boids scene",
+			);
+		self.depth_texture = crate::scene::utilities::texture::Texture::create_depth_texture(
+			device,
+			render_target_configuration.width,
+			render_target_configuration.height,
+			render_target_configuration.sample_count,
+			"Boids scene",
+		);
+	}
+
+	fn update(&mut self, dt: f32) {
+		self.room_model.update(dt);
+		// Long frames are shortened so that boids don't tunnel through each other or the walls.
+		self.simulation_push_constants.dt = dt.min(0.05);
+	}
+
+	fn uses_bloom(&self) -> bool {
+		true
+	}
+
+	fn audio_changed(&mut self, audio_features: &crate::audio::AudioFeatures) {
+		self.audio_uniforms = crate::scene::utilities::audio::AudioUniforms::new(audio_features);
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		// Sort the boids into the grid and then move them.
+		let boid_workgroup_count = crate::scene::utilities::compute::workgroup_count(
+			self.boids_configuration.boid_count,
+			WORKGROUP_SIZE,
+		);
+		let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+			label: Some("Boids scene simulation compute pass"),
+		});
+		compute_pass.set_bind_group(0, self.boid_buffers.bind_group(), &[]);
+		compute_pass.set_bind_group(1, &self.spatial_grid.bind_group, &[]);
+		for (pipeline, workgroup_count) in [
+			(
+				&self.clear_cells_pipeline,
+				crate::scene::utilities::compute::workgroup_count(
+					self.spatial_grid.cell_count(),
+					WORKGROUP_SIZE,
+				),
+			),
+			(&self.count_boids_pipeline, boid_workgroup_count),
+			(&self.scan_cells_pipeline, 1),
+			(&self.scatter_boids_pipeline, boid_workgroup_count),
+			(&self.simulation_pipeline, boid_workgroup_count),
+		] {
+			compute_pass.set_pipeline(pipeline);
+			compute_pass.set_push_constants(0, bytemuck::bytes_of(&self.simulation_push_constants));
+			compute_pass.dispatch(workgroup_count, 1, 1);
+		}
+		drop(compute_pass);
+		self.boid_buffers.swap();
+		let boid_render_bind_group =
+			&self.boid_render_bind_groups[self.boid_buffers.current_index()];
+
+		// Write uniforms and the walls, which change size along with the room.
+		let wall_instance_data = self.quad_transforms[6..]
+			.iter()
+			.zip(&self.room_model.wall_quads)
+			.map(|(quad_transform, quad_data)| {
+				let model_transform =
+					glam::Mat4::from_scale(glam::Vec3::from_slice(&self.room_model.scene_bounds))
+						* *quad_transform;
+				crate::scene::bouncing_cube::InstanceData {
+					shininess: quad_data.shininess,
+					ambient_color: quad_data.ambient_color,
+					diffuse_color: quad_data.diffuse_color,
+					specular_color: quad_data.specular_color,
+					object_transform: model_transform.to_cols_array_2d(),
+					normal_transform: model_transform.inverse().transpose().to_cols_array_2d(),
+				}
+			})
+			.collect::<Vec<_>>();
+		queue.write_buffer(
+			&self.wall_instance_buffer,
+			0,
+			bytemuck::cast_slice(&wall_instance_data),
+		);
+		queue.write_buffer(
+			&self.audio_uniform_buffer,
+			0,
+			bytemuck::bytes_of(&self.audio_uniforms),
+		);
+		queue.write_buffer(
+			&self.camera_uniform_buffer,
+			0,
+			bytemuck::bytes_of(&self.room_model.scene_camera.transformation),
+		);
+		let shadow_map_transforms = self
+			.room_model
+			.lights
+			.iter()
+			.map(crate::scene::bouncing_cube::shadow_map_transforms)
+			.collect::<Vec<_>>();
+		queue.write_buffer(
+			&self.light_information_buffer,
+			0,
+			bytemuck::cast_slice(&crate::scene::bouncing_cube::light_information_data(
+				&self.room_model.lights,
+				&shadow_map_transforms,
+			)),
+		);
+
+		// Render the shadows that the boids cast. The walls are left out since nothing is behind them.
+		for (i, light_transforms) in shadow_map_transforms.iter().enumerate() {
+			for (j, light_transform) in light_transforms.iter().enumerate() {
+				let mut render_pass =
+					command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+						label: Some("Boids scene shadow render pass"),
+						color_attachments: &[],
+						depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+							view: &self.shadow_map_texture_views[i * 6 + j],
+							depth_ops: Some(wgpu::Operations {
+								load: wgpu::LoadOp::Clear(1.0),
+								store: true,
+							}),
+							stencil_ops: None,
+						}),
+					});
+				render_pass.set_pipeline(&self.boid_shadow_pipeline);
+				render_pass.set_vertex_buffer(0, self.boid_vertex_buffer.slice(..));
+				render_pass.set_bind_group(0, boid_render_bind_group, &[]);
+				render_pass.set_push_constants(
+					wgpu::ShaderStages::VERTEX,
+					0,
+					bytemuck::bytes_of(&crate::scene::bouncing_cube::ShadowPushConstantData {
+						light_transform: *light_transform,
+					}),
+				);
+				render_pass.draw(
+					0..self.boid_vertex_count,
+					0..self.boids_configuration.boid_count,
+				);
+			}
+		}
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Boids scene render pass"),
+			color_attachments: &[self.multisampled_color_target.color_attachment(
+				output_texture_view,
+				wgpu::LoadOp::Clear(wgpu::Color {
+					r: 0.5,
+					g: 0.5,
+					b: 0.5,
+					a: 1.0,
+				}),
+			)],
+			depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+				view: &self.depth_texture.texture_view,
+				depth_ops: Some(wgpu::Operations {
+					load: wgpu::LoadOp::Clear(1.0),
+					store: true,
+				}),
+				stencil_ops: None,
+			}),
+		});
+		let push_constant_data = crate::scene::bouncing_cube::PushConstantData {
+			camera_position: self.room_model.scene_camera.position.into(),
+		};
+		render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+		render_pass.set_bind_group(1, &self.light_information_bind_group, &[]);
+		render_pass.set_bind_group(2, &self.shadow_map_bind_group, &[]);
+
+		render_pass.set_pipeline(&self.wall_render_pipeline);
+		render_pass.set_bind_group(3, &self.audio_bind_group, &[]);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&push_constant_data),
+		);
+		render_pass.set_vertex_buffer(0, self.quad_vertex_buffer.slice(..));
+		render_pass.set_vertex_buffer(1, self.wall_instance_buffer.slice(..));
+		render_pass.set_index_buffer(self.quad_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+		render_pass.draw_indexed(0..6, 0, 0..self.room_model.wall_quads.len() as u32);
+
+		render_pass.set_pipeline(&self.boid_render_pipeline);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&push_constant_data),
+		);
+		render_pass.set_bind_group(3, boid_render_bind_group, &[]);
+		render_pass.set_vertex_buffer(0, self.boid_vertex_buffer.slice(..));
+		render_pass.draw(
+			0..self.boid_vertex_count,
+			0..self.boids_configuration.boid_count,
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	/**
+	 * Parse and validate a shader the way that creating its module does, so that shaders pieced together from the
+	 * sources of other scenes can't quietly stop fitting together.
+	 */
+	fn validate_shader(source: &str) {
+		let module = naga::front::wgsl::parse_str(source)
+			.unwrap_or_else(|error| panic!("{}", error.emit_to_string(source)));
+		naga::valid::Validator::new(
+			naga::valid::ValidationFlags::all(),
+			naga::valid::Capabilities::PUSH_CONSTANT,
+		)
+		.validate(&module)
+		.unwrap();
+	}
+
+	#[test]
+	fn wall_shader_is_valid() {
+		validate_shader(&crate::scene::bouncing_cube::lit_shader_source(
+			&crate::scene::bouncing_cube::quad_vertex_source(),
+		));
+	}
+
+	#[test]
+	fn boid_shader_is_valid() {
+		validate_shader(&crate::scene::bouncing_cube::lit_shader_source(
+			&(include_str!("boid.wgsl").to_owned() + include_str!("render.wgsl")),
+		));
+	}
+}
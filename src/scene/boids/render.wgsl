@@ -0,0 +1,38 @@
+struct VertexInput {
+	@location(0) position: vec3<f32>,
+	@location(1) normal: vec3<f32>,
+};
+
+struct Transform {
+	transformation: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera_transform: Transform;
+
+@group(3) @binding(0)
+var<storage, read> boids: array<Boid>;
+
+// Get a fully saturated color from a hue between 0 and 1.
+fn hue_color(hue: f32) -> vec3<f32> {
+	return clamp(abs(fract(hue + vec3<f32>(0.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0) - 1.0, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@vertex
+fn vertex_stage(vertex: VertexInput, @builtin(instance_index) instance_index: u32) -> FragmentInput {
+	let boid = boids[instance_index];
+	let rotation = boid_rotation(boid);
+	let world_position = vec4<f32>(boid.position.xyz + rotation * vertex.position, 1.0);
+
+	// Color boids by the direction they're heading in so that flocks stand out from each other.
+	let forward = rotation[2];
+	let color = mix(vec3<f32>(1.0), hue_color(atan2(forward.z, forward.x) / 6.28318530718 + 0.5), 0.8);
+	return FragmentInput(
+		camera_transform.transformation * world_position,
+		world_position,
+		vec4<f32>(rotation * vertex.normal, 0.0),
+		0.3,
+		color,
+		color,
+		vec3<f32>(0.5),
+	);
+}
@@ -0,0 +1,20 @@
+struct VertexInput {
+	@location(0) position: vec3<f32>,
+	@location(1) normal: vec3<f32>,
+};
+
+struct PushConstantData {
+	light_transform: mat4x4<f32>,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var<storage, read> boids: array<Boid>;
+
+@vertex
+fn vertex_stage(vertex: VertexInput, @builtin(instance_index) instance_index: u32) -> @builtin(position) vec4<f32> {
+	let boid = boids[instance_index];
+	let world_position = boid.position.xyz + boid_rotation(boid) * vertex.position;
+	return push_constant_data.light_transform * vec4<f32>(world_position, 1.0);
+}
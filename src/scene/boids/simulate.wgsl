@@ -0,0 +1,199 @@
+struct SimulationPushConstants {
+	bounds: vec3<f32>,
+	boid_count: u32,
+	grid_size: vec3<u32>,
+	cell_size: f32,
+	perception_radius: f32,
+	separation_distance: f32,
+	separation_weight: f32,
+	alignment_weight: f32,
+	cohesion_weight: f32,
+	minimum_speed: f32,
+	maximum_speed: f32,
+	dt: f32,
+};
+
+var<push_constant> push_constants: SimulationPushConstants;
+
+@group(0) @binding(0)
+var<storage, read> source_boids: array<Boid>;
+@group(0) @binding(1)
+var<storage, read_write> destination_boids: array<Boid>;
+
+// The number of boids in each cell of the grid.
+@group(1) @binding(0)
+var<storage, read_write> cell_counts: array<atomic<u32>>;
+// The index into the sorted indices where the boids of each cell start.
+@group(1) @binding(1)
+var<storage, read_write> cell_starts: array<u32>;
+// The cell that each boid is in, along with where it is among the boids of that cell.
+@group(1) @binding(2)
+var<storage, read_write> boid_cells: array<vec2<u32>>;
+// The indices of the boids, sorted so that the boids of each cell are next to each other.
+@group(1) @binding(3)
+var<storage, read_write> sorted_boid_indices: array<u32>;
+
+// Neighbors past this many are ignored so that very dense flocks don't slow the simulation to a crawl.
+let maximum_neighbor_count = 64u;
+// How close to a wall boids start turning away from it.
+let wall_avoidance_distance = 0.2;
+// How quickly boids turn towards where the rules want them to go, per second.
+let steering_rate = 2.0;
+let scan_workgroup_size = 256u;
+
+var<workgroup> partial_sums: array<u32, 256>;
+
+fn cell_count() -> u32 {
+	return push_constants.grid_size.x * push_constants.grid_size.y * push_constants.grid_size.z;
+}
+
+fn cell_coordinates(position: vec3<f32>) -> vec3<i32> {
+	let coordinates = vec3<i32>(floor((position + push_constants.bounds) / push_constants.cell_size));
+	return clamp(coordinates, vec3<i32>(0), vec3<i32>(push_constants.grid_size) - vec3<i32>(1));
+}
+
+fn cell_index(coordinates: vec3<i32>) -> u32 {
+	let grid_size = push_constants.grid_size;
+	return u32(coordinates.x) + grid_size.x * (u32(coordinates.y) + grid_size.y * u32(coordinates.z));
+}
+
+@compute @workgroup_size(64)
+fn clear_cells(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x >= cell_count()) {
+		return;
+	}
+	atomicStore(&cell_counts[invocation_id.x], 0u);
+}
+
+@compute @workgroup_size(64)
+fn count_boids(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let boid_index = invocation_id.x;
+	if (boid_index >= push_constants.boid_count) {
+		return;
+	}
+	let cell = cell_index(cell_coordinates(source_boids[boid_index].position.xyz));
+	let rank = atomicAdd(&cell_counts[cell], 1u);
+	boid_cells[boid_index] = vec2<u32>(cell, rank);
+}
+
+// Find where the boids of each cell start with an exclusive prefix sum of the cell counts. A single workgroup does
+// this: every invocation sums a contiguous chunk of cells, the chunk sums are scanned together, and then every
+// invocation writes out the starts of its own chunk.
+@compute @workgroup_size(256)
+fn scan_cells(@builtin(local_invocation_index) invocation_index: u32) {
+	let cell_count = cell_count();
+	let chunk_size = (cell_count + scan_workgroup_size - 1u) / scan_workgroup_size;
+	let chunk_start = min(invocation_index * chunk_size, cell_count);
+	let chunk_end = min(chunk_start + chunk_size, cell_count);
+	var chunk_sum = 0u;
+	for (var i = chunk_start; i < chunk_end; i = i + 1u) {
+		chunk_sum = chunk_sum + atomicLoad(&cell_counts[i]);
+	}
+	partial_sums[invocation_index] = chunk_sum;
+	workgroupBarrier();
+
+	for (var offset = 1u; offset < scan_workgroup_size; offset = offset * 2u) {
+		var sum = partial_sums[invocation_index];
+		if (invocation_index >= offset) {
+			sum = sum + partial_sums[invocation_index - offset];
+		}
+		workgroupBarrier();
+		partial_sums[invocation_index] = sum;
+		workgroupBarrier();
+	}
+
+	var start = partial_sums[invocation_index] - chunk_sum;
+	for (var i = chunk_start; i < chunk_end; i = i + 1u) {
+		cell_starts[i] = start;
+		start = start + atomicLoad(&cell_counts[i]);
+	}
+}
+
+@compute @workgroup_size(64)
+fn scatter_boids(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let boid_index = invocation_id.x;
+	if (boid_index >= push_constants.boid_count) {
+		return;
+	}
+	let boid_cell = boid_cells[boid_index];
+	sorted_boid_indices[cell_starts[boid_cell.x] + boid_cell.y] = boid_index;
+}
+
+@compute @workgroup_size(64)
+fn simulate(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let boid_index = invocation_id.x;
+	if (boid_index >= push_constants.boid_count) {
+		return;
+	}
+	var boid = source_boids[boid_index];
+	let position = boid.position.xyz;
+	var velocity = boid.velocity.xyz;
+
+	// Gather the neighbors from the cells around the boid's own cell, which is enough because cells are at least as
+	// large as the perception radius.
+	var separation = vec3<f32>(0.0);
+	var velocity_sum = vec3<f32>(0.0);
+	var position_sum = vec3<f32>(0.0);
+	var neighbor_count = 0u;
+	let center_cell = cell_coordinates(position);
+	let grid_size = vec3<i32>(push_constants.grid_size);
+	for (var z = -1; z <= 1; z = z + 1) {
+		for (var y = -1; y <= 1; y = y + 1) {
+			for (var x = -1; x <= 1; x = x + 1) {
+				let neighbor_cell = center_cell + vec3<i32>(x, y, z);
+				if (any(neighbor_cell < vec3<i32>(0)) || any(neighbor_cell >= grid_size)) {
+					continue;
+				}
+				let cell = cell_index(neighbor_cell);
+				let start = cell_starts[cell];
+				let end = start + atomicLoad(&cell_counts[cell]);
+				for (var i = start; i < end && neighbor_count < maximum_neighbor_count; i = i + 1u) {
+					let other_index = sorted_boid_indices[i];
+					if (other_index == boid_index) {
+						continue;
+					}
+					let other = source_boids[other_index];
+					let offset = position - other.position.xyz;
+					let distance = length(offset);
+					if (distance >= push_constants.perception_radius || distance <= 0.0) {
+						continue;
+					}
+					if (distance < push_constants.separation_distance) {
+						separation = separation + offset / distance * (1.0 - distance / push_constants.separation_distance);
+					}
+					velocity_sum = velocity_sum + other.velocity.xyz;
+					position_sum = position_sum + other.position.xyz;
+					neighbor_count = neighbor_count + 1u;
+				}
+			}
+		}
+	}
+
+	// Every rule gives a change in velocity that is scaled so that the rules are comparable to each other.
+	var steering = push_constants.separation_weight * push_constants.maximum_speed * separation;
+	if (neighbor_count > 0u) {
+		let neighbor_count = f32(neighbor_count);
+		steering = steering + push_constants.alignment_weight * (velocity_sum / neighbor_count - velocity);
+		let center_offset = position_sum / neighbor_count - position;
+		steering = steering + push_constants.cohesion_weight * push_constants.maximum_speed * center_offset / push_constants.perception_radius;
+	}
+
+	// Turn away from walls that are getting close.
+	let wall_distances = push_constants.bounds - abs(position);
+	let wall_proximity = clamp(1.0 - wall_distances / wall_avoidance_distance, vec3<f32>(0.0), vec3<f32>(1.0));
+	steering = steering - sign(position) * wall_proximity * push_constants.maximum_speed * 2.0;
+
+	velocity = velocity + steering * steering_rate * push_constants.dt;
+	let speed = length(velocity);
+	if (speed > 0.0) {
+		velocity = velocity / speed * clamp(speed, push_constants.minimum_speed, push_constants.maximum_speed);
+	}
+	var new_position = position + velocity * push_constants.dt;
+
+	// Bounce off of the walls if turning away from them wasn't enough.
+	let is_outside = abs(new_position) > push_constants.bounds;
+	new_position = clamp(new_position, -push_constants.bounds, push_constants.bounds);
+	velocity = select(velocity, -velocity, is_outside);
+
+	destination_boids[boid_index] = Boid(vec4<f32>(new_position, 0.0), vec4<f32>(velocity, 0.0));
+}
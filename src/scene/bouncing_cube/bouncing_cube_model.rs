@@ -20,6 +20,19 @@ pub struct BouncingCubeSceneInformation {
 	pub wall_quads: [QuadInformation; 5],
 }
 
+/**
+ * What is saved of the bouncing cube scene so that the cube and the lights carry on where they left off after a
+ * restart.
+ */
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct BouncingCubeState {
+	pub cube_center: [f32; 3],
+	pub cube_velocity: [f32; 3],
+	pub cube_rotation_angle: f32,
+	pub cube_axis_of_rotation: [f32; 3],
+	pub point_light_rotation_angle: f32,
+}
+
 pub struct PointLightInformation {
 	pub position: glam::Vec3A,
 	pub ambient_light: [f32; 3],
@@ -211,10 +224,48 @@ impl BouncingCubeSceneInformation {
 		// TODO: here, it is possible for the cube to go out of bounds because the bounds change
 	}
 
-	pub fn update(&mut self, dt: f32) {
-		self.cube.rotation_angle += std::f32::consts::FRAC_PI_4 * dt;
-		self.point_light_rotation_angle += std::f32::consts::FRAC_PI_2 * dt;
-		self.cube.center += self.cube.velocity * dt;
+	pub fn state(&self) -> BouncingCubeState {
+		BouncingCubeState {
+			cube_center: self.cube.center.into(),
+			cube_velocity: self.cube.velocity.into(),
+			cube_rotation_angle: self.cube.rotation_angle,
+			cube_axis_of_rotation: self.cube.axis_of_rotation.into(),
+			point_light_rotation_angle: self.point_light_rotation_angle,
+		}
+	}
+
+	/**
+	 * Carry on from saved state. The window might have changed size since it was saved, so the cube is moved back
+	 * inside of the bounds if it's outside of them now.
+	 */
+	pub fn restore_state(&mut self, state: &BouncingCubeState) -> Result<(), String> {
+		let cube_axis_of_rotation = glam::Vec3A::from(state.cube_axis_of_rotation);
+		let is_finite = state
+			.cube_center
+			.iter()
+			.chain(&state.cube_velocity)
+			.chain(&state.cube_axis_of_rotation)
+			.chain([
+				&state.cube_rotation_angle,
+				&state.point_light_rotation_angle,
+			])
+			.all(|value| value.is_finite());
+		if !is_finite || cube_axis_of_rotation.length_squared() == 0.0 {
+			return Err("the saved cube isn't valid".to_owned());
+		}
+		let cube_semi_diagonal_length =
+			f32::sqrt(3.0 * (self.cube.side_length / 2.0) * (self.cube.side_length / 2.0));
+		let inner_bounds = glam::Vec3A::from(self.scene_bounds) - cube_semi_diagonal_length;
+		self.cube.center = glam::Vec3A::from(state.cube_center).clamp(-inner_bounds, inner_bounds);
+		self.cube.velocity = state.cube_velocity.into();
+		self.cube.rotation_angle = state.cube_rotation_angle;
+		self.cube.axis_of_rotation = cube_axis_of_rotation.normalize();
+		self.point_light_rotation_angle = state.point_light_rotation_angle;
+		self.update_light_positions();
+		Ok(())
+	}
+
+	fn update_light_positions(&mut self) {
 		(0..self.lights.len()).for_each(|light_index| {
 			let relative_angle = 2.0 * light_index as f32 * std::f32::consts::FRAC_PI_3;
 			let absolute_angle = self.point_light_rotation_angle + relative_angle;
@@ -223,6 +274,13 @@ impl BouncingCubeSceneInformation {
 			self.lights[light_index].position.y =
 				self.point_light_distance_from_center * absolute_angle.sin();
 		});
+	}
+
+	pub fn update(&mut self, dt: f32) {
+		self.cube.rotation_angle += std::f32::consts::FRAC_PI_4 * dt;
+		self.point_light_rotation_angle += std::f32::consts::FRAC_PI_2 * dt;
+		self.cube.center += self.cube.velocity * dt;
+		self.update_light_positions();
 		// TODO: do the math/physics and have more realistic collisions that affect the rotation -- the walls can only apply forces along their own normal on the touching/violating corners of the cube
 		let cube_semi_diagonal_length =
 			f32::sqrt(3.0 * (self.cube.side_length / 2.0) * (self.cube.side_length / 2.0));
@@ -252,3 +310,89 @@ impl BouncingCubeSceneInformation {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{BouncingCubeSceneInformation, BouncingCubeState};
+
+	fn state() -> BouncingCubeState {
+		BouncingCubeState {
+			cube_center: [0.0, 0.0, 0.0],
+			cube_velocity: [1.0, 2.0, 3.0],
+			cube_rotation_angle: 0.5,
+			cube_axis_of_rotation: [0.0, 2.0, 0.0],
+			point_light_rotation_angle: 1.0,
+		}
+	}
+
+	#[test]
+	fn restores_saved_state() {
+		let mut bouncing_cube_model = BouncingCubeSceneInformation::new(1920.0, 1080.0);
+		bouncing_cube_model.restore_state(&state()).unwrap();
+		assert_eq!(
+			bouncing_cube_model.cube.velocity,
+			glam::Vec3A::new(1.0, 2.0, 3.0)
+		);
+		assert_eq!(bouncing_cube_model.cube.rotation_angle, 0.5);
+		// The axis of rotation is normalized, and the lights are moved to where the rotation angle puts them.
+		assert_eq!(bouncing_cube_model.cube.axis_of_rotation, glam::Vec3A::Y);
+		assert!(
+			(bouncing_cube_model.lights[0].position.x
+				- bouncing_cube_model.point_light_distance_from_center * 1.0_f32.cos())
+			.abs() < 1e-5
+		);
+	}
+
+	#[test]
+	fn rejects_non_finite_and_degenerate_state() {
+		let invalid_states = [
+			BouncingCubeState {
+				cube_center: [f32::NAN, 0.0, 0.0],
+				..state()
+			},
+			BouncingCubeState {
+				cube_velocity: [0.0, f32::INFINITY, 0.0],
+				..state()
+			},
+			BouncingCubeState {
+				cube_rotation_angle: f32::NEG_INFINITY,
+				..state()
+			},
+			BouncingCubeState {
+				point_light_rotation_angle: f32::NAN,
+				..state()
+			},
+			BouncingCubeState {
+				cube_axis_of_rotation: [0.0, 0.0, 0.0],
+				..state()
+			},
+		];
+		for invalid_state in &invalid_states {
+			let mut bouncing_cube_model = BouncingCubeSceneInformation::new(1920.0, 1080.0);
+			let cube_center = bouncing_cube_model.cube.center;
+			assert!(bouncing_cube_model.restore_state(invalid_state).is_err());
+			// Nothing is restored from state that is rejected.
+			assert_eq!(bouncing_cube_model.cube.center, cube_center);
+		}
+	}
+
+	#[test]
+	fn moves_the_cube_back_inside_smaller_bounds() {
+		let mut bouncing_cube_model = BouncingCubeSceneInformation::new(1920.0, 1080.0);
+		let scene_bounds = glam::Vec3A::from(bouncing_cube_model.scene_bounds);
+		bouncing_cube_model
+			.restore_state(&BouncingCubeState {
+				cube_center: [1e6, -1e6, 1e6],
+				..state()
+			})
+			.unwrap();
+		let cube_semi_diagonal_length = 3.0_f32.sqrt() * bouncing_cube_model.cube.side_length / 2.0;
+		let inner_bounds = scene_bounds - cube_semi_diagonal_length;
+		assert!(
+			(bouncing_cube_model.cube.center - inner_bounds * glam::Vec3A::new(1.0, -1.0, 1.0))
+				.abs()
+				.max_element()
+				< 1e-4
+		);
+	}
+}
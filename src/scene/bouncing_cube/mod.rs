@@ -1,4 +1,4 @@
-mod bouncing_cube_model;
+pub(crate) mod bouncing_cube_model;
 use wgpu::util::DeviceExt;
 
 /**
@@ -10,24 +10,24 @@ use wgpu::util::DeviceExt;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct QuadVertex {
-	position: [f32; 2],
+pub(crate) struct QuadVertex {
+	pub(crate) position: [f32; 2],
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct InstanceData {
-	shininess: f32,
-	ambient_color: [f32; 3],
-	diffuse_color: [f32; 3],
-	specular_color: [f32; 3],
-	object_transform: [[f32; 4]; 4],
-	normal_transform: [[f32; 4]; 4],
+pub(crate) struct InstanceData {
+	pub(crate) shininess: f32,
+	pub(crate) ambient_color: [f32; 3],
+	pub(crate) diffuse_color: [f32; 3],
+	pub(crate) specular_color: [f32; 3],
+	pub(crate) object_transform: [[f32; 4]; 4],
+	pub(crate) normal_transform: [[f32; 4]; 4],
 }
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct LightInformationDatum {
+pub(crate) struct LightInformationDatum {
 	position: [f32; 3],
 	_padding_0: u32,
 	ambient_color: [f32; 3],
@@ -45,8 +45,308 @@ struct LightInformationDatum {
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct PushConstantData {
-	camera_position: [f32; 3],
+pub(crate) struct PushConstantData {
+	pub(crate) camera_position: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct ShadowPushConstantData {
+	pub(crate) light_transform: [[f32; 4]; 4],
+}
+
+impl QuadVertex {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x2];
+
+	/**
+	 * Describe how quad vertices are laid out in the vertex buffer.
+	 */
+	pub(crate) fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Vertex,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+impl InstanceData {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 12] = wgpu::vertex_attr_array![1 => Float32, 2 => Float32x3, 3 => Float32x3, 4 => Float32x3, 5 => Float32x4, 6 => Float32x4, 7 => Float32x4, 8 => Float32x4, 9 => Float32x4, 10 => Float32x4, 11 => Float32x4, 12 => Float32x4];
+
+	/**
+	 * The attributes for the columns of the object transform, taken from the full set of attributes so that their
+	 * locations and offsets always agree with it.
+	 */
+	const OBJECT_TRANSFORM_ATTRIBUTES: [wgpu::VertexAttribute; 4] = [
+		Self::ATTRIBUTES[4],
+		Self::ATTRIBUTES[5],
+		Self::ATTRIBUTES[6],
+		Self::ATTRIBUTES[7],
+	];
+
+	/**
+	 * Describe how instances are laid out in the instance buffer for the render pipeline.
+	 */
+	pub(crate) fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Instance,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+
+	/**
+	 * Describe the same instance buffer for depth-only pipelines, which only read the object transform.
+	 */
+	fn object_transform_vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			attributes: &Self::OBJECT_TRANSFORM_ATTRIBUTES,
+			..Self::vertex_buffer_layout()
+		}
+	}
+}
+
+/**
+ * Get the transforms that place the unit quad in the xy plane onto each face of the cube and each wall of the room.
+ * The rotations are incredibly important because the normal must also be transformed correctly.
+ * The walls span from -1 to 1 so that they can be scaled by the scene bounds.
+ */
+pub(crate) fn quad_transforms() -> [glam::Mat4; 11] {
+	[
+		glam::Mat4::from_rotation_translation(
+			glam::Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
+			-0.5 * glam::Vec3::X,
+		), // left cube quad
+		glam::Mat4::from_rotation_translation(
+			glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+			0.5 * glam::Vec3::X,
+		), // right cube quad
+		glam::Mat4::from_rotation_translation(
+			glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+			0.5 * glam::Vec3::Y,
+		), // top cube quad
+		glam::Mat4::from_rotation_translation(
+			glam::Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+			-0.5 * glam::Vec3::Y,
+		), // bottom cube quad
+		glam::Mat4::from_translation(0.5 * glam::Vec3::Z), // back cube quad
+		glam::Mat4::from_rotation_translation(
+			glam::Quat::from_rotation_x(std::f32::consts::PI),
+			-0.5 * glam::Vec3::Z,
+		), // front cube quad
+		glam::Mat4::from_scale_rotation_translation(
+			glam::Vec3::new(2.0, 2.0, 2.0),
+			glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+			-glam::Vec3::X,
+		), // left wall quad
+		glam::Mat4::from_scale_rotation_translation(
+			glam::Vec3::new(2.0, 2.0, 2.0),
+			glam::Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
+			glam::Vec3::X,
+		), // right wall quad
+		glam::Mat4::from_scale_rotation_translation(
+			glam::Vec3::new(2.0, 2.0, 2.0),
+			glam::Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+			glam::Vec3::Y,
+		), // top wall quad
+		glam::Mat4::from_scale_rotation_translation(
+			glam::Vec3::new(2.0, 2.0, 2.0),
+			glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+			-glam::Vec3::Y,
+		), // bottom wall quad
+		glam::Mat4::from_scale_rotation_translation(
+			glam::Vec3::new(2.0, 2.0, 2.0),
+			glam::Quat::from_rotation_x(std::f32::consts::PI),
+			glam::Vec3::Z,
+		), // back wall quad
+	]
+}
+
+/**
+ * Create a shader module that lights fragments with the Blinn-Phong lighting model, using the lights and shadow maps
+ * of the bouncing cube scene. The vertex source has to provide a `vertex_stage` entry point that outputs the
+ * `FragmentInput` struct declared by the lighting code.
+ */
+pub(crate) fn create_lit_shader_module(
+	device: &wgpu::Device,
+	label: &str,
+	vertex_source: &str,
+) -> wgpu::ShaderModule {
+	device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+		label: Some(label),
+		source: wgpu::ShaderSource::Wgsl(lit_shader_source(vertex_source).into()),
+	})
+}
+
+/**
+ * Get the source of a shader made by `create_lit_shader_module`.
+ */
+pub(crate) fn lit_shader_source(vertex_source: &str) -> String {
+	include_str!("lighting.wgsl").to_owned() + vertex_source
+}
+
+/**
+ * Get the vertex source that draws the quads of the cube and the walls, which pulses their lights to the beat of the
+ * audio uniforms bound in group 3.
+ */
+pub(crate) fn quad_vertex_source() -> String {
+	crate::scene::utilities::audio::AUDIO_UNIFORMS_SOURCE.to_owned() + include_str!("render.wgsl")
+}
+
+/**
+ * Create the uniform buffer holding the information of every light along with a bind group for it.
+ */
+pub(crate) fn create_light_information_bind_group(
+	device: &wgpu::Device,
+	scene_name: &str,
+) -> (wgpu::Buffer, wgpu::BindGroupLayout, wgpu::BindGroup) {
+	let light_information_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+		label: Some(&(scene_name.to_owned() + " light information uniform buffer")),
+		size: 3 * std::mem::size_of::<LightInformationDatum>() as wgpu::BufferAddress,
+		usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+		mapped_at_creation: false,
+	});
+	let light_information_bind_group_layout =
+		device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some(&(scene_name.to_owned() + " light information bind group layout")),
+			entries: &[wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Buffer {
+					ty: wgpu::BufferBindingType::Uniform,
+					has_dynamic_offset: false,
+					min_binding_size: None,
+				},
+				count: None,
+			}],
+		});
+	let light_information_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+		label: Some(&(scene_name.to_owned() + " light information bind group")),
+		layout: &light_information_bind_group_layout,
+		entries: &[wgpu::BindGroupEntry {
+			binding: 0,
+			resource: light_information_buffer.as_entire_binding(),
+		}],
+	});
+	(
+		light_information_buffer,
+		light_information_bind_group_layout,
+		light_information_bind_group,
+	)
+}
+
+/**
+ * Create the array of shadow maps with six layers for each light, one for every direction that the light shines in,
+ * along with a view of each layer for rendering into it.
+ */
+pub(crate) fn create_shadow_map(
+	device: &wgpu::Device,
+	light_count: u32,
+	scene_name: &str,
+) -> (
+	crate::scene::utilities::texture::Texture,
+	Vec<wgpu::TextureView>,
+) {
+	let shadow_map_size = 512;
+	let shadow_map_extent = wgpu::Extent3d {
+		width: shadow_map_size,
+		height: shadow_map_size,
+		depth_or_array_layers: light_count * 6,
+	};
+	let shadow_map_texture = device.create_texture(&wgpu::TextureDescriptor {
+		label: Some(&(scene_name.to_owned() + " shadow depth texture")),
+		size: shadow_map_extent,
+		mip_level_count: 1,
+		sample_count: 1,
+		dimension: wgpu::TextureDimension::D2,
+		format: crate::scene::utilities::texture::Texture::DEPTH_FORMAT,
+		usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+	});
+	let total_shadow_map_texture_view =
+		shadow_map_texture.create_view(&wgpu::TextureViewDescriptor::default());
+	let shadow_map_texture_views = (0..light_count * 6)
+		.map(|i| {
+			shadow_map_texture.create_view(&wgpu::TextureViewDescriptor {
+				label: Some(&(scene_name.to_owned() + " shadow depth texture view")),
+				format: None,
+				dimension: Some(wgpu::TextureViewDimension::D2),
+				aspect: wgpu::TextureAspect::All,
+				base_mip_level: 0,
+				mip_level_count: None,
+				base_array_layer: i,
+				array_layer_count: std::num::NonZeroU32::new(1),
+			})
+		})
+		.collect::<Vec<_>>();
+	let shadow_map_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+		label: Some(&(scene_name.to_owned() + " shadow depth sampler")),
+		mag_filter: wgpu::FilterMode::Linear,
+		min_filter: wgpu::FilterMode::Linear,
+		compare: Some(wgpu::CompareFunction::LessEqual),
+		..wgpu::SamplerDescriptor::default()
+	});
+	let shadow_map = crate::scene::utilities::texture::Texture {
+		texture: shadow_map_texture,
+		texture_view: total_shadow_map_texture_view,
+		sampler: shadow_map_sampler,
+		sample_type: wgpu::TextureSampleType::Depth,
+		view_dimension: wgpu::TextureViewDimension::D2Array,
+		sampler_binding_type: wgpu::SamplerBindingType::Comparison,
+	};
+	(shadow_map, shadow_map_texture_views)
+}
+
+/**
+ * Get the transforms of the six cameras that render the shadow maps of a light, one for each direction along an axis.
+ */
+pub(crate) fn shadow_map_transforms(
+	light: &bouncing_cube_model::PointLightInformation,
+) -> [[[f32; 4]; 4]; 6] {
+	let look_and_up_directions = [
+		(-glam::Vec3A::X, -glam::Vec3A::Y),
+		(glam::Vec3A::X, -glam::Vec3A::Y),
+		(glam::Vec3A::Y, glam::Vec3A::Z),
+		(-glam::Vec3A::Y, glam::Vec3A::Z),
+		(glam::Vec3A::Z, -glam::Vec3A::Y),
+		(-glam::Vec3A::Z, -glam::Vec3A::Y),
+	];
+	look_and_up_directions.map(|(look_direction, up_direction)| {
+		let mut shadow_render_camera =
+			crate::scene::utilities::camera::Camera::new(std::f32::consts::FRAC_PI_2, 1.0);
+		shadow_render_camera.position = light.position;
+		shadow_render_camera.look_direction = look_direction;
+		shadow_render_camera.up_direction = up_direction;
+		shadow_render_camera.recalculate_transformation_and_view_planes();
+		shadow_render_camera.transformation.to_cols_array_2d()
+	})
+}
+
+/**
+ * Get the data of every light as it's laid out in the light information uniform buffer.
+ */
+pub(crate) fn light_information_data(
+	lights: &[bouncing_cube_model::PointLightInformation],
+	shadow_map_transforms: &[[[[f32; 4]; 4]; 6]],
+) -> Vec<LightInformationDatum> {
+	lights
+		.iter()
+		.zip(shadow_map_transforms)
+		.map(|(light, camera_transforms)| LightInformationDatum {
+			position: light.position.into(),
+			ambient_color: light.ambient_light,
+			diffuse_color: light.diffuse_light,
+			specular_color: light.specular_light,
+			constant_attenuation: light.constant_attenuation,
+			linear_attenuation: light.linear_attenuation,
+			quadratic_attenuation: light.quadratic_attenuation,
+			camera_transforms: *camera_transforms,
+			_padding_0: 0,
+			_padding_1: 0,
+			_padding_2: 0,
+			_padding_3: 0,
+			_padding_4: 0,
+		})
+		.collect()
 }
 
 pub struct BouncingCubeScene {
@@ -60,6 +360,10 @@ pub struct BouncingCubeScene {
 	instance_buffer: wgpu::Buffer,
 	light_information_buffer: wgpu::Buffer,
 	light_information_bind_group: wgpu::BindGroup,
+	audio_uniform_buffer: wgpu::Buffer,
+	audio_bind_group: wgpu::BindGroup,
+	audio_uniforms: crate::scene::utilities::audio::AudioUniforms,
+	multisampled_color_target: crate::scene::utilities::render_target::MultisampledColorTarget,
 	depth_texture: crate::scene::utilities::texture::Texture,
 	shadow_map: crate::scene::utilities::texture::Texture,
 	shadow_map_texture_views: Vec<wgpu::TextureView>,
@@ -68,66 +372,22 @@ pub struct BouncingCubeScene {
 }
 
 impl BouncingCubeScene {
-	pub fn new(device: &wgpu::Device, surface_configuration: &wgpu::SurfaceConfiguration) -> Self {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) -> Self {
 		// Make the model that this scene represents.
 		let bouncing_cube_model = bouncing_cube_model::BouncingCubeSceneInformation::new(
-			surface_configuration.width as f32,
-			surface_configuration.height as f32,
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
 		);
 
-		// Make the quad transforms since all drawn quads are instanced from the same quad of unit sidelength centered in the xy plane.
-		// The quad transform rotations are incredibly important because the normal must also be transformed correctly.
-		let quad_transforms = [
-			glam::Mat4::from_rotation_translation(
-				glam::Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
-				-0.5 * glam::Vec3::X,
-			), // left cube quad
-			glam::Mat4::from_rotation_translation(
-				glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
-				0.5 * glam::Vec3::X,
-			), // right cube quad
-			glam::Mat4::from_rotation_translation(
-				glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
-				0.5 * glam::Vec3::Y,
-			), // top cube quad
-			glam::Mat4::from_rotation_translation(
-				glam::Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
-				-0.5 * glam::Vec3::Y,
-			), // bottom cube quad
-			glam::Mat4::from_translation(0.5 * glam::Vec3::Z), // back cube quad
-			glam::Mat4::from_rotation_translation(
-				glam::Quat::from_rotation_x(std::f32::consts::PI),
-				-0.5 * glam::Vec3::Z,
-			), // front cube quad
-			glam::Mat4::from_scale_rotation_translation(
-				glam::Vec3::new(2.0, 2.0, 2.0),
-				glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
-				-glam::Vec3::X,
-			), // left wall quad
-			glam::Mat4::from_scale_rotation_translation(
-				glam::Vec3::new(2.0, 2.0, 2.0),
-				glam::Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2),
-				glam::Vec3::X,
-			), // right wall quad
-			glam::Mat4::from_scale_rotation_translation(
-				glam::Vec3::new(2.0, 2.0, 2.0),
-				glam::Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
-				glam::Vec3::Y,
-			), // top wall quad
-			glam::Mat4::from_scale_rotation_translation(
-				glam::Vec3::new(2.0, 2.0, 2.0),
-				glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
-				-glam::Vec3::Y,
-			), // bottom wall quad
-			glam::Mat4::from_scale_rotation_translation(
-				glam::Vec3::new(2.0, 2.0, 2.0),
-				glam::Quat::from_rotation_x(std::f32::consts::PI),
-				glam::Vec3::Z,
-			), // back wall quad
-		];
-
-		// Get shader.
-		let render_shader_module = device.create_shader_module(&wgpu::include_wgsl!("render.wgsl"));
+		let quad_transforms = quad_transforms();
+
+		// Get shaders.
+		let render_shader_module =
+			create_lit_shader_module(device, "Bouncing cube scene shader", &quad_vertex_source());
+		let shadow_shader_module = device.create_shader_module(&wgpu::include_wgsl!("shadow.wgsl"));
 
 		// Create buffers and bind groups.
 		let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -157,10 +417,17 @@ impl BouncingCubeScene {
 			bouncing_cube_model
 				.scene_camera
 				.create_bind_group(device, "Bouncing cube scene");
+		let multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Bouncing cube scene",
+			);
 		let depth_texture = crate::scene::utilities::texture::Texture::create_depth_texture(
 			device,
-			surface_configuration.width,
-			surface_configuration.height,
+			render_target_configuration.width,
+			render_target_configuration.height,
+			render_target_configuration.sample_count,
 			"Bouncing cube scene",
 		);
 
@@ -173,82 +440,26 @@ impl BouncingCubeScene {
 		});
 
 		// Create uniform buffer for light information.
-		let light_information_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-			label: Some("Boucning cube scene light information uniform buffer"),
-			size: 3 * std::mem::size_of::<LightInformationDatum>() as wgpu::BufferAddress,
-			usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
-			mapped_at_creation: false,
-		});
-		let light_information_bind_group_layout =
-			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-				label: Some("Bouncing cube scene light information bind group layout"),
-				entries: &[wgpu::BindGroupLayoutEntry {
-					binding: 0,
-					visibility: wgpu::ShaderStages::FRAGMENT,
-					ty: wgpu::BindingType::Buffer {
-						ty: wgpu::BufferBindingType::Uniform,
-						has_dynamic_offset: false,
-						min_binding_size: None,
-					},
-					count: None,
-				}],
-			});
-		let light_information_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-			label: Some("Bouncing cube scene light information bind group"),
-			layout: &light_information_bind_group_layout,
-			entries: &[wgpu::BindGroupEntry {
-				binding: 0,
-				resource: light_information_buffer.as_entire_binding(),
-			}],
-		});
+		let (
+			light_information_buffer,
+			light_information_bind_group_layout,
+			light_information_bind_group,
+		) = create_light_information_bind_group(device, "Bouncing cube scene");
+
+		// Create uniform buffer for the audio that the lights pulse to.
+		let (audio_uniform_buffer, audio_bind_group_layout, audio_bind_group) =
+			crate::scene::utilities::audio::create_audio_bind_group(
+				device,
+				"Bouncing cube scene",
+				wgpu::ShaderStages::VERTEX,
+			);
 
 		// Create shadow maps and corresponding bind group layout and bind group.
-		let shadow_map_size = 512;
-		let shadow_map_extent = wgpu::Extent3d {
-			width: shadow_map_size,
-			height: shadow_map_size,
-			depth_or_array_layers: bouncing_cube_model.lights.len() as u32 * 6,
-		};
-		let shadow_map_texture = device.create_texture(&wgpu::TextureDescriptor {
-			label: Some("Bouncing cube scene shadow depth texture"),
-			size: shadow_map_extent,
-			mip_level_count: 1,
-			sample_count: 1,
-			dimension: wgpu::TextureDimension::D2,
-			format: crate::scene::utilities::texture::Texture::DEPTH_FORMAT,
-			usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-		});
-		let total_shadow_map_texture_view =
-			shadow_map_texture.create_view(&wgpu::TextureViewDescriptor::default());
-		let shadow_map_texture_views = (0..bouncing_cube_model.lights.len() as u32 * 6)
-			.map(|i| {
-				shadow_map_texture.create_view(&wgpu::TextureViewDescriptor {
-					label: Some("Bouncing cube scene shadow depth texture view"),
-					format: None,
-					dimension: Some(wgpu::TextureViewDimension::D2),
-					aspect: wgpu::TextureAspect::All,
-					base_mip_level: 0,
-					mip_level_count: None,
-					base_array_layer: i,
-					array_layer_count: std::num::NonZeroU32::new(1),
-				})
-			})
-			.collect::<Vec<_>>();
-		let shadow_map_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-			label: Some("Bouncing cube scene shadow depth sampler"),
-			mag_filter: wgpu::FilterMode::Linear,
-			min_filter: wgpu::FilterMode::Linear,
-			compare: Some(wgpu::CompareFunction::LessEqual),
-			..wgpu::SamplerDescriptor::default()
-		});
-		let shadow_map = crate::scene::utilities::texture::Texture {
-			texture: shadow_map_texture,
-			texture_view: total_shadow_map_texture_view,
-			sampler: shadow_map_sampler,
-			sample_type: wgpu::TextureSampleType::Depth,
-			view_dimension: wgpu::TextureViewDimension::D2Array,
-			sampler_binding_type: wgpu::SamplerBindingType::Comparison,
-		};
+		let (shadow_map, shadow_map_texture_views) = create_shadow_map(
+			device,
+			bouncing_cube_model.lights.len() as u32,
+			"Bouncing cube scene",
+		);
 		let (shadow_map_bind_group_layout, shadow_map_bind_group) = shadow_map.create_bind_group(
 			device,
 			"Bouncing cube scene shadow",
@@ -263,6 +474,7 @@ impl BouncingCubeScene {
 					&camera_bind_group_layout,
 					&light_information_bind_group_layout,
 					&shadow_map_bind_group_layout,
+					&audio_bind_group_layout,
 				],
 				push_constant_ranges: &[wgpu::PushConstantRange {
 					stages: wgpu::ShaderStages::FRAGMENT,
@@ -276,23 +488,15 @@ impl BouncingCubeScene {
 				module: &render_shader_module,
 				entry_point: "vertex_stage",
 				buffers: &[
-					wgpu::VertexBufferLayout {
-						array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
-						step_mode: wgpu::VertexStepMode::Vertex,
-						attributes: &wgpu::vertex_attr_array![0 => Float32x2],
-					},
-					wgpu::VertexBufferLayout {
-						array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
-						step_mode: wgpu::VertexStepMode::Instance,
-						attributes: &wgpu::vertex_attr_array![1 => Float32, 2 => Float32x3, 3 => Float32x3, 4 => Float32x3, 5 => Float32x4, 6 => Float32x4, 7 => Float32x4, 8 => Float32x4, 9 => Float32x4, 10 => Float32x4, 11 => Float32x4, 12 => Float32x4],
-					}
+					QuadVertex::vertex_buffer_layout(),
+					InstanceData::vertex_buffer_layout(),
 				],
 			},
 			fragment: Some(wgpu::FragmentState {
 				module: &render_shader_module,
 				entry_point: "fragment_stage",
 				targets: &[wgpu::ColorTargetState {
-					format: surface_configuration.format,
+					format: render_target_configuration.format,
 					blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
 					write_mask: wgpu::ColorWrites::all(),
 				}],
@@ -305,7 +509,7 @@ impl BouncingCubeScene {
 				stencil: wgpu::StencilState::default(),
 				bias: wgpu::DepthBiasState::default(),
 			}),
-			multisample: wgpu::MultisampleState::default(),
+			multisample: render_target_configuration.multisample_state(),
 			multiview: None,
 		});
 
@@ -313,26 +517,21 @@ impl BouncingCubeScene {
 		let shadow_map_pipeline_layout =
 			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
 				label: Some("Bouncing cube scene shadow pipeline layout"),
-				bind_group_layouts: &[&camera_bind_group_layout],
-				push_constant_ranges: &[],
+				bind_group_layouts: &[],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::VERTEX,
+					range: 0..std::mem::size_of::<ShadowPushConstantData>() as u32,
+				}],
 			});
 		let shadow_map_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
 			label: Some("Bouncing cube scene shadow pipeline"),
 			layout: Some(&shadow_map_pipeline_layout),
 			vertex: wgpu::VertexState {
-				module: &render_shader_module, // TODO: the shader module should be different
+				module: &shadow_shader_module,
 				entry_point: "vertex_stage",
-				buffers: &[ // TODO: vertex layout is exactly the same from render pipeline, so this shouldn't be able to differ from that
-					wgpu::VertexBufferLayout {
-						array_stride: std::mem::size_of::<QuadVertex>() as wgpu::BufferAddress,
-						step_mode: wgpu::VertexStepMode::Vertex,
-						attributes: &wgpu::vertex_attr_array![0 => Float32x2],
-					},
-					wgpu::VertexBufferLayout {
-						array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
-						step_mode: wgpu::VertexStepMode::Instance,
-						attributes: &wgpu::vertex_attr_array![1 => Float32, 2 => Float32x3, 3 => Float32x3, 4 => Float32x3, 5 => Float32x4, 6 => Float32x4, 7 => Float32x4, 8 => Float32x4, 9 => Float32x4, 10 => Float32x4, 11 => Float32x4, 12 => Float32x4],
-					}
+				buffers: &[
+					QuadVertex::vertex_buffer_layout(),
+					InstanceData::object_transform_vertex_buffer_layout(),
 				],
 			},
 			fragment: None,
@@ -366,6 +565,10 @@ impl BouncingCubeScene {
 			instance_buffer,
 			light_information_buffer,
 			light_information_bind_group,
+			audio_uniform_buffer,
+			audio_bind_group,
+			audio_uniforms: crate::scene::utilities::audio::AudioUniforms::default(),
+			multisampled_color_target,
 			depth_texture,
 			shadow_map,
 			shadow_map_texture_views,
@@ -379,16 +582,23 @@ impl crate::scene::Scene for BouncingCubeScene {
 	fn resize(
 		&mut self,
 		device: &wgpu::Device,
-		surface_configuration: &wgpu::SurfaceConfiguration,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
 	) {
 		self.bouncing_cube_model.resize(
-			surface_configuration.width as f32,
-			surface_configuration.height as f32,
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
 		);
+		self.multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Bouncing cube scene",
+			);
 		self.depth_texture = crate::scene::utilities::texture::Texture::create_depth_texture(
 			device,
-			surface_configuration.width,
-			surface_configuration.height,
+			render_target_configuration.width,
+			render_target_configuration.height,
+			render_target_configuration.sample_count,
 			"Bouncing cube scene",
 		);
 	}
@@ -397,6 +607,24 @@ impl crate::scene::Scene for BouncingCubeScene {
 		self.bouncing_cube_model.update(dt);
 	}
 
+	fn uses_bloom(&self) -> bool {
+		true
+	}
+
+	fn audio_changed(&mut self, audio_features: &crate::audio::AudioFeatures) {
+		self.audio_uniforms = crate::scene::utilities::audio::AudioUniforms::new(audio_features);
+	}
+
+	fn save_state(&self) -> Option<serde_json::Value> {
+		serde_json::to_value(self.bouncing_cube_model.state()).ok()
+	}
+
+	fn restore_state(&mut self, state: serde_json::Value) -> Result<(), String> {
+		let state = serde_json::from_value::<bouncing_cube_model::BouncingCubeState>(state)
+			.map_err(|error| error.to_string())?;
+		self.bouncing_cube_model.restore_state(&state)
+	}
+
 	fn render(
 		&mut self,
 		command_encoder: &mut wgpu::CommandEncoder,
@@ -449,31 +677,21 @@ impl crate::scene::Scene for BouncingCubeScene {
 			bytemuck::cast_slice(&instance_buffer_data),
 		);
 
-		let look_and_up_directions = [
-			(-glam::Vec3A::X, -glam::Vec3A::Y),
-			(glam::Vec3A::X, -glam::Vec3A::Y),
-			(glam::Vec3A::Y, glam::Vec3A::Z),
-			(-glam::Vec3A::Y, glam::Vec3A::Z),
-			(glam::Vec3A::Z, -glam::Vec3A::Y),
-			(-glam::Vec3A::Z, -glam::Vec3A::Y),
-		];
-		let mut shadow_map_transforms = Vec::from([[[[0.0; 4]; 4]; 6]; 3]);
-		for i in 0..self.bouncing_cube_model.lights.len() {
-			let light = &self.bouncing_cube_model.lights[i];
-			for j in 0..6 {
-				let shadow_map_texture_view = &self.shadow_map_texture_views[i * 6 + j];
-				let mut shadow_render_camera =
-					crate::scene::utilities::camera::Camera::new(std::f32::consts::FRAC_PI_2, 1.0);
-				shadow_render_camera.position = light.position;
-				shadow_render_camera.look_direction = look_and_up_directions[j].0;
-				shadow_render_camera.up_direction = look_and_up_directions[j].1;
-				shadow_render_camera.recalculate_transformation_and_view_planes();
-				shadow_map_transforms[i][j] = shadow_render_camera.transformation.to_cols_array_2d();
-				queue.write_buffer(
-					&self.camera_uniform_buffer,
-					0,
-					bytemuck::bytes_of(&shadow_render_camera.transformation),
-				);
+		let shadow_map_transforms = self
+			.bouncing_cube_model
+			.lights
+			.iter()
+			.map(shadow_map_transforms)
+			.collect::<Vec<_>>();
+		// Every light has a shadow map face and a transform for each of the six directions around it.
+		for (shadow_map_texture_views, light_transforms) in self
+			.shadow_map_texture_views
+			.chunks(6)
+			.zip(&shadow_map_transforms)
+		{
+			for (shadow_map_texture_view, light_transform) in
+				shadow_map_texture_views.iter().zip(light_transforms)
+			{
 				let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
 					label: Some("Bouncing cube scene shadow render pass"),
 					color_attachments: &[],
@@ -490,7 +708,13 @@ impl crate::scene::Scene for BouncingCubeScene {
 				render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
 				render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
 				render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-				render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+				render_pass.set_push_constants(
+					wgpu::ShaderStages::VERTEX,
+					0,
+					bytemuck::bytes_of(&ShadowPushConstantData {
+						light_transform: *light_transform,
+					}),
+				);
 				render_pass.draw_indexed(0..6, 0, 0..11);
 			}
 		}
@@ -504,46 +728,28 @@ impl crate::scene::Scene for BouncingCubeScene {
 		queue.write_buffer(
 			&self.light_information_buffer,
 			0,
-			bytemuck::cast_slice(
-				&self
-					.bouncing_cube_model
-					.lights
-					.iter()
-					.enumerate()
-					.map(|(i, light)| LightInformationDatum {
-						position: light.position.into(),
-						ambient_color: light.ambient_light,
-						diffuse_color: light.diffuse_light,
-						specular_color: light.specular_light,
-						constant_attenuation: light.constant_attenuation,
-						linear_attenuation: light.linear_attenuation,
-						quadratic_attenuation: light.quadratic_attenuation,
-						camera_transforms: shadow_map_transforms[i],
-						_padding_0: 0,
-						_padding_1: 0,
-						_padding_2: 0,
-						_padding_3: 0,
-						_padding_4: 0,
-					})
-					.collect::<Vec<_>>(),
-			),
+			bytemuck::cast_slice(&light_information_data(
+				&self.bouncing_cube_model.lights,
+				&shadow_map_transforms,
+			)),
+		);
+		queue.write_buffer(
+			&self.audio_uniform_buffer,
+			0,
+			bytemuck::bytes_of(&self.audio_uniforms),
 		);
 
 		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
 			label: Some("Bouncing cube scene render pass"),
-			color_attachments: &[wgpu::RenderPassColorAttachment {
-				view: output_texture_view,
-				resolve_target: None,
-				ops: wgpu::Operations {
-					load: wgpu::LoadOp::Clear(wgpu::Color {
-						r: 0.5,
-						g: 0.5,
-						b: 0.5,
-						a: 1.0,
-					}),
-					store: true,
-				},
-			}],
+			color_attachments: &[self.multisampled_color_target.color_attachment(
+				output_texture_view,
+				wgpu::LoadOp::Clear(wgpu::Color {
+					r: 0.5,
+					g: 0.5,
+					b: 0.5,
+					a: 1.0,
+				}),
+			)],
 			depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
 				view: &self.depth_texture.texture_view,
 				depth_ops: Some(wgpu::Operations {
@@ -567,6 +773,7 @@ impl crate::scene::Scene for BouncingCubeScene {
 		render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
 		render_pass.set_bind_group(1, &self.light_information_bind_group, &[]);
 		render_pass.set_bind_group(2, &self.shadow_map_bind_group, &[]);
+		render_pass.set_bind_group(3, &self.audio_bind_group, &[]);
 		render_pass.draw_indexed(0..6, 0, 0..11);
 	}
 }
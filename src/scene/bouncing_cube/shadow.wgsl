@@ -0,0 +1,26 @@
+struct VertexInput {
+	@location(0) position: vec2<f32>,
+};
+struct InstanceInput {
+	@location(5) object_transform_col_0: vec4<f32>,
+	@location(6) object_transform_col_1: vec4<f32>,
+	@location(7) object_transform_col_2: vec4<f32>,
+	@location(8) object_transform_col_3: vec4<f32>,
+};
+
+struct PushConstantData {
+	light_transform: mat4x4<f32>,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+@vertex
+fn vertex_stage(vertex: VertexInput, instance: InstanceInput) -> @builtin(position) vec4<f32> {
+	let object_transform = mat4x4<f32>(
+		instance.object_transform_col_0,
+		instance.object_transform_col_1,
+		instance.object_transform_col_2,
+		instance.object_transform_col_3,
+	);
+	return push_constant_data.light_transform * object_transform * vec4<f32>(vertex.position, 0.0, 1.0);
+}
@@ -0,0 +1,656 @@
+use rand::Rng;
+
+/**
+ * The width and height of the workgroups of the simulation shader.
+ */
+const WORKGROUP_SIZE: u32 = 8;
+
+/**
+ * The most emitters that drift around on their own, not counting the one that can follow the cursor.
+ */
+const MAXIMUM_EMITTER_COUNT: usize = 8;
+
+/**
+ * The number of Jacobi iterations used when diffusing a field, which has to be odd so that the result ends up in the
+ * second texture of the field.
+ */
+const DIFFUSION_ITERATION_COUNT: usize = 11;
+
+/**
+ * The format of every field of the simulation, which has to support being written to as a storage texture as well as
+ * being filtered.
+ */
+const FIELD_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	grid_size: [f32; 2],
+	dt: f32,
+	parameter: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
+struct EmitterData {
+	position: [f32; 2],
+	velocity: [f32; 2],
+	color: [f32; 3],
+	radius: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct EmitterUniforms {
+	emitters: [EmitterData; MAXIMUM_EMITTER_COUNT + 1],
+	emitter_count: u32,
+	_padding: [u32; 3],
+}
+
+/**
+ * A source of dye and motion that wanders around the grid, changing its direction and color over time.
+ */
+struct DriftingEmitter {
+	position: glam::Vec2,
+	direction_angle: f32,
+	hue: f32,
+}
+
+/**
+ * The kinds of steps that make up a frame of the simulation, each of which is an entry point of the simulation shader.
+ */
+#[derive(Clone, Copy)]
+enum StepKind {
+	SplatVelocity,
+	SplatDye,
+	AdvectVelocity,
+	AdvectDye,
+	DiffuseVelocity,
+	DiffuseDye,
+	Divergence,
+	SolvePressure,
+	SubtractPressureGradient,
+}
+
+/**
+ * A single dispatch of the simulation shader along with the fields that it reads from and writes into.
+ */
+struct SimulationStep {
+	kind: StepKind,
+	bind_group: wgpu::BindGroup,
+}
+
+/**
+ * Convert a color from hue, saturation and value, which are all from 0 to 1, into linear RGB.
+ */
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [f32; 3] {
+	let channel = |offset: f32| {
+		let k = (offset + hue * 6.0) % 6.0;
+		value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0)
+	};
+	[channel(5.0), channel(3.0), channel(1.0)]
+}
+
+/**
+ * Simulates dye being stirred around in a fluid using Jos Stam's stable fluids method, entirely in compute shaders.
+ * Each frame, emitters push the fluid and add dye to it, then the velocity and dye are advected along the velocity and
+ * diffused, and finally pressure is solved for and used to keep the fluid from compressing. The simulation runs on a
+ * grid whose resolution doesn't depend on the size of the screen, and its dye is stretched over the whole screen.
+ */
+pub struct FluidScene {
+	fluid_configuration: crate::config::FluidConfig,
+	grid_size: [u32; 2],
+	resolution: [f32; 2],
+	dt: f32,
+	drifting_emitters: Vec<DriftingEmitter>,
+	cursor_position: Option<glam::Vec2>,
+	previous_cursor_position: Option<glam::Vec2>,
+	cursor_velocity: glam::Vec2,
+	emitter_uniforms: EmitterUniforms,
+	emitter_uniform_buffer: wgpu::Buffer,
+	emitter_uniform_bind_group: wgpu::BindGroup,
+	dye_textures: [crate::scene::utilities::texture::Texture; 2],
+	splat_velocity_pipeline: wgpu::ComputePipeline,
+	splat_dye_pipeline: wgpu::ComputePipeline,
+	advect_pipeline: wgpu::ComputePipeline,
+	diffuse_pipeline: wgpu::ComputePipeline,
+	divergence_pipeline: wgpu::ComputePipeline,
+	solve_pressure_pipeline: wgpu::ComputePipeline,
+	subtract_pressure_gradient_pipeline: wgpu::ComputePipeline,
+	simulation_steps: Vec<SimulationStep>,
+	dye_bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl FluidScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		fluid_configuration: &crate::config::FluidConfig,
+	) -> Self {
+		let grid_size = fluid_configuration.grid_resolution.map(|size| size.max(1));
+		if fluid_configuration.emitter_count as usize > MAXIMUM_EMITTER_COUNT {
+			log::warn!(
+				"Only {} of the {} configured emitters will be used",
+				MAXIMUM_EMITTER_COUNT,
+				fluid_configuration.emitter_count
+			);
+		}
+
+		// Scatter the emitters around the grid, spreading their colors around the color wheel.
+		let mut random_number_generator = rand::thread_rng();
+		let emitter_count = (fluid_configuration.emitter_count as usize).min(MAXIMUM_EMITTER_COUNT);
+		let drifting_emitters = (0..emitter_count)
+			.map(|emitter_index| DriftingEmitter {
+				position: glam::Vec2::new(
+					random_number_generator.gen_range(0.2..0.8) * grid_size[0] as f32,
+					random_number_generator.gen_range(0.2..0.8) * grid_size[1] as f32,
+				),
+				direction_angle: random_number_generator.gen_range(0.0..std::f32::consts::TAU),
+				hue: emitter_index as f32 / emitter_count as f32,
+			})
+			.collect();
+
+		// Create the fields of the simulation.
+		let create_field_texture = |label: &str| {
+			crate::scene::utilities::texture::Texture::create_storage_texture(
+				device,
+				FIELD_FORMAT,
+				grid_size[0],
+				grid_size[1],
+				&("Fluid scene ".to_owned() + label),
+			)
+		};
+		let velocity_textures = [
+			create_field_texture("first velocity"),
+			create_field_texture("second velocity"),
+		];
+		let dye_textures = [
+			create_field_texture("first dye"),
+			create_field_texture("second dye"),
+		];
+		let pressure_textures = [
+			create_field_texture("first pressure"),
+			create_field_texture("second pressure"),
+		];
+		let divergence_texture = create_field_texture("divergence");
+		let scratch_texture = create_field_texture("scratch");
+
+		// Create the emitter uniforms, which get filled in every frame.
+		let emitter_uniforms = EmitterUniforms {
+			emitters: [EmitterData::default(); MAXIMUM_EMITTER_COUNT + 1],
+			emitter_count: 0,
+			_padding: [0; 3],
+		};
+		let emitter_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Fluid scene emitter uniform buffer"),
+			size: std::mem::size_of::<EmitterUniforms>() as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let emitter_uniform_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Fluid scene emitter uniform bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::COMPUTE,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let emitter_uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Fluid scene emitter uniform bind group"),
+			layout: &emitter_uniform_bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: emitter_uniform_buffer.as_entire_binding(),
+			}],
+		});
+
+		// Create the simulation pipelines, which all share the same layout.
+		let field_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Fluid scene field bind group layout"),
+				entries: &[
+					wgpu::BindGroupLayoutEntry {
+						binding: 0,
+						visibility: wgpu::ShaderStages::COMPUTE,
+						ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+						count: None,
+					},
+					wgpu::BindGroupLayoutEntry {
+						binding: 1,
+						visibility: wgpu::ShaderStages::COMPUTE,
+						ty: wgpu::BindingType::Texture {
+							sample_type: wgpu::TextureSampleType::Float { filterable: true },
+							view_dimension: wgpu::TextureViewDimension::D2,
+							multisampled: false,
+						},
+						count: None,
+					},
+					wgpu::BindGroupLayoutEntry {
+						binding: 2,
+						visibility: wgpu::ShaderStages::COMPUTE,
+						ty: wgpu::BindingType::Texture {
+							sample_type: wgpu::TextureSampleType::Float { filterable: true },
+							view_dimension: wgpu::TextureViewDimension::D2,
+							multisampled: false,
+						},
+						count: None,
+					},
+					wgpu::BindGroupLayoutEntry {
+						binding: 3,
+						visibility: wgpu::ShaderStages::COMPUTE,
+						ty: wgpu::BindingType::StorageTexture {
+							access: wgpu::StorageTextureAccess::WriteOnly,
+							format: FIELD_FORMAT,
+							view_dimension: wgpu::TextureViewDimension::D2,
+						},
+						count: None,
+					},
+				],
+			});
+		let simulation_shader_module =
+			device.create_shader_module(&wgpu::include_wgsl!("simulate.wgsl"));
+		let simulation_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Fluid scene simulation pipeline layout"),
+				bind_group_layouts: &[&field_bind_group_layout, &emitter_uniform_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::COMPUTE,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		let create_simulation_pipeline = |entry_point: &str| {
+			crate::scene::utilities::compute::create_compute_pipeline(
+				device,
+				&format!("Fluid scene {} pipeline", entry_point),
+				&simulation_shader_module,
+				entry_point,
+				&simulation_pipeline_layout,
+			)
+		};
+
+		// Lay out the steps of a frame. Every field starts out in its first texture, and the steps are arranged so that
+		// the fields end up back in their first textures except for the dye, which gets copied back afterwards.
+		let create_step = |kind: StepKind,
+		                   input_a: &crate::scene::utilities::texture::Texture,
+		                   input_b: &crate::scene::utilities::texture::Texture,
+		                   output: &crate::scene::utilities::texture::Texture| {
+			SimulationStep {
+				kind,
+				bind_group: device.create_bind_group(&wgpu::BindGroupDescriptor {
+					label: Some("Fluid scene field bind group"),
+					layout: &field_bind_group_layout,
+					entries: &[
+						wgpu::BindGroupEntry {
+							binding: 0,
+							resource: wgpu::BindingResource::Sampler(&input_a.sampler),
+						},
+						wgpu::BindGroupEntry {
+							binding: 1,
+							resource: wgpu::BindingResource::TextureView(&input_a.texture_view),
+						},
+						wgpu::BindGroupEntry {
+							binding: 2,
+							resource: wgpu::BindingResource::TextureView(&input_b.texture_view),
+						},
+						wgpu::BindGroupEntry {
+							binding: 3,
+							resource: wgpu::BindingResource::TextureView(&output.texture_view),
+						},
+					],
+				}),
+			}
+		};
+		let create_diffusion_steps =
+			|kind: StepKind, field_textures: &[crate::scene::utilities::texture::Texture; 2]| {
+				// The first texture holds the field before diffusing while the estimates alternate between the second
+				// texture and the scratch texture, ending in the second texture.
+				std::iter::once(create_step(
+					kind,
+					&field_textures[0],
+					&field_textures[0],
+					&field_textures[1],
+				))
+				.chain((1..DIFFUSION_ITERATION_COUNT).map(|iteration_index| {
+					if iteration_index % 2 == 1 {
+						create_step(
+							kind,
+							&field_textures[0],
+							&field_textures[1],
+							&scratch_texture,
+						)
+					} else {
+						create_step(
+							kind,
+							&field_textures[0],
+							&scratch_texture,
+							&field_textures[1],
+						)
+					}
+				}))
+				.collect::<Vec<_>>()
+			};
+		let pressure_iteration_count = fluid_configuration.pressure_iteration_count.div_ceil(2) * 2;
+		let mut simulation_steps = vec![
+			create_step(
+				StepKind::SplatVelocity,
+				&velocity_textures[0],
+				&velocity_textures[0],
+				&velocity_textures[1],
+			),
+			create_step(
+				StepKind::SplatDye,
+				&dye_textures[0],
+				&dye_textures[0],
+				&dye_textures[1],
+			),
+			create_step(
+				StepKind::AdvectVelocity,
+				&velocity_textures[1],
+				&velocity_textures[1],
+				&velocity_textures[0],
+			),
+			create_step(
+				StepKind::AdvectDye,
+				&dye_textures[1],
+				&velocity_textures[1],
+				&dye_textures[0],
+			),
+		];
+		simulation_steps.extend(create_diffusion_steps(
+			StepKind::DiffuseVelocity,
+			&velocity_textures,
+		));
+		simulation_steps.extend(create_diffusion_steps(StepKind::DiffuseDye, &dye_textures));
+		simulation_steps.push(create_step(
+			StepKind::Divergence,
+			&velocity_textures[1],
+			&velocity_textures[1],
+			&divergence_texture,
+		));
+		// The pressure from the previous frame is a good starting estimate, so it's kept between frames.
+		for iteration_index in 0..pressure_iteration_count {
+			simulation_steps.push(create_step(
+				StepKind::SolvePressure,
+				&divergence_texture,
+				&pressure_textures[iteration_index as usize % 2],
+				&pressure_textures[1 - iteration_index as usize % 2],
+			));
+		}
+		simulation_steps.push(create_step(
+			StepKind::SubtractPressureGradient,
+			&velocity_textures[1],
+			&pressure_textures[0],
+			&velocity_textures[0],
+		));
+
+		// Create the render pipeline, which stretches the dye over the whole render target.
+		let dye_bind_group_layout = dye_textures[0].create_bind_group_layout(
+			device,
+			"Fluid scene dye",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let dye_bind_group = dye_textures[0].create_bind_group_with_layout(
+			device,
+			&dye_bind_group_layout,
+			"Fluid scene dye",
+		);
+		let render_shader_module =
+			crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+				device,
+				"Fluid scene render shader",
+				include_str!("render.wgsl"),
+			);
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Fluid scene render pipeline layout"),
+				bind_group_layouts: &[&dye_bind_group_layout],
+				push_constant_ranges: &[],
+			});
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Fluid scene render pipeline",
+			&render_shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: render_target_configuration.format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+
+		Self {
+			fluid_configuration: fluid_configuration.clone(),
+			grid_size,
+			resolution: [
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+			],
+			dt: 0.0,
+			drifting_emitters,
+			cursor_position: None,
+			previous_cursor_position: None,
+			cursor_velocity: glam::Vec2::ZERO,
+			emitter_uniforms,
+			emitter_uniform_buffer,
+			emitter_uniform_bind_group,
+			dye_textures,
+			splat_velocity_pipeline: create_simulation_pipeline("splat_velocity"),
+			splat_dye_pipeline: create_simulation_pipeline("splat_dye"),
+			advect_pipeline: create_simulation_pipeline("advect"),
+			diffuse_pipeline: create_simulation_pipeline("diffuse"),
+			divergence_pipeline: create_simulation_pipeline("divergence"),
+			solve_pressure_pipeline: create_simulation_pipeline("solve_pressure"),
+			subtract_pressure_gradient_pipeline: create_simulation_pipeline(
+				"subtract_pressure_gradient",
+			),
+			simulation_steps,
+			dye_bind_group,
+			render_pipeline,
+		}
+	}
+
+	/**
+	 * Get the pipeline that runs a kind of step along with the parameter that it's run with this frame.
+	 */
+	fn step_pipeline_and_parameter(&self, kind: StepKind) -> (&wgpu::ComputePipeline, f32) {
+		match kind {
+			StepKind::SplatVelocity => (&self.splat_velocity_pipeline, 0.0),
+			StepKind::SplatDye => (
+				&self.splat_dye_pipeline,
+				self.fluid_configuration.dye_intensity,
+			),
+			StepKind::AdvectVelocity => (
+				&self.advect_pipeline,
+				(-self.fluid_configuration.velocity_dissipation * self.dt).exp(),
+			),
+			StepKind::AdvectDye => (
+				&self.advect_pipeline,
+				(-self.fluid_configuration.dye_dissipation * self.dt).exp(),
+			),
+			StepKind::DiffuseVelocity => (
+				&self.diffuse_pipeline,
+				self.fluid_configuration.viscosity * self.dt,
+			),
+			StepKind::DiffuseDye => (
+				&self.diffuse_pipeline,
+				self.fluid_configuration.dye_diffusion * self.dt,
+			),
+			StepKind::Divergence => (&self.divergence_pipeline, 0.0),
+			StepKind::SolvePressure => (&self.solve_pressure_pipeline, 0.0),
+			StepKind::SubtractPressureGradient => (&self.subtract_pressure_gradient_pipeline, 0.0),
+		}
+	}
+}
+
+impl crate::scene::Scene for FluidScene {
+	fn resize(
+		&mut self,
+		_: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		// The grid keeps its resolution, so only the conversion of cursor positions into the grid has to change.
+		self.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		];
+	}
+
+	fn update(&mut self, dt: f32) {
+		// Long frames are shortened so that the fluid doesn't jump.
+		self.dt = dt.min(1.0 / 30.0);
+		let grid_size = glam::Vec2::new(self.grid_size[0] as f32, self.grid_size[1] as f32);
+		// Splats get divided by the radius, so it can't be smaller than a cell.
+		let radius = (self.fluid_configuration.emitter_radius * grid_size.y).max(1.0);
+		let speed = self.fluid_configuration.drift_speed * grid_size.y;
+		let force = self.fluid_configuration.emitter_force * grid_size.y;
+
+		// Make the emitters wander around, turning back when they get close to the edges of the grid.
+		let mut random_number_generator = rand::thread_rng();
+		let mut emitters = Vec::new();
+		for drifting_emitter in &mut self.drifting_emitters {
+			drifting_emitter.direction_angle +=
+				random_number_generator.gen_range(-4.0..4.0) * self.dt;
+			let mut direction = glam::Vec2::new(
+				drifting_emitter.direction_angle.cos(),
+				drifting_emitter.direction_angle.sin(),
+			);
+			let margin = 0.1 * grid_size;
+			if (drifting_emitter.position.x < margin.x && direction.x < 0.0)
+				|| (drifting_emitter.position.x > grid_size.x - margin.x && direction.x > 0.0)
+			{
+				direction.x = -direction.x;
+			}
+			if (drifting_emitter.position.y < margin.y && direction.y < 0.0)
+				|| (drifting_emitter.position.y > grid_size.y - margin.y && direction.y > 0.0)
+			{
+				direction.y = -direction.y;
+			}
+			drifting_emitter.direction_angle = direction.y.atan2(direction.x);
+			drifting_emitter.position += direction * speed * self.dt;
+			drifting_emitter.hue = (drifting_emitter.hue + 0.02 * self.dt).fract();
+			emitters.push(EmitterData {
+				position: drifting_emitter.position.into(),
+				velocity: (direction * force).into(),
+				color: hsv_to_rgb(drifting_emitter.hue, 0.8, 1.0),
+				radius,
+			});
+		}
+
+		// Make the cursor stir the fluid in the direction that it moves in.
+		if let Some(cursor_position) = self.cursor_position {
+			if self.dt > 0.0 {
+				self.cursor_velocity = (cursor_position
+					- self.previous_cursor_position.unwrap_or(cursor_position))
+					/ self.dt;
+			}
+			emitters.push(EmitterData {
+				position: cursor_position.into(),
+				velocity: self.cursor_velocity.into(),
+				color: [1.0, 1.0, 1.0],
+				radius,
+			});
+		}
+		self.previous_cursor_position = self.cursor_position;
+
+		self.emitter_uniforms.emitters[..emitters.len()].copy_from_slice(&emitters);
+		self.emitter_uniforms.emitter_count = emitters.len() as u32;
+	}
+
+	fn uses_bloom(&self) -> bool {
+		true
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		queue.write_buffer(
+			&self.emitter_uniform_buffer,
+			0,
+			bytemuck::bytes_of(&self.emitter_uniforms),
+		);
+
+		let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+			label: Some("Fluid scene simulation compute pass"),
+		});
+		compute_pass.set_bind_group(1, &self.emitter_uniform_bind_group, &[]);
+		for simulation_step in &self.simulation_steps {
+			let (pipeline, parameter) = self.step_pipeline_and_parameter(simulation_step.kind);
+			compute_pass.set_pipeline(pipeline);
+			compute_pass.set_push_constants(
+				0,
+				bytemuck::bytes_of(&PushConstantData {
+					grid_size: self.grid_size.map(|size| size as f32),
+					dt: self.dt,
+					parameter,
+				}),
+			);
+			compute_pass.set_bind_group(0, &simulation_step.bind_group, &[]);
+			compute_pass.dispatch(
+				crate::scene::utilities::compute::workgroup_count(
+					self.grid_size[0],
+					WORKGROUP_SIZE,
+				),
+				crate::scene::utilities::compute::workgroup_count(
+					self.grid_size[1],
+					WORKGROUP_SIZE,
+				),
+				1,
+			);
+		}
+		drop(compute_pass);
+
+		// Diffusing the dye leaves it in its second texture, so it gets copied back for the next frame.
+		command_encoder.copy_texture_to_texture(
+			self.dye_textures[1].texture.as_image_copy(),
+			self.dye_textures[0].texture.as_image_copy(),
+			wgpu::Extent3d {
+				width: self.grid_size[0],
+				height: self.grid_size[1],
+				depth_or_array_layers: 1,
+			},
+		);
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Fluid scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_bind_group(0, &self.dye_bind_group, &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+
+	fn cursor_changed(&mut self, cursor_state: &crate::scene::CursorState) {
+		if !self.fluid_configuration.follow_cursor {
+			return;
+		}
+		// Convert the cursor's position from pixels into grid cells.
+		self.cursor_position = cursor_state.position.map(|position| {
+			glam::Vec2::new(
+				position[0] / self.resolution[0] * self.grid_size[0] as f32,
+				position[1] / self.resolution[1] * self.grid_size[1] as f32,
+			)
+		});
+		if self.cursor_position.is_none() {
+			self.previous_cursor_position = None;
+		}
+	}
+}
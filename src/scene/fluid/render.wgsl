@@ -0,0 +1,11 @@
+@group(0) @binding(0)
+var dye_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var dye_sampler: sampler;
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	let dye = textureSample(dye_texture, dye_sampler, input.texture_coordinates).rgb;
+	let background_color = vec3<f32>(0.002, 0.002, 0.005);
+	return vec4<f32>(background_color + max(dye, vec3<f32>(0.0)), 1.0);
+}
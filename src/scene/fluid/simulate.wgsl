@@ -0,0 +1,163 @@
+// Every field of the simulation is stored in a texture with one texel per grid cell, and every step reads from up to
+// two of them while writing into a third. Positions and velocities are measured in cells, with y pointing down.
+
+struct PushConstantData {
+	grid_size: vec2<f32>,
+	dt: f32,
+	// What this means depends on the step, such as how much of a field is kept while advecting it.
+	parameter: f32,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+struct Emitter {
+	position: vec2<f32>,
+	velocity: vec2<f32>,
+	color: vec3<f32>,
+	radius: f32,
+};
+
+struct EmitterUniforms {
+	emitters: array<Emitter, 9>,
+	emitter_count: u32,
+};
+
+@group(0) @binding(0)
+var field_sampler: sampler;
+@group(0) @binding(1)
+var input_a: texture_2d<f32>;
+@group(0) @binding(2)
+var input_b: texture_2d<f32>;
+@group(0) @binding(3)
+var output: texture_storage_2d<rgba16float, write>;
+
+@group(1) @binding(0)
+var<uniform> emitter_uniforms: EmitterUniforms;
+
+// Reading outside of the grid reads its edge instead.
+fn load(field: texture_2d<f32>, cell: vec2<i32>) -> vec4<f32> {
+	let grid_size = vec2<i32>(push_constant_data.grid_size);
+	return textureLoad(field, clamp(cell, vec2<i32>(0), grid_size - vec2<i32>(1)), 0);
+}
+
+fn is_outside_grid(cell: vec2<u32>) -> bool {
+	return any(vec2<f32>(cell) >= push_constant_data.grid_size);
+}
+
+fn emitter_weight(emitter: Emitter, cell: vec2<u32>) -> f32 {
+	let offset = vec2<f32>(cell) + 0.5 - emitter.position;
+	return exp(-dot(offset, offset) / (emitter.radius * emitter.radius));
+}
+
+// Push the velocity around each emitter along in the direction that the emitter is moving in.
+@compute @workgroup_size(8, 8)
+fn splat_velocity(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	var velocity = textureLoad(input_a, vec2<i32>(cell), 0).xy;
+	for (var i = 0u; i < emitter_uniforms.emitter_count; i = i + 1u) {
+		let emitter = emitter_uniforms.emitters[i];
+		velocity += emitter.velocity * emitter_weight(emitter, cell) * push_constant_data.dt;
+	}
+	textureStore(output, vec2<i32>(cell), vec4<f32>(velocity, 0.0, 0.0));
+}
+
+// Add the color of each emitter to the dye around it, where the parameter is how much dye is added per second.
+@compute @workgroup_size(8, 8)
+fn splat_dye(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	var dye = textureLoad(input_a, vec2<i32>(cell), 0).rgb;
+	for (var i = 0u; i < emitter_uniforms.emitter_count; i = i + 1u) {
+		let emitter = emitter_uniforms.emitters[i];
+		dye += emitter.color * emitter_weight(emitter, cell) * push_constant_data.parameter * push_constant_data.dt;
+	}
+	textureStore(output, vec2<i32>(cell), vec4<f32>(dye, 1.0));
+}
+
+// Move field A along the velocity in field B by tracing back to where it came from, keeping the fraction of it given
+// by the parameter.
+@compute @workgroup_size(8, 8)
+fn advect(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	let velocity = textureLoad(input_b, vec2<i32>(cell), 0).xy;
+	let source_position = vec2<f32>(cell) + 0.5 - velocity * push_constant_data.dt;
+	let value = textureSampleLevel(input_a, field_sampler, source_position / push_constant_data.grid_size, 0.0);
+	textureStore(output, vec2<i32>(cell), value * push_constant_data.parameter);
+}
+
+// Take one Jacobi iteration towards diffusing field A, where field B is the previous estimate and the parameter is how
+// far the field spreads into neighboring cells during this frame.
+@compute @workgroup_size(8, 8)
+fn diffuse(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	let position = vec2<i32>(cell);
+	let neighbor_sum = load(input_b, position + vec2<i32>(-1, 0))
+		+ load(input_b, position + vec2<i32>(1, 0))
+		+ load(input_b, position + vec2<i32>(0, -1))
+		+ load(input_b, position + vec2<i32>(0, 1));
+	let alpha = push_constant_data.parameter;
+	let value = (textureLoad(input_a, position, 0) + alpha * neighbor_sum) / (1.0 + 4.0 * alpha);
+	textureStore(output, position, value);
+}
+
+// Measure how much the velocity in field A flows out of each cell.
+@compute @workgroup_size(8, 8)
+fn divergence(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	let position = vec2<i32>(cell);
+	let left = load(input_a, position + vec2<i32>(-1, 0)).x;
+	let right = load(input_a, position + vec2<i32>(1, 0)).x;
+	let up = load(input_a, position + vec2<i32>(0, -1)).y;
+	let down = load(input_a, position + vec2<i32>(0, 1)).y;
+	textureStore(output, position, vec4<f32>(0.5 * (right - left + down - up), 0.0, 0.0, 0.0));
+}
+
+// Take one Jacobi iteration towards the pressure that cancels out the divergence in field A, where field B is the
+// previous estimate of the pressure.
+@compute @workgroup_size(8, 8)
+fn solve_pressure(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	let position = vec2<i32>(cell);
+	let neighbor_sum = load(input_b, position + vec2<i32>(-1, 0)).x
+		+ load(input_b, position + vec2<i32>(1, 0)).x
+		+ load(input_b, position + vec2<i32>(0, -1)).x
+		+ load(input_b, position + vec2<i32>(0, 1)).x;
+	let pressure = 0.25 * (neighbor_sum - textureLoad(input_a, position, 0).x);
+	textureStore(output, position, vec4<f32>(pressure, 0.0, 0.0, 0.0));
+}
+
+// Subtract the gradient of the pressure in field B from the velocity in field A so that the velocity stops diverging,
+// and stop the fluid from flowing through the edges of the grid.
+@compute @workgroup_size(8, 8)
+fn subtract_pressure_gradient(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let cell = invocation_id.xy;
+	if (is_outside_grid(cell)) {
+		return;
+	}
+	let position = vec2<i32>(cell);
+	let left = load(input_b, position + vec2<i32>(-1, 0)).x;
+	let right = load(input_b, position + vec2<i32>(1, 0)).x;
+	let up = load(input_b, position + vec2<i32>(0, -1)).x;
+	let down = load(input_b, position + vec2<i32>(0, 1)).x;
+	var velocity = textureLoad(input_a, position, 0).xy - 0.5 * vec2<f32>(right - left, down - up);
+	let last_cell = vec2<u32>(push_constant_data.grid_size) - vec2<u32>(1u);
+	velocity = select(velocity, vec2<f32>(0.0), (cell == vec2<u32>(0u)) | (cell == last_cell));
+	textureStore(output, position, vec4<f32>(velocity, 0.0, 0.0));
+}
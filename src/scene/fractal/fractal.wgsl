@@ -0,0 +1,104 @@
+// Numbers with about twice the precision of f32 are stored as the unevaluated sum of two f32s, the larger in x and the
+// smaller in y. The algorithms rely on every operation being rounded exactly as written, so none of them may be
+// simplified algebraically.
+fn two_sum(a: f32, b: f32) -> vec2<f32> {
+	let sum = a + b;
+	let b_part = sum - a;
+	return vec2<f32>(sum, (a - (sum - b_part)) + (b - b_part));
+}
+
+fn quick_two_sum(a: f32, b: f32) -> vec2<f32> {
+	let sum = a + b;
+	return vec2<f32>(sum, b - (sum - a));
+}
+
+fn two_product(a: f32, b: f32) -> vec2<f32> {
+	let product = a * b;
+	return vec2<f32>(product, fma(a, b, -product));
+}
+
+fn double_add(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+	var high = two_sum(a.x, b.x);
+	let low = two_sum(a.y, b.y);
+	high.y = high.y + low.x;
+	high = quick_two_sum(high.x, high.y);
+	high.y = high.y + low.y;
+	return quick_two_sum(high.x, high.y);
+}
+
+fn double_multiply(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+	var product = two_product(a.x, b.x);
+	product.y = product.y + (a.x * b.y + a.y * b.x);
+	return quick_two_sum(product.x, product.y);
+}
+
+struct FractalUniforms {
+	// The center of the view, with the real part in xy and the imaginary part in zw.
+	center: vec4<f32>,
+	// The constant of the Julia set, laid out like the center.
+	julia_constant: vec4<f32>,
+	// The coefficients of the cosine palette: offset, amplitude, frequency and phase.
+	palette: array<vec4<f32>, 4>,
+	resolution: vec2<f32>,
+	// Half of the height of the view in the complex plane.
+	scale: f32,
+	iteration_count: u32,
+	is_julia: u32,
+	color_offset: f32,
+	brightness: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: FractalUniforms;
+
+let escape_radius = 256.0;
+
+fn palette_color(t: f32) -> vec3<f32> {
+	let palette = uniforms.palette;
+	let color = palette[0].rgb + palette[1].rgb * cos(6.28318530718 * (palette[2].rgb * t + palette[3].rgb));
+	// The palettes are made for displaying directly, so they get converted into linear colors.
+	return pow(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(2.2));
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	// The offset from the center is small, so it fits in an f32 without losing precision relative to the view.
+	let aspect_ratio = uniforms.resolution.x / uniforms.resolution.y;
+	let offset = (2.0 * input.texture_coordinates - 1.0) * vec2<f32>(aspect_ratio, -1.0) * uniforms.scale;
+	let point_real = double_add(uniforms.center.xy, vec2<f32>(offset.x, 0.0));
+	let point_imaginary = double_add(uniforms.center.zw, vec2<f32>(offset.y, 0.0));
+
+	// The Mandelbrot set iterates from zero with the point as the constant, while Julia sets iterate from the point.
+	var z_real = vec2<f32>(0.0);
+	var z_imaginary = vec2<f32>(0.0);
+	var c_real = point_real;
+	var c_imaginary = point_imaginary;
+	if (uniforms.is_julia != 0u) {
+		z_real = point_real;
+		z_imaginary = point_imaginary;
+		c_real = uniforms.julia_constant.xy;
+		c_imaginary = uniforms.julia_constant.zw;
+	}
+
+	var iteration = 0u;
+	var magnitude_squared = 0.0;
+	for (; iteration < uniforms.iteration_count; iteration = iteration + 1u) {
+		let real_squared = double_multiply(z_real, z_real);
+		let imaginary_squared = double_multiply(z_imaginary, z_imaginary);
+		magnitude_squared = real_squared.x + imaginary_squared.x;
+		if (magnitude_squared > escape_radius * escape_radius) {
+			break;
+		}
+		let cross_product = double_multiply(z_real, z_imaginary);
+		z_imaginary = double_add(double_add(cross_product, cross_product), c_imaginary);
+		z_real = double_add(double_add(real_squared, -imaginary_squared), c_real);
+	}
+	if (iteration >= uniforms.iteration_count) {
+		return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+	}
+
+	// Smooth out the bands between iteration counts using how far past the escape radius the point got.
+	let smooth_iteration = f32(iteration) + 1.0 - log2(0.5 * log(magnitude_squared));
+	let color = palette_color(0.02 * smooth_iteration + uniforms.color_offset);
+	return vec4<f32>(uniforms.brightness * color, 1.0);
+}
@@ -0,0 +1,350 @@
+/**
+ * The relative precision of the shader's double-float arithmetic, where each number is the sum of two f32s with 24 bit
+ * significands.
+ */
+const DOUBLE_FLOAT_EPSILON: f64 = 1.0 / (1u64 << 48) as f64;
+
+/**
+ * How many units of the double-float precision a pixel has to span for neighboring pixels to stay apart.
+ */
+const PIXEL_PRECISION_MARGIN: f64 = 4.0;
+
+/**
+ * The view height that every zoom starts from, which shows the whole Mandelbrot set.
+ */
+const INITIAL_SCALE: f64 = 1.5;
+
+/**
+ * The number of e-folds of zoom at the start and end of each zoom over which the view fades in and out.
+ */
+const FADE_LENGTH: f64 = 1.0;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FractalUniforms {
+	center: [f32; 4],
+	julia_constant: [f32; 4],
+	palette: [[f32; 4]; 4],
+	resolution: [f32; 2],
+	scale: f32,
+	iteration_count: u32,
+	is_julia: u32,
+	color_offset: f32,
+	brightness: f32,
+	_padding: u32,
+}
+
+/**
+ * Endlessly zooms into the Mandelbrot set or a Julia set, one target after another. The shader does its arithmetic
+ * with pairs of f32s to get close to f64 precision, which lets zooms go about a million times deeper than f32 would
+ * allow before they reach the precision limit and move on to the next target with the next palette.
+ */
+pub struct FractalScene {
+	fractal_configuration: crate::config::FractalConfig,
+	targets: Vec<[f64; 2]>,
+	target_index: usize,
+	palette_index: usize,
+	scale: f64,
+	time: f32,
+	uniforms: FractalUniforms,
+	uniform_buffer: wgpu::Buffer,
+	uniform_bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl FractalScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		fractal_configuration: &crate::config::FractalConfig,
+	) -> Self {
+		let targets = if fractal_configuration.targets.is_empty() {
+			Self::default_targets(fractal_configuration)
+		} else {
+			fractal_configuration.targets.clone()
+		};
+
+		let uniforms = FractalUniforms {
+			center: [0.0; 4],
+			julia_constant: Self::split_complex(fractal_configuration.julia_constant),
+			palette: [[0.0; 4]; 4],
+			resolution: [
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+			],
+			scale: INITIAL_SCALE as f32,
+			iteration_count: 0,
+			is_julia: matches!(
+				fractal_configuration.kind,
+				crate::config::FractalKind::Julia
+			) as u32,
+			color_offset: 0.0,
+			brightness: 0.0,
+			_padding: 0,
+		};
+		let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Fractal scene uniform buffer"),
+			size: std::mem::size_of::<FractalUniforms>() as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let uniform_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Fractal scene uniform bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Fractal scene uniform bind group"),
+			layout: &uniform_bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: uniform_buffer.as_entire_binding(),
+			}],
+		});
+
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			"Fractal scene shader",
+			include_str!("fractal.wgsl"),
+		);
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Fractal scene pipeline layout"),
+				bind_group_layouts: &[&uniform_bind_group_layout],
+				push_constant_ranges: &[],
+			});
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Fractal scene pipeline",
+			&shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: render_target_configuration.format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+
+		let mut fractal_scene = Self {
+			fractal_configuration: fractal_configuration.clone(),
+			targets,
+			target_index: 0,
+			palette_index: 0,
+			scale: INITIAL_SCALE,
+			time: 0.0,
+			uniforms,
+			uniform_buffer,
+			uniform_bind_group,
+			render_pipeline,
+		};
+		fractal_scene.start_zoom();
+		fractal_scene
+	}
+
+	/**
+	 * Get the targets that are zoomed into when none are configured. For the Mandelbrot set, these are well known
+	 * points on its boundary. For Julia sets, these are the repelling fixed point of the iteration and some of the
+	 * points that lead to it, all of which are always on the boundary of the set.
+	 */
+	fn default_targets(fractal_configuration: &crate::config::FractalConfig) -> Vec<[f64; 2]> {
+		match fractal_configuration.kind {
+			crate::config::FractalKind::Mandelbrot => vec![
+				[-0.743_643_887_037_158_7, 0.131_825_904_205_311_97],
+				[0.001_643_721_971_153, -0.822_467_633_298_876],
+				[-0.101_096_363_845_622, 0.956_286_510_809_142],
+				[-1.250_660_4, 0.020_120_2],
+			],
+			crate::config::FractalKind::Julia => {
+				// The repelling fixed point of z² + c is (1 + sqrt(1 - 4c)) / 2, and the points that map to a point w
+				// are ±sqrt(w - c).
+				let [c_real, c_imaginary] = fractal_configuration.julia_constant;
+				let fixed_point =
+					Self::complex_square_root([1.0 - 4.0 * c_real, -4.0 * c_imaginary]);
+				let fixed_point = [0.5 * (1.0 + fixed_point[0]), 0.5 * fixed_point[1]];
+				let preimage = Self::complex_square_root([
+					fixed_point[0] - c_real,
+					fixed_point[1] - c_imaginary,
+				]);
+				let second_preimage =
+					Self::complex_square_root([preimage[0] - c_real, preimage[1] - c_imaginary]);
+				vec![
+					fixed_point,
+					[-fixed_point[0], -fixed_point[1]],
+					second_preimage,
+					[-second_preimage[0], -second_preimage[1]],
+				]
+			}
+		}
+	}
+
+	fn complex_square_root([real, imaginary]: [f64; 2]) -> [f64; 2] {
+		let magnitude = real.hypot(imaginary);
+		let root_real = (0.5 * (magnitude + real)).max(0.0).sqrt();
+		let root_imaginary = (0.5 * (magnitude - real))
+			.max(0.0)
+			.sqrt()
+			.copysign(imaginary);
+		[root_real, root_imaginary]
+	}
+
+	/**
+	 * Split both parts of a complex number into the high and low f32s that the shader adds together to get back
+	 * close to the original f64.
+	 */
+	fn split_complex([real, imaginary]: [f64; 2]) -> [f32; 4] {
+		let real_high = real as f32;
+		let imaginary_high = imaginary as f32;
+		[
+			real_high,
+			(real - real_high as f64) as f32,
+			imaginary_high,
+			(imaginary - imaginary_high as f64) as f32,
+		]
+	}
+
+	/**
+	 * Get the smallest view height that can be drawn without the pixels turning into blocks, which is when a pixel
+	 * gets close to the precision of the coordinates around the current target. Once a zoom gets this deep, it moves on
+	 * to the next target.
+	 */
+	fn precision_limit(&self) -> f64 {
+		let [real, imaginary] = self.targets[self.target_index];
+		// Points being iterated reach magnitudes of around 1 even when the target is close to 0.
+		let magnitude = (real * real + imaginary * imaginary).sqrt().max(1.0);
+		PIXEL_PRECISION_MARGIN
+			* DOUBLE_FLOAT_EPSILON
+			* magnitude
+			* self.uniforms.resolution[1].max(1.0) as f64
+	}
+
+	/**
+	 * Start zooming into the current target with the current palette from all the way out.
+	 */
+	fn start_zoom(&mut self) {
+		self.scale = INITIAL_SCALE;
+		self.uniforms.center = Self::split_complex(self.targets[self.target_index]);
+		let palette = self
+			.fractal_configuration
+			.palettes
+			.get(self.palette_index)
+			.copied()
+			.unwrap_or(crate::config::FractalPalette::Rainbow);
+		self.uniforms.palette = Self::palette_coefficients(palette);
+	}
+
+	/**
+	 * Get the offset, amplitude, frequency and phase of the cosine wave that each color channel of a palette follows.
+	 */
+	fn palette_coefficients(palette: crate::config::FractalPalette) -> [[f32; 4]; 4] {
+		let [offset, amplitude, frequency, phase] = match palette {
+			crate::config::FractalPalette::Rainbow => [
+				[0.5, 0.5, 0.5],
+				[0.5, 0.5, 0.5],
+				[1.0, 1.0, 1.0],
+				[0.0, 0.33, 0.67],
+			],
+			crate::config::FractalPalette::Sunset => [
+				[0.5, 0.5, 0.5],
+				[0.5, 0.5, 0.5],
+				[1.0, 0.7, 0.4],
+				[0.0, 0.15, 0.2],
+			],
+			crate::config::FractalPalette::Ocean => [
+				[0.2, 0.45, 0.6],
+				[0.2, 0.35, 0.4],
+				[1.0, 1.0, 1.0],
+				[0.5, 0.6, 0.7],
+			],
+			crate::config::FractalPalette::Ember => [
+				[0.8, 0.5, 0.4],
+				[0.2, 0.4, 0.2],
+				[2.0, 1.0, 1.0],
+				[0.0, 0.25, 0.25],
+			],
+			crate::config::FractalPalette::Grayscale => [
+				[0.5, 0.5, 0.5],
+				[0.5, 0.5, 0.5],
+				[1.0, 1.0, 1.0],
+				[0.0, 0.0, 0.0],
+			],
+		};
+		[offset, amplitude, frequency, phase].map(|[r, g, b]| [r, g, b, 0.0])
+	}
+}
+
+impl crate::scene::Scene for FractalScene {
+	fn resize(
+		&mut self,
+		_device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.uniforms.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		];
+	}
+
+	fn update(&mut self, dt: f32) {
+		self.time += dt;
+		self.scale *= (-self.fractal_configuration.zoom_speed as f64 * dt as f64).exp();
+		let precision_limit = self.precision_limit();
+		if self.scale < precision_limit {
+			self.target_index = (self.target_index + 1) % self.targets.len();
+			self.palette_index =
+				(self.palette_index + 1) % self.fractal_configuration.palettes.len().max(1);
+			self.start_zoom();
+		}
+
+		// Deeper zooms need more iterations to tell which points escape, so iterations are added as the zoom goes on.
+		let zoom_depth = (INITIAL_SCALE / self.scale).ln();
+		self.uniforms.scale = self.scale as f32;
+		self.uniforms.iteration_count = ((self.fractal_configuration.initial_iteration_count as f64
+			+ self.fractal_configuration.iterations_per_zoom_depth as f64 * zoom_depth)
+			as u32)
+			.min(self.fractal_configuration.maximum_iteration_count);
+		self.uniforms.color_offset = self.time * self.fractal_configuration.color_cycle_speed;
+		let fade = (zoom_depth / FADE_LENGTH)
+			.min((self.scale / precision_limit).ln() / FADE_LENGTH)
+			.clamp(0.0, 1.0);
+		self.uniforms.brightness = fade as f32;
+	}
+
+	fn uses_tonemapping(&self) -> bool {
+		false
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&self.uniforms));
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Fractal scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+}
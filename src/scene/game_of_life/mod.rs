@@ -0,0 +1,445 @@
+mod pattern;
+
+use wgpu::util::DeviceExt;
+
+/**
+ * The width and height of the workgroups of the simulation shader.
+ */
+const WORKGROUP_SIZE: u32 = 8;
+
+/**
+ * The most ticks that get simulated in a single frame, so that slow frames don't get slower trying to catch up.
+ */
+const MAXIMUM_TICKS_PER_FRAME: u32 = 8;
+
+/**
+ * The rule of Conway's Game of Life, which is used when no valid rule is configured.
+ */
+const DEFAULT_RULE: &str = "B3/S23";
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SimulationPushConstantData {
+	grid_size: [u32; 2],
+	birth_mask: u32,
+	survival_mask: u32,
+	random_density: f32,
+	seed_from_pattern: u32,
+}
+
+/**
+ * Matches the state that the simulation shader keeps between ticks for detecting stagnation.
+ */
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SimulationState {
+	population: u32,
+	board_hash: u32,
+	should_reseed: u32,
+	reseed_count: u32,
+	history_length: u32,
+	history_index: u32,
+	history: [[u32; 2]; 32],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct RenderPushConstantData {
+	grid_size: [u32; 2],
+	resolution: [f32; 2],
+}
+
+/**
+ * Parse a rule in B/S notation, such as B3/S23 for Conway's Game of Life, into bit masks of the neighbor counts that
+ * make dead cells come alive and that keep live cells alive.
+ */
+fn parse_rule(rule: &str) -> Option<(u32, u32)> {
+	let mut birth_mask = None;
+	let mut survival_mask = None;
+	for part in rule.split('/') {
+		let mut characters = part.trim().chars();
+		let kind = characters.next()?.to_ascii_uppercase();
+		let mask = characters.try_fold(0, |mask, character| {
+			character
+				.to_digit(10)
+				.filter(|&neighbor_count| neighbor_count <= 8)
+				.map(|neighbor_count| mask | 1 << neighbor_count)
+		})?;
+		match kind {
+			'B' => birth_mask = Some(mask),
+			'S' => survival_mask = Some(mask),
+			_ => return None,
+		}
+	}
+	Some((birth_mask?, survival_mask?))
+}
+
+/**
+ * Simulates a cellular automaton like Conway's Game of Life on the GPU, coloring live cells by how long they have been
+ * alive. Boards that die out or settle into repeating themselves are detected and seeded again.
+ */
+pub struct GameOfLifeScene {
+	simulation_push_constant_data: SimulationPushConstantData,
+	render_push_constant_data: RenderPushConstantData,
+	seconds_per_tick: f32,
+	seconds_since_tick: f32,
+	cell_buffers: crate::scene::utilities::compute::PingPongBuffers,
+	simulation_state_bind_group: wgpu::BindGroup,
+	step_pipeline: wgpu::ComputePipeline,
+	detect_stagnation_pipeline: wgpu::ComputePipeline,
+	cell_bind_groups: [wgpu::BindGroup; 2],
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl GameOfLifeScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		game_of_life_configuration: &crate::config::GameOfLifeConfig,
+	) -> Self {
+		let [grid_width, grid_height] = game_of_life_configuration
+			.grid_resolution
+			.map(|size| size.max(1));
+		let cell_count = (grid_width * grid_height) as usize;
+
+		// Load the pattern, falling back to random noise if there isn't one or it can't be loaded.
+		let pattern = game_of_life_configuration
+			.pattern
+			.as_ref()
+			.and_then(|path| {
+				pattern::Pattern::load(path)
+					.map_err(|error| {
+						log::warn!("Could not load pattern {}: {}", path.display(), error)
+					})
+					.ok()
+			});
+		// Storage buffers can't be empty, so a single unused cell stands in for the pattern when seeding randomly.
+		let mut pattern_cells = vec![0u32; if pattern.is_some() { cell_count } else { 1 }];
+		if let Some(pattern) = &pattern {
+			if pattern.width > grid_width || pattern.height > grid_height {
+				log::warn!(
+					"The {}x{} pattern doesn't fit in the {}x{} grid, so it will be cut off",
+					pattern.width,
+					pattern.height,
+					grid_width,
+					grid_height
+				);
+			}
+			// Center the pattern on the grid.
+			let offset = [
+				(grid_width as i64 - pattern.width as i64) / 2,
+				(grid_height as i64 - pattern.height as i64) / 2,
+			];
+			for [column, row] in &pattern.live_cells {
+				let x = *column as i64 + offset[0];
+				let y = *row as i64 + offset[1];
+				if (0..grid_width as i64).contains(&x) && (0..grid_height as i64).contains(&y) {
+					pattern_cells[(y * grid_width as i64 + x) as usize] = 1;
+				}
+			}
+		}
+
+		// Use the configured rule, then the pattern's rule, and then Conway's Game of Life.
+		let rule = game_of_life_configuration
+			.rule
+			.as_deref()
+			.or_else(|| pattern.as_ref().and_then(|pattern| pattern.rule.as_deref()))
+			.unwrap_or(DEFAULT_RULE);
+		let (birth_mask, survival_mask) = parse_rule(rule).unwrap_or_else(|| {
+			log::warn!("Ignoring invalid rule {}", rule);
+			parse_rule(DEFAULT_RULE).unwrap()
+		});
+
+		// Create the buffers. The board starts out empty, and the first tick seeds it.
+		let cell_buffers = crate::scene::utilities::compute::PingPongBuffers::new(
+			device,
+			"Game of life scene cell",
+			bytemuck::cast_slice(&vec![0u32; cell_count]),
+			wgpu::BufferUsages::empty(),
+		);
+		let simulation_state_buffer =
+			device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+				label: Some("Game of life scene simulation state buffer"),
+				contents: bytemuck::bytes_of(&SimulationState {
+					should_reseed: 1,
+					..bytemuck::Zeroable::zeroed()
+				}),
+				usage: wgpu::BufferUsages::STORAGE,
+			});
+		let pattern_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("Game of life scene pattern buffer"),
+			contents: bytemuck::cast_slice(&pattern_cells),
+			usage: wgpu::BufferUsages::STORAGE,
+		});
+		let simulation_state_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Game of life scene simulation state bind group layout"),
+				entries: &[
+					wgpu::BindGroupLayoutEntry {
+						binding: 0,
+						visibility: wgpu::ShaderStages::COMPUTE,
+						ty: wgpu::BindingType::Buffer {
+							ty: wgpu::BufferBindingType::Storage { read_only: false },
+							has_dynamic_offset: false,
+							min_binding_size: None,
+						},
+						count: None,
+					},
+					wgpu::BindGroupLayoutEntry {
+						binding: 1,
+						visibility: wgpu::ShaderStages::COMPUTE,
+						ty: wgpu::BindingType::Buffer {
+							ty: wgpu::BufferBindingType::Storage { read_only: true },
+							has_dynamic_offset: false,
+							min_binding_size: None,
+						},
+						count: None,
+					},
+				],
+			});
+		let simulation_state_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Game of life scene simulation state bind group"),
+			layout: &simulation_state_bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry {
+					binding: 0,
+					resource: simulation_state_buffer.as_entire_binding(),
+				},
+				wgpu::BindGroupEntry {
+					binding: 1,
+					resource: pattern_buffer.as_entire_binding(),
+				},
+			],
+		});
+
+		// Create the simulation pipelines.
+		let simulation_shader_module =
+			device.create_shader_module(&wgpu::include_wgsl!("simulate.wgsl"));
+		let simulation_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Game of life scene simulation pipeline layout"),
+				bind_group_layouts: &[
+					cell_buffers.bind_group_layout(),
+					&simulation_state_bind_group_layout,
+				],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::COMPUTE,
+					range: 0..std::mem::size_of::<SimulationPushConstantData>() as u32,
+				}],
+			});
+		let step_pipeline = crate::scene::utilities::compute::create_compute_pipeline(
+			device,
+			"Game of life scene step pipeline",
+			&simulation_shader_module,
+			"simulate_step",
+			&simulation_pipeline_layout,
+		);
+		let detect_stagnation_pipeline = crate::scene::utilities::compute::create_compute_pipeline(
+			device,
+			"Game of life scene detect stagnation pipeline",
+			&simulation_shader_module,
+			"detect_stagnation",
+			&simulation_pipeline_layout,
+		);
+
+		// Create the render pipeline along with a bind group for reading each of the cell buffers.
+		let cell_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Game of life scene cell bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Storage { read_only: true },
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let cell_bind_groups = [0, 1].map(|buffer_index| {
+			device.create_bind_group(&wgpu::BindGroupDescriptor {
+				label: Some("Game of life scene cell bind group"),
+				layout: &cell_bind_group_layout,
+				entries: &[wgpu::BindGroupEntry {
+					binding: 0,
+					resource: cell_buffers.buffers()[buffer_index].as_entire_binding(),
+				}],
+			})
+		});
+		let render_shader_module =
+			crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+				device,
+				"Game of life scene render shader",
+				include_str!("render.wgsl"),
+			);
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Game of life scene render pipeline layout"),
+				bind_group_layouts: &[&cell_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::FRAGMENT,
+					range: 0..std::mem::size_of::<RenderPushConstantData>() as u32,
+				}],
+			});
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Game of life scene render pipeline",
+			&render_shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: render_target_configuration.format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+
+		let seconds_per_tick = 1.0
+			/ game_of_life_configuration
+				.ticks_per_second
+				.max(f32::EPSILON);
+		Self {
+			simulation_push_constant_data: SimulationPushConstantData {
+				grid_size: [grid_width, grid_height],
+				birth_mask,
+				survival_mask,
+				random_density: game_of_life_configuration.random_density,
+				seed_from_pattern: pattern.is_some() as u32,
+			},
+			render_push_constant_data: RenderPushConstantData {
+				grid_size: [grid_width, grid_height],
+				resolution: [
+					render_target_configuration.width as f32,
+					render_target_configuration.height as f32,
+				],
+			},
+			seconds_per_tick,
+			// Make the first frame tick right away so that the board gets seeded.
+			seconds_since_tick: seconds_per_tick,
+			cell_buffers,
+			simulation_state_bind_group,
+			step_pipeline,
+			detect_stagnation_pipeline,
+			cell_bind_groups,
+			render_pipeline,
+		}
+	}
+}
+
+impl crate::scene::Scene for GameOfLifeScene {
+	fn resize(
+		&mut self,
+		_: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.render_push_constant_data.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		];
+	}
+
+	fn update(&mut self, dt: f32) {
+		self.seconds_since_tick += dt;
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		_: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		let tick_count =
+			((self.seconds_since_tick / self.seconds_per_tick) as u32).min(MAXIMUM_TICKS_PER_FRAME);
+		self.seconds_since_tick = (self.seconds_since_tick
+			- tick_count as f32 * self.seconds_per_tick)
+			.min(self.seconds_per_tick);
+
+		for _ in 0..tick_count {
+			let mut compute_pass =
+				command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+					label: Some("Game of life scene simulation compute pass"),
+				});
+			compute_pass.set_pipeline(&self.step_pipeline);
+			compute_pass
+				.set_push_constants(0, bytemuck::bytes_of(&self.simulation_push_constant_data));
+			compute_pass.set_bind_group(0, self.cell_buffers.bind_group(), &[]);
+			compute_pass.set_bind_group(1, &self.simulation_state_bind_group, &[]);
+			compute_pass.dispatch(
+				crate::scene::utilities::compute::workgroup_count(
+					self.simulation_push_constant_data.grid_size[0],
+					WORKGROUP_SIZE,
+				),
+				crate::scene::utilities::compute::workgroup_count(
+					self.simulation_push_constant_data.grid_size[1],
+					WORKGROUP_SIZE,
+				),
+				1,
+			);
+			compute_pass.set_pipeline(&self.detect_stagnation_pipeline);
+			compute_pass.dispatch(1, 1, 1);
+			drop(compute_pass);
+			self.cell_buffers.swap();
+		}
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Game of life scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_bind_group(
+			0,
+			&self.cell_bind_groups[self.cell_buffers.current_index()],
+			&[],
+		);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&self.render_push_constant_data),
+		);
+		render_pass.draw(0..3, 0..1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::parse_rule;
+
+	#[test]
+	fn parses_birth_and_survival_counts() {
+		assert_eq!(parse_rule("B3/S23"), Some((1 << 3, 1 << 2 | 1 << 3)));
+		assert_eq!(
+			parse_rule("B36/S23"),
+			Some((1 << 3 | 1 << 6, 1 << 2 | 1 << 3))
+		);
+	}
+
+	#[test]
+	fn accepts_either_order_and_lowercase() {
+		assert_eq!(parse_rule("s23/b3"), parse_rule("B3/S23"));
+		assert_eq!(parse_rule(" B3 / S23 "), parse_rule("B3/S23"));
+	}
+
+	#[test]
+	fn accepts_empty_counts() {
+		assert_eq!(parse_rule("B2/S"), Some((1 << 2, 0)));
+		assert_eq!(parse_rule("B0/S8"), Some((1, 1 << 8)));
+	}
+
+	#[test]
+	fn rejects_invalid_rules() {
+		assert_eq!(parse_rule("B3"), None);
+		assert_eq!(parse_rule("B9/S23"), None);
+		assert_eq!(parse_rule("B3/X23"), None);
+		assert_eq!(parse_rule("23/3"), None);
+		assert_eq!(parse_rule(""), None);
+	}
+}
@@ -0,0 +1,170 @@
+/**
+ * A pattern of live cells loaded from a run length encoded (.rle) file.
+ */
+pub struct Pattern {
+	pub width: u32,
+	pub height: u32,
+	/**
+	 * The column and row of every live cell, counting from the top left corner of the pattern.
+	 */
+	pub live_cells: Vec<[u32; 2]>,
+	/**
+	 * The rule that the pattern was made for, if its header names one.
+	 */
+	pub rule: Option<String>,
+}
+
+impl Pattern {
+	/**
+	 * Load a pattern in the RLE format, which starts with a header like `x = 3, y = 3, rule = B3/S23` that can be
+	 * preceded by comment lines starting with #. After it come runs of cells such as `2o` for two live cells or `3b`
+	 * for three dead cells, with `$` ending rows and `!` ending the pattern.
+	 */
+	pub fn load(path: &std::path::Path) -> Result<Self, String> {
+		let contents = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+		let mut lines = contents
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+		// Read the header.
+		let header = lines.next().ok_or("missing header")?;
+		let mut width = None;
+		let mut height = None;
+		let mut rule = None;
+		for field in header.split(',') {
+			let (key, value) = field
+				.split_once('=')
+				.ok_or_else(|| format!("invalid header field {:?}", field))?;
+			let value = value.trim();
+			match key.trim() {
+				"x" => {
+					width = Some(
+						value
+							.parse::<u32>()
+							.map_err(|_| format!("invalid width {}", value))?,
+					)
+				}
+				"y" => {
+					height = Some(
+						value
+							.parse::<u32>()
+							.map_err(|_| format!("invalid height {}", value))?,
+					)
+				}
+				"rule" => rule = Some(value.to_owned()),
+				_ => (),
+			}
+		}
+		let width = width.ok_or("missing width in header")?;
+		let height = height.ok_or("missing height in header")?;
+
+		// Read the runs of cells.
+		let mut live_cells = Vec::new();
+		let mut column = 0;
+		let mut row = 0;
+		let mut run_length = None;
+		'lines: for line in lines {
+			for character in line.chars() {
+				match character {
+					'0'..='9' => {
+						let digit = character.to_digit(10).unwrap();
+						run_length = Some(run_length.unwrap_or(0) * 10 + digit);
+						continue;
+					}
+					'b' | '.' => column += run_length.unwrap_or(1),
+					'$' => {
+						row += run_length.unwrap_or(1);
+						column = 0;
+					}
+					'!' => break 'lines,
+					character if character.is_whitespace() => continue,
+					// Patterns for rules with more than two states use other letters for live cells.
+					_ => {
+						for _ in 0..run_length.unwrap_or(1) {
+							if column < width && row < height {
+								live_cells.push([column, row]);
+							}
+							column += 1;
+						}
+					}
+				}
+				run_length = None;
+			}
+		}
+
+		Ok(Self {
+			width,
+			height,
+			live_cells,
+			rule,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Pattern;
+
+	fn load(name: &str, contents: &str) -> Result<Pattern, String> {
+		let path = std::env::temp_dir().join(format!(
+			"game-of-life-pattern-{}-{}.rle",
+			std::process::id(),
+			name
+		));
+		std::fs::write(&path, contents).unwrap();
+		let pattern = Pattern::load(&path);
+		std::fs::remove_file(&path).unwrap();
+		pattern
+	}
+
+	#[test]
+	fn reads_the_header_and_skips_comments() {
+		let pattern = load(
+			"header",
+			"#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n",
+		)
+		.unwrap();
+		assert_eq!((pattern.width, pattern.height), (3, 3));
+		assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+		assert_eq!(
+			pattern.live_cells,
+			vec![[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]]
+		);
+	}
+
+	#[test]
+	fn expands_run_counts() {
+		let pattern = load("runs", "x = 12, y = 1\n2b10o!").unwrap();
+		assert_eq!(pattern.rule, None);
+		assert_eq!(
+			pattern.live_cells,
+			(2..12).map(|column| [column, 0]).collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn skips_rows_with_counted_row_ends() {
+		let pattern = load("rows", "x = 2, y = 4\no\n3$bo!").unwrap();
+		assert_eq!(pattern.live_cells, vec![[0, 0], [1, 3]]);
+	}
+
+	#[test]
+	fn stops_at_the_terminator() {
+		let pattern = load("terminator", "x = 3, y = 2\n3o!\n3o$3o").unwrap();
+		assert_eq!(pattern.live_cells, vec![[0, 0], [1, 0], [2, 0]]);
+	}
+
+	#[test]
+	fn drops_cells_outside_the_declared_size() {
+		let pattern = load("clipped", "x = 2, y = 1\n4o$o!").unwrap();
+		assert_eq!(pattern.live_cells, vec![[0, 0], [1, 0]]);
+	}
+
+	#[test]
+	fn rejects_incomplete_headers() {
+		assert!(load("empty", "#C Only a comment\n").is_err());
+		assert!(load("no-height", "x = 3\n3o!").is_err());
+		assert!(load("bad-width", "x = three, y = 1\n3o!").is_err());
+	}
+}
@@ -0,0 +1,40 @@
+struct PushConstantData {
+	grid_size: vec2<u32>,
+	resolution: vec2<f32>,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var<storage, read> cells: array<u32>;
+
+// Newborn cells are a warm white that cools through green into blue as they age.
+fn age_color(age: u32) -> vec3<f32> {
+	if (age == 0u) {
+		return vec3<f32>(0.005, 0.005, 0.01);
+	}
+	let newborn_color = vec3<f32>(1.0, 0.85, 0.5);
+	let young_color = vec3<f32>(0.2, 0.8, 0.35);
+	let old_color = vec3<f32>(0.1, 0.15, 0.8);
+	let age_amount = clamp(log2(f32(age)) / 8.0, 0.0, 1.0);
+	if (age_amount < 0.5) {
+		return mix(newborn_color, young_color, 2.0 * age_amount);
+	}
+	return mix(young_color, old_color, 2.0 * age_amount - 1.0);
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	let grid_size = vec2<f32>(push_constant_data.grid_size);
+	let grid_position = input.texture_coordinates * grid_size;
+	let cell = min(vec2<u32>(grid_position), push_constant_data.grid_size - vec2<u32>(1u));
+	let age = cells[cell.y * push_constant_data.grid_size.x + cell.x];
+
+	// Cells that are big enough on the screen get a thin dark border so that the grid can be made out.
+	let cell_size_in_pixels = push_constant_data.resolution / grid_size;
+	let distance_to_edge = min(fract(grid_position), 1.0 - fract(grid_position)) * cell_size_in_pixels;
+	let is_on_border = min(distance_to_edge.x, distance_to_edge.y) < 0.5;
+	let is_large = min(cell_size_in_pixels.x, cell_size_in_pixels.y) >= 4.0;
+	let border = select(1.0, 0.6, is_on_border && is_large);
+	return vec4<f32>(age_color(age) * border, 1.0);
+}
@@ -0,0 +1,126 @@
+struct PushConstantData {
+	grid_size: vec2<u32>,
+	birth_mask: u32,
+	survival_mask: u32,
+	random_density: f32,
+	seed_from_pattern: u32,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+// A summary of each board, made of its population and a hash of where its live cells are, gets remembered for a
+// number of ticks so that boards which settle into repeating themselves can be detected.
+let history_capacity = 32u;
+
+struct SimulationState {
+	population: atomic<u32>,
+	board_hash: atomic<u32>,
+	should_reseed: u32,
+	reseed_count: u32,
+	history_length: u32,
+	history_index: u32,
+	history: array<vec2<u32>, 32>,
+};
+
+// Cells hold how many ticks they have been alive for, where 0 means that they are dead.
+@group(0) @binding(0)
+var<storage, read> source_cells: array<u32>;
+@group(0) @binding(1)
+var<storage, read_write> destination_cells: array<u32>;
+
+@group(1) @binding(0)
+var<storage, read_write> simulation_state: SimulationState;
+@group(1) @binding(1)
+var<storage, read> pattern_cells: array<u32>;
+
+let maximum_age = 100000u;
+
+// The PCG hash from "Hash Functions for GPU Rendering" by Jarzynski and Olano.
+fn hash(value: u32) -> u32 {
+	let state = value * 747796405u + 2891336453u;
+	let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+	return (word >> 22u) ^ word;
+}
+
+// The grid wraps around at its edges.
+fn cell_index(position: vec2<i32>) -> u32 {
+	let grid_size = vec2<i32>(push_constant_data.grid_size);
+	let wrapped_position = (position % grid_size + grid_size) % grid_size;
+	return u32(wrapped_position.y * grid_size.x + wrapped_position.x);
+}
+
+// A pattern only seeds the first board, since it would just settle the same way again, so later boards are random.
+fn is_seeded_alive(index: u32) -> bool {
+	if (push_constant_data.seed_from_pattern != 0u && simulation_state.reseed_count == 0u) {
+		return pattern_cells[index] != 0u;
+	}
+	let random_value = f32(hash(index ^ hash(simulation_state.reseed_count))) / 4294967295.0;
+	return random_value < push_constant_data.random_density;
+}
+
+@compute @workgroup_size(8, 8)
+fn simulate_step(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (any(invocation_id.xy >= push_constant_data.grid_size)) {
+		return;
+	}
+	let position = vec2<i32>(invocation_id.xy);
+	let index = cell_index(position);
+
+	var age = 0u;
+	if (simulation_state.should_reseed != 0u) {
+		age = select(0u, 1u, is_seeded_alive(index));
+	} else {
+		var neighbor_count = 0u;
+		for (var y = -1; y <= 1; y = y + 1) {
+			for (var x = -1; x <= 1; x = x + 1) {
+				if ((x != 0 || y != 0) && source_cells[cell_index(position + vec2<i32>(x, y))] != 0u) {
+					neighbor_count = neighbor_count + 1u;
+				}
+			}
+		}
+		let previous_age = source_cells[index];
+		if (previous_age != 0u) {
+			let survives = ((push_constant_data.survival_mask >> neighbor_count) & 1u) != 0u;
+			age = select(0u, min(previous_age + 1u, maximum_age), survives);
+		} else {
+			let is_born = ((push_constant_data.birth_mask >> neighbor_count) & 1u) != 0u;
+			age = select(0u, 1u, is_born);
+		}
+	}
+	destination_cells[index] = age;
+
+	if (age != 0u) {
+		atomicAdd(&simulation_state.population, 1u);
+		atomicAdd(&simulation_state.board_hash, hash(index));
+	}
+}
+
+// Runs after every step to check whether the new board has been seen recently, in which case the next step seeds a
+// new board instead.
+@compute @workgroup_size(1)
+fn detect_stagnation() {
+	let board_summary = vec2<u32>(
+		atomicLoad(&simulation_state.population),
+		atomicLoad(&simulation_state.board_hash),
+	);
+	atomicStore(&simulation_state.population, 0u);
+	atomicStore(&simulation_state.board_hash, 0u);
+
+	// Boards from before the latest seeding don't count.
+	if (simulation_state.should_reseed != 0u) {
+		simulation_state.should_reseed = 0u;
+		simulation_state.reseed_count = simulation_state.reseed_count + 1u;
+		simulation_state.history_length = 0u;
+		simulation_state.history_index = 0u;
+	}
+
+	for (var i = 0u; i < simulation_state.history_length; i = i + 1u) {
+		if (all(simulation_state.history[i] == board_summary)) {
+			simulation_state.should_reseed = 1u;
+			return;
+		}
+	}
+	simulation_state.history[simulation_state.history_index] = board_summary;
+	simulation_state.history_index = (simulation_state.history_index + 1u) % history_capacity;
+	simulation_state.history_length = min(simulation_state.history_length + 1u, history_capacity);
+}
@@ -10,10 +10,14 @@ struct HelloWorldTriangleVertex {
 pub struct HelloWorldTriangleScene {
 	render_pipeline: wgpu::RenderPipeline,
 	vertex_buffer: wgpu::Buffer,
+	multisampled_color_target: crate::scene::utilities::render_target::MultisampledColorTarget,
 }
 
 impl HelloWorldTriangleScene {
-	pub fn new(device: &wgpu::Device, surface_configuration: &wgpu::SurfaceConfiguration) -> Self {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) -> Self {
 		let shader_module = device.create_shader_module(&wgpu::include_wgsl!("shader.wgsl"));
 		let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
 			label: Some("Hello world triangle scene vertex buffer"),
@@ -56,25 +60,43 @@ impl HelloWorldTriangleScene {
 				module: &shader_module,
 				entry_point: "fragment_stage",
 				targets: &[wgpu::ColorTargetState {
-					format: surface_configuration.format,
+					format: render_target_configuration.format,
 					blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
 					write_mask: wgpu::ColorWrites::all(),
 				}],
 			}),
 			primitive: wgpu::PrimitiveState::default(),
 			depth_stencil: None,
-			multisample: wgpu::MultisampleState::default(),
+			multisample: render_target_configuration.multisample_state(),
 			multiview: None,
 		});
+		let multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Hello world triangle scene",
+			);
 		Self {
 			render_pipeline,
 			vertex_buffer,
+			multisampled_color_target,
 		}
 	}
 }
 
 impl crate::scene::Scene for HelloWorldTriangleScene {
-	fn resize(&mut self, _: &wgpu::Device, _: &wgpu::SurfaceConfiguration) {}
+	fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Hello world triangle scene",
+			);
+	}
 
 	fn update(&mut self, _: f32) {}
 
@@ -86,19 +108,15 @@ impl crate::scene::Scene for HelloWorldTriangleScene {
 	) {
 		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
 			label: Some("Hello world triangle scene render pass"),
-			color_attachments: &[wgpu::RenderPassColorAttachment {
-				view: output_texture_view,
-				resolve_target: None,
-				ops: wgpu::Operations {
-					load: wgpu::LoadOp::Clear(wgpu::Color {
-						r: 0.5,
-						g: 0.5,
-						b: 0.5,
-						a: 1.0,
-					}),
-					store: true,
-				},
-			}],
+			color_attachments: &[self.multisampled_color_target.color_attachment(
+				output_texture_view,
+				wgpu::LoadOp::Clear(wgpu::Color {
+					r: 0.5,
+					g: 0.5,
+					b: 0.5,
+					a: 1.0,
+				}),
+			)],
 			depth_stencil_attachment: None,
 		});
 		render_pass.set_pipeline(&self.render_pipeline);
@@ -1,12 +1,162 @@
+pub mod boids;
 pub mod bouncing_cube;
+pub mod fluid;
+pub mod fractal;
+pub mod game_of_life;
 pub mod hello_world_triangle;
+pub mod particles;
+pub mod raymarching;
+pub mod shadertoy;
+pub mod slideshow;
+pub mod starfield;
+pub mod terrain;
 pub mod utilities;
 
+/**
+ * Where the cursor is over the window, in pixels from the top left corner, and whether the left mouse button is held.
+ * The position is None while the cursor is outside of the window.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CursorState {
+	pub position: Option<[f32; 2]>,
+	pub is_pressed: bool,
+}
+
 /**
  * List required functionality of all scenes.
  */
 pub trait Scene {
-	fn resize(&mut self, _: &wgpu::Device, _: &wgpu::SurfaceConfiguration);
+	fn resize(
+		&mut self,
+		_: &wgpu::Device,
+		_: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	);
 	fn update(&mut self, _: f32);
 	fn render(&mut self, _: &mut wgpu::CommandEncoder, _: &wgpu::Queue, _: &wgpu::TextureView);
+
+	/**
+	 * Whether bright parts of this scene should bloom after it is rendered. Scenes opt into this since it costs a
+	 * number of extra passes every frame.
+	 */
+	fn uses_bloom(&self) -> bool {
+		false
+	}
+
+	/**
+	 * Whether this scene renders high dynamic range colors that have to be tonemapped. Scenes that already output
+	 * displayable colors can opt out so that they are shown as they are.
+	 */
+	fn uses_tonemapping(&self) -> bool {
+		true
+	}
+
+	/**
+	 * Called whenever the cursor moves or the left mouse button is pressed or released.
+	 */
+	fn cursor_changed(&mut self, _: &CursorState) {}
+
+	/**
+	 * Called before every update with the latest analysis of the audio input, while there is one.
+	 */
+	fn audio_changed(&mut self, _: &crate::audio::AudioFeatures) {}
+
+	/**
+	 * Get the state of the simulation so that the scene can carry on from it after a restart. Scenes that start over
+	 * every time have nothing to save.
+	 */
+	fn save_state(&self) -> Option<serde_json::Value> {
+		None
+	}
+
+	/**
+	 * Carry on from state that `save_state` returned before. State that can't be used is rejected with an error, and
+	 * the scene carries on as it was.
+	 */
+	fn restore_state(&mut self, _: serde_json::Value) -> Result<(), String> {
+		Ok(())
+	}
+}
+
+/**
+ * Create the scene that the configuration asks for.
+ */
+pub fn create_scene(
+	device: &wgpu::Device,
+	queue: &wgpu::Queue,
+	render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	config: &crate::config::Config,
+) -> Box<dyn Scene> {
+	match config.scene_kind() {
+		crate::config::SceneKind::BouncingCube => {
+			Box::new(crate::scene::bouncing_cube::BouncingCubeScene::new(
+				device,
+				render_target_configuration,
+			))
+		}
+		crate::config::SceneKind::HelloWorldTriangle => Box::new(
+			crate::scene::hello_world_triangle::HelloWorldTriangleScene::new(
+				device,
+				render_target_configuration,
+			),
+		),
+		crate::config::SceneKind::Shadertoy => {
+			Box::new(crate::scene::shadertoy::ShadertoyScene::new(
+				device,
+				queue,
+				render_target_configuration,
+				&config.shadertoy,
+			))
+		}
+		crate::config::SceneKind::Particles => {
+			Box::new(crate::scene::particles::ParticlesScene::new(
+				device,
+				render_target_configuration,
+				&config.particles,
+			))
+		}
+		crate::config::SceneKind::GameOfLife => {
+			Box::new(crate::scene::game_of_life::GameOfLifeScene::new(
+				device,
+				render_target_configuration,
+				&config.game_of_life,
+			))
+		}
+		crate::config::SceneKind::Fluid => Box::new(crate::scene::fluid::FluidScene::new(
+			device,
+			render_target_configuration,
+			&config.fluid,
+		)),
+		crate::config::SceneKind::Boids => Box::new(crate::scene::boids::BoidsScene::new(
+			device,
+			render_target_configuration,
+			&config.boids,
+		)),
+		crate::config::SceneKind::Raymarching => {
+			Box::new(crate::scene::raymarching::RaymarchingScene::new(
+				device,
+				render_target_configuration,
+				&config.raymarching,
+			))
+		}
+		crate::config::SceneKind::Fractal => Box::new(crate::scene::fractal::FractalScene::new(
+			device,
+			render_target_configuration,
+			&config.fractal,
+		)),
+		crate::config::SceneKind::Terrain => Box::new(crate::scene::terrain::TerrainScene::new(
+			device,
+			render_target_configuration,
+			&config.terrain,
+		)),
+		crate::config::SceneKind::Starfield => Box::new(crate::scene::starfield::StarfieldScene::new(
+			device,
+			render_target_configuration,
+			&config.starfield,
+		)),
+		crate::config::SceneKind::Slideshow => Box::new(crate::scene::slideshow::SlideshowScene::new(
+			device,
+			render_target_configuration,
+			&config.slideshow,
+		)),
+	}
 }
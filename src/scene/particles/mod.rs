@@ -0,0 +1,377 @@
+use rand::Rng;
+use wgpu::util::DeviceExt;
+
+/**
+ * The most attractors that can be configured, not counting the one that can follow the cursor.
+ */
+const MAXIMUM_ATTRACTOR_COUNT: usize = 8;
+
+/**
+ * The number of particles that each workgroup of the simulation shader handles.
+ */
+const WORKGROUP_SIZE: u32 = 64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Particle {
+	position: [f32; 2],
+	velocity: [f32; 2],
+}
+
+impl Particle {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+		wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Instance,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SimulationUniforms {
+	attractors: [[f32; 4]; MAXIMUM_ATTRACTOR_COUNT + 1],
+	attractor_count: u32,
+	particle_count: u32,
+	dt: f32,
+	damping: f32,
+	bounds: [f32; 2],
+	_padding: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	resolution: [f32; 2],
+	particle_size: f32,
+	brightness: f32,
+}
+
+/**
+ * Simulates a large number of particles being pulled around by attractors on the GPU, drawing each of them as a small
+ * glowing sprite. The world spans from -1 to 1 vertically and as far horizontally as the aspect ratio of the screen
+ * allows, and particles bounce off of its edges.
+ */
+pub struct ParticlesScene {
+	particles_configuration: crate::config::ParticlesConfig,
+	simulation_uniforms: SimulationUniforms,
+	push_constant_data: PushConstantData,
+	particle_buffers: crate::scene::utilities::compute::PingPongBuffers,
+	simulation_uniform_buffer: wgpu::Buffer,
+	simulation_uniform_bind_group: wgpu::BindGroup,
+	simulation_pipeline: wgpu::ComputePipeline,
+	render_pipeline: wgpu::RenderPipeline,
+	multisampled_color_target: crate::scene::utilities::render_target::MultisampledColorTarget,
+}
+
+impl ParticlesScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		particles_configuration: &crate::config::ParticlesConfig,
+	) -> Self {
+		if particles_configuration.attractors.len() > MAXIMUM_ATTRACTOR_COUNT {
+			log::warn!(
+				"Only the first {} of the {} configured attractors will be used",
+				MAXIMUM_ATTRACTOR_COUNT,
+				particles_configuration.attractors.len()
+			);
+		}
+		// Storage buffers can't be empty, so there's always at least one particle.
+		let mut particles_configuration = particles_configuration.clone();
+		if particles_configuration.particle_count == 0 {
+			log::warn!("The particle count has to be at least 1, so 1 particle will be used");
+			particles_configuration.particle_count = 1;
+		}
+		let bounds = Self::bounds(render_target_configuration);
+
+		// Scatter the particles across the screen with small random velocities.
+		let mut random_number_generator = rand::thread_rng();
+		let particles = (0..particles_configuration.particle_count)
+			.map(|_| Particle {
+				position: [
+					random_number_generator.gen_range(-bounds[0]..=bounds[0]),
+					random_number_generator.gen_range(-bounds[1]..=bounds[1]),
+				],
+				velocity: [
+					random_number_generator.gen_range(-0.1..=0.1),
+					random_number_generator.gen_range(-0.1..=0.1),
+				],
+			})
+			.collect::<Vec<_>>();
+		let particle_buffers = crate::scene::utilities::compute::PingPongBuffers::new(
+			device,
+			"Particles scene particle",
+			bytemuck::cast_slice(&particles),
+			wgpu::BufferUsages::VERTEX,
+		);
+
+		// Create the simulation uniforms, which get their attractors filled in every frame.
+		let simulation_uniforms = SimulationUniforms {
+			attractors: [[0.0; 4]; MAXIMUM_ATTRACTOR_COUNT + 1],
+			attractor_count: 0,
+			particle_count: particles_configuration.particle_count,
+			dt: 0.0,
+			damping: particles_configuration.damping,
+			bounds,
+			_padding: [0.0; 2],
+		};
+		let simulation_uniform_buffer =
+			device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+				label: Some("Particles scene simulation uniform buffer"),
+				contents: bytemuck::bytes_of(&simulation_uniforms),
+				usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			});
+		let simulation_uniform_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Particles scene simulation uniform bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::COMPUTE,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let simulation_uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Particles scene simulation uniform bind group"),
+			layout: &simulation_uniform_bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: simulation_uniform_buffer.as_entire_binding(),
+			}],
+		});
+
+		// Create the simulation pipeline.
+		let simulation_shader_module =
+			device.create_shader_module(&wgpu::include_wgsl!("simulate.wgsl"));
+		let simulation_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Particles scene simulation pipeline layout"),
+				bind_group_layouts: &[
+					particle_buffers.bind_group_layout(),
+					&simulation_uniform_bind_group_layout,
+				],
+				push_constant_ranges: &[],
+			});
+		let simulation_pipeline = crate::scene::utilities::compute::create_compute_pipeline(
+			device,
+			"Particles scene simulation pipeline",
+			&simulation_shader_module,
+			"simulate",
+			&simulation_pipeline_layout,
+		);
+
+		// Create the render pipeline, which adds the sprites of overlapping particles together.
+		let render_shader_module = device.create_shader_module(&wgpu::include_wgsl!("render.wgsl"));
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Particles scene render pipeline layout"),
+				bind_group_layouts: &[],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::VERTEX,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		let additive_blend_component = wgpu::BlendComponent {
+			src_factor: wgpu::BlendFactor::One,
+			dst_factor: wgpu::BlendFactor::One,
+			operation: wgpu::BlendOperation::Add,
+		};
+		let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("Particles scene render pipeline"),
+			layout: Some(&render_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &render_shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[Particle::vertex_buffer_layout()],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: &render_shader_module,
+				entry_point: "fragment_stage",
+				targets: &[wgpu::ColorTargetState {
+					format: render_target_configuration.format,
+					blend: Some(wgpu::BlendState {
+						color: additive_blend_component,
+						alpha: additive_blend_component,
+					}),
+					write_mask: wgpu::ColorWrites::all(),
+				}],
+			}),
+			primitive: wgpu::PrimitiveState {
+				topology: wgpu::PrimitiveTopology::TriangleStrip,
+				..wgpu::PrimitiveState::default()
+			},
+			depth_stencil: None,
+			multisample: render_target_configuration.multisample_state(),
+			multiview: None,
+		});
+		let multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Particles scene",
+			);
+
+		let push_constant_data = PushConstantData {
+			resolution: [
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+			],
+			particle_size: particles_configuration.particle_size,
+			brightness: particles_configuration.brightness,
+		};
+		let mut particles_scene = Self {
+			particles_configuration,
+			simulation_uniforms,
+			push_constant_data,
+			particle_buffers,
+			simulation_uniform_buffer,
+			simulation_uniform_bind_group,
+			simulation_pipeline,
+			render_pipeline,
+			multisampled_color_target,
+		};
+		particles_scene.set_attractors(None);
+		particles_scene
+	}
+
+	/**
+	 * Get how far the world extends from its center horizontally and vertically.
+	 */
+	fn bounds(
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) -> [f32; 2] {
+		[
+			render_target_configuration.width as f32 / render_target_configuration.height as f32,
+			1.0,
+		]
+	}
+
+	/**
+	 * Put the configured attractors into the simulation uniforms, followed by the cursor if it's being followed.
+	 */
+	fn set_attractors(&mut self, cursor_position: Option<[f32; 2]>) {
+		let attractors = self
+			.particles_configuration
+			.attractors
+			.iter()
+			.take(MAXIMUM_ATTRACTOR_COUNT)
+			.map(|attractor| {
+				[
+					attractor.position[0],
+					attractor.position[1],
+					attractor.strength,
+					0.0,
+				]
+			})
+			.chain(cursor_position.map(|cursor_position| {
+				[
+					cursor_position[0],
+					cursor_position[1],
+					self.particles_configuration.cursor_strength,
+					0.0,
+				]
+			}))
+			.collect::<Vec<_>>();
+		self.simulation_uniforms.attractors[..attractors.len()].copy_from_slice(&attractors);
+		self.simulation_uniforms.attractor_count = attractors.len() as u32;
+	}
+}
+
+impl crate::scene::Scene for ParticlesScene {
+	fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.simulation_uniforms.bounds = Self::bounds(render_target_configuration);
+		self.push_constant_data.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		];
+		self.multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Particles scene",
+			);
+	}
+
+	fn update(&mut self, dt: f32) {
+		// Long frames are shortened so that particles don't shoot past attractors and off of the screen.
+		self.simulation_uniforms.dt = dt.min(0.05);
+	}
+
+	fn uses_bloom(&self) -> bool {
+		true
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		queue.write_buffer(
+			&self.simulation_uniform_buffer,
+			0,
+			bytemuck::bytes_of(&self.simulation_uniforms),
+		);
+
+		let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+			label: Some("Particles scene simulation compute pass"),
+		});
+		compute_pass.set_pipeline(&self.simulation_pipeline);
+		compute_pass.set_bind_group(0, self.particle_buffers.bind_group(), &[]);
+		compute_pass.set_bind_group(1, &self.simulation_uniform_bind_group, &[]);
+		compute_pass.dispatch(
+			crate::scene::utilities::compute::workgroup_count(
+				self.particles_configuration.particle_count,
+				WORKGROUP_SIZE,
+			),
+			1,
+			1,
+		);
+		drop(compute_pass);
+		self.particle_buffers.swap();
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Particles scene render pass"),
+			color_attachments: &[self
+				.multisampled_color_target
+				.color_attachment(output_texture_view, wgpu::LoadOp::Clear(wgpu::Color::BLACK))],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_vertex_buffer(0, self.particle_buffers.current_buffer().slice(..));
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::VERTEX,
+			0,
+			bytemuck::bytes_of(&self.push_constant_data),
+		);
+		render_pass.draw(0..4, 0..self.particles_configuration.particle_count);
+	}
+
+	fn cursor_changed(&mut self, cursor_state: &crate::scene::CursorState) {
+		if !self.particles_configuration.follow_cursor {
+			return;
+		}
+		// Convert the cursor's position from pixels into the world's coordinates.
+		let [width, height] = self.push_constant_data.resolution;
+		let cursor_position = cursor_state.position.map(|position| {
+			[
+				(2.0 * position[0] - width) / height,
+				1.0 - 2.0 * position[1] / height,
+			]
+		});
+		self.set_attractors(cursor_position);
+	}
+}
@@ -0,0 +1,39 @@
+struct PushConstantData {
+	resolution: vec2<f32>,
+	particle_size: f32,
+	brightness: f32,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+struct InstanceInput {
+	@location(0) position: vec2<f32>,
+	@location(1) velocity: vec2<f32>,
+};
+
+struct FragmentInput {
+	@builtin(position) clip_position: vec4<f32>,
+	@location(0) corner: vec2<f32>,
+	@location(1) color: vec3<f32>,
+};
+
+// Each particle is drawn as a triangle strip of four vertices making a square sprite around its position, where the
+// world spans from -1 to 1 vertically and is as wide as the screen's aspect ratio allows horizontally.
+@vertex
+fn vertex_stage(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) -> FragmentInput {
+	let corner = vec2<f32>(f32(vertex_index & 1u), f32(vertex_index >> 1u)) * 2.0 - 1.0;
+	let aspect_ratio = push_constant_data.resolution.x / push_constant_data.resolution.y;
+	let center = vec2<f32>(instance.position.x / aspect_ratio, instance.position.y);
+	let offset = corner * push_constant_data.particle_size / push_constant_data.resolution;
+	let speed = length(instance.velocity);
+	let slow_color = vec3<f32>(0.1, 0.3, 1.0);
+	let fast_color = vec3<f32>(1.0, 0.45, 0.1);
+	let color = mix(slow_color, fast_color, clamp(speed / 2.0, 0.0, 1.0)) * push_constant_data.brightness;
+	return FragmentInput(vec4<f32>(center + offset, 0.0, 1.0), corner, color);
+}
+
+@fragment
+fn fragment_stage(fragment: FragmentInput) -> @location(0) vec4<f32> {
+	let falloff = max(0.0, 1.0 - dot(fragment.corner, fragment.corner));
+	return vec4<f32>(fragment.color * falloff * falloff, 1.0);
+}
@@ -0,0 +1,52 @@
+struct Particle {
+	position: vec2<f32>,
+	velocity: vec2<f32>,
+};
+
+struct SimulationUniforms {
+	// Each attractor holds its position in xy and its strength in z. There is room for one more attractor than can be
+	// configured so that the cursor can be one too.
+	attractors: array<vec4<f32>, 9>,
+	attractor_count: u32,
+	particle_count: u32,
+	dt: f32,
+	damping: f32,
+	bounds: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<storage, read> source_particles: array<Particle>;
+@group(0) @binding(1)
+var<storage, read_write> destination_particles: array<Particle>;
+
+@group(1) @binding(0)
+var<uniform> uniforms: SimulationUniforms;
+
+// Keeps particles from being flung away when they pass right through an attractor.
+let softening = 0.01;
+
+@compute @workgroup_size(64)
+fn simulate(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let particle_index = invocation_id.x;
+	if (particle_index >= uniforms.particle_count) {
+		return;
+	}
+	var particle = source_particles[particle_index];
+
+	var acceleration = vec2<f32>(0.0);
+	for (var i = 0u; i < uniforms.attractor_count; i = i + 1u) {
+		let attractor = uniforms.attractors[i];
+		let offset = attractor.xy - particle.position;
+		let distance_squared = dot(offset, offset) + softening;
+		acceleration += attractor.z * offset * inverseSqrt(distance_squared) / distance_squared;
+	}
+	particle.velocity = (particle.velocity + acceleration * uniforms.dt) * exp(-uniforms.damping * uniforms.dt);
+	particle.position += particle.velocity * uniforms.dt;
+
+	// Bounce off of the edges of the screen.
+	let is_outside = abs(particle.position) > uniforms.bounds;
+	particle.position = clamp(particle.position, -uniforms.bounds, uniforms.bounds);
+	particle.velocity = select(particle.velocity, -particle.velocity, is_outside);
+
+	destination_particles[particle_index] = particle;
+}
@@ -0,0 +1,121 @@
+/**
+ * The shapes and other scenery that the raymarching scene draws, loaded from a TOML file such as:
+ *
+ * ```toml
+ * floor_height = -1.0
+ *
+ * [[primitives]]
+ * shape = "sphere"
+ * radius = 0.5
+ * position = [0.0, 0.0, 0.0]
+ * color = [0.9, 0.2, 0.1]
+ *
+ * [[primitives]]
+ * shape = "torus"
+ * major_radius = 0.8
+ * minor_radius = 0.15
+ * rotation = [90.0, 0.0, 0.0]
+ * smoothness = 0.2
+ * ```
+ *
+ * Every primitive is combined with all of the primitives before it, in order.
+ */
+#[derive(Debug, serde::Deserialize)]
+pub struct Composition {
+	/**
+	 * The height of an infinite checkered floor that the primitives stand on. Without one, there is no floor.
+	 */
+	#[serde(default)]
+	pub floor_height: Option<f32>,
+	pub primitives: Vec<Primitive>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Primitive {
+	#[serde(flatten)]
+	pub shape: Shape,
+	#[serde(default)]
+	pub position: [f32; 3],
+	/**
+	 * The rotations around the x, y and z axes in degrees, which are applied in that order.
+	 */
+	#[serde(default)]
+	pub rotation: [f32; 3],
+	#[serde(default = "Primitive::default_color")]
+	pub color: [f32; 3],
+	/**
+	 * How the primitive is combined with the primitives before it.
+	 */
+	#[serde(default)]
+	pub operation: Operation,
+	/**
+	 * The distance over which the primitive blends into the primitives before it. Zero gives a sharp edge.
+	 */
+	#[serde(default)]
+	pub smoothness: f32,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum Shape {
+	Sphere {
+		radius: f32,
+	},
+	Box {
+		/**
+		 * Half of the width, height and depth of the box.
+		 */
+		half_extents: [f32; 3],
+		/**
+		 * The radius that the edges and corners are rounded off by.
+		 */
+		#[serde(default)]
+		rounding: f32,
+	},
+	Torus {
+		/**
+		 * The distance from the center of the torus to the center of its tube. The torus lies flat in the xz plane.
+		 */
+		major_radius: f32,
+		minor_radius: f32,
+	},
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+	#[default]
+	Union,
+	/**
+	 * Carve the primitive out of the primitives before it.
+	 */
+	Subtraction,
+	/**
+	 * Keep only where the primitive overlaps the primitives before it.
+	 */
+	Intersection,
+}
+
+impl Primitive {
+	fn default_color() -> [f32; 3] {
+		[0.8; 3]
+	}
+}
+
+impl Composition {
+	/**
+	 * Load a composition from a TOML file.
+	 */
+	pub fn load(path: &std::path::Path) -> Result<Self, String> {
+		let contents = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+		Self::parse(&contents)
+	}
+
+	pub fn parse(contents: &str) -> Result<Self, String> {
+		let composition = toml::from_str::<Self>(contents).map_err(|error| error.to_string())?;
+		if composition.primitives.is_empty() {
+			return Err("there are no primitives".to_owned());
+		}
+		Ok(composition)
+	}
+}
@@ -0,0 +1,264 @@
+pub mod composition;
+
+/**
+ * The most primitives that a composition can have.
+ */
+const MAXIMUM_PRIMITIVE_COUNT: usize = 32;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PrimitiveData {
+	world_to_primitive: [[f32; 4]; 4],
+	parameters: [f32; 4],
+	color_and_smoothness: [f32; 4],
+	shape: u32,
+	operation: u32,
+	_padding: [u32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct RaymarchingUniforms {
+	inverse_camera_transformation: [[f32; 4]; 4],
+	camera_position: [f32; 4],
+	sun_direction_and_shadow_sharpness: [f32; 4],
+	floor_height: f32,
+	has_floor: u32,
+	primitive_count: u32,
+	maximum_step_count: u32,
+	primitives: [PrimitiveData; MAXIMUM_PRIMITIVE_COUNT],
+}
+
+/**
+ * Draws spheres, boxes and tori that are smoothly blended together by raymarching their signed distance fields, lit
+ * by a sun with soft shadows and ambient occlusion. The camera slowly orbits around the origin. The primitives are
+ * described by a composition file, so new compositions don't need any changes to the shader.
+ */
+pub struct RaymarchingScene {
+	raymarching_configuration: crate::config::RaymarchingConfig,
+	camera: crate::scene::utilities::camera::Camera,
+	orbit_angle: f32,
+	uniforms: RaymarchingUniforms,
+	uniform_buffer: wgpu::Buffer,
+	uniform_bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl RaymarchingScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		raymarching_configuration: &crate::config::RaymarchingConfig,
+	) -> Self {
+		// Load the composition, falling back to the default one if it can't be used.
+		let composition = raymarching_configuration
+			.composition
+			.as_ref()
+			.and_then(|path| {
+				composition::Composition::load(path)
+					.map_err(|error| {
+						log::warn!("Could not load composition {}: {}", path.display(), error)
+					})
+					.ok()
+			})
+			.unwrap_or_else(|| {
+				composition::Composition::parse(include_str!("default_composition.toml"))
+					.expect("the default composition should be valid")
+			});
+		if composition.primitives.len() > MAXIMUM_PRIMITIVE_COUNT {
+			log::warn!(
+				"Only the first {} of the {} primitives in the composition will be drawn",
+				MAXIMUM_PRIMITIVE_COUNT,
+				composition.primitives.len()
+			);
+		}
+		let mut primitives: [PrimitiveData; MAXIMUM_PRIMITIVE_COUNT] = bytemuck::Zeroable::zeroed();
+		for (primitive_data, primitive) in primitives.iter_mut().zip(&composition.primitives) {
+			*primitive_data = Self::primitive_data(primitive);
+		}
+
+		let sun_direction =
+			glam::Vec3::from(raymarching_configuration.sun_direction).normalize_or_zero();
+		let uniforms = RaymarchingUniforms {
+			inverse_camera_transformation: glam::Mat4::IDENTITY.to_cols_array_2d(),
+			camera_position: [0.0; 4],
+			sun_direction_and_shadow_sharpness: [
+				sun_direction.x,
+				sun_direction.y,
+				sun_direction.z,
+				raymarching_configuration.shadow_sharpness,
+			],
+			floor_height: composition.floor_height.unwrap_or(0.0),
+			has_floor: composition.floor_height.is_some() as u32,
+			primitive_count: composition.primitives.len().min(MAXIMUM_PRIMITIVE_COUNT) as u32,
+			maximum_step_count: raymarching_configuration.maximum_step_count,
+			primitives,
+		};
+		let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Raymarching scene uniform buffer"),
+			size: std::mem::size_of::<RaymarchingUniforms>() as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let uniform_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Raymarching scene uniform bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Raymarching scene uniform bind group"),
+			layout: &uniform_bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: uniform_buffer.as_entire_binding(),
+			}],
+		});
+
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			"Raymarching scene shader",
+			include_str!("raymarch.wgsl"),
+		);
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Raymarching scene pipeline layout"),
+				bind_group_layouts: &[&uniform_bind_group_layout],
+				push_constant_ranges: &[],
+			});
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Raymarching scene pipeline",
+			&shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: render_target_configuration.format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+
+		let mut camera = crate::scene::utilities::camera::Camera::new(
+			raymarching_configuration.field_of_view.to_radians(),
+			render_target_configuration.width as f32 / render_target_configuration.height as f32,
+		);
+		// Unlike the bouncing cube scene, y points up in this world.
+		camera.up_direction = glam::Vec3A::Y;
+		camera.far_plane_distance = 100.0;
+		let mut raymarching_scene = Self {
+			raymarching_configuration: raymarching_configuration.clone(),
+			camera,
+			orbit_angle: 0.0,
+			uniforms,
+			uniform_buffer,
+			uniform_bind_group,
+			render_pipeline,
+		};
+		raymarching_scene.update_camera();
+		raymarching_scene
+	}
+
+	fn primitive_data(primitive: &composition::Primitive) -> PrimitiveData {
+		let [x_rotation, y_rotation, z_rotation] = primitive.rotation.map(f32::to_radians);
+		let primitive_to_world = glam::Mat4::from_rotation_translation(
+			glam::Quat::from_euler(glam::EulerRot::ZYX, z_rotation, y_rotation, x_rotation),
+			glam::Vec3::from(primitive.position),
+		);
+		let (shape, parameters) = match primitive.shape {
+			composition::Shape::Sphere { radius } => (0, [radius, 0.0, 0.0, 0.0]),
+			composition::Shape::Box {
+				half_extents,
+				rounding,
+			} => (
+				1,
+				[half_extents[0], half_extents[1], half_extents[2], rounding],
+			),
+			composition::Shape::Torus {
+				major_radius,
+				minor_radius,
+			} => (2, [major_radius, minor_radius, 0.0, 0.0]),
+		};
+		PrimitiveData {
+			world_to_primitive: primitive_to_world.inverse().to_cols_array_2d(),
+			parameters,
+			color_and_smoothness: [
+				primitive.color[0],
+				primitive.color[1],
+				primitive.color[2],
+				primitive.smoothness,
+			],
+			shape,
+			operation: primitive.operation as u32,
+			_padding: [0; 2],
+		}
+	}
+
+	/**
+	 * Move the camera to its place along its orbit and point it at the origin, then give its transformation to the
+	 * shader so that it can make a ray for every pixel.
+	 */
+	fn update_camera(&mut self) {
+		let (orbit_sin, orbit_cos) = self.orbit_angle.sin_cos();
+		self.camera.position = glam::Vec3A::new(
+			self.raymarching_configuration.camera_distance * orbit_sin,
+			self.raymarching_configuration.camera_height,
+			-self.raymarching_configuration.camera_distance * orbit_cos,
+		);
+		self.camera.look_direction = (-self.camera.position).normalize();
+		self.camera.recalculate_transformation_and_view_planes();
+		self.uniforms.inverse_camera_transformation =
+			self.camera.transformation.inverse().to_cols_array_2d();
+		self.uniforms.camera_position = self.camera.position.extend(1.0).into();
+	}
+}
+
+impl crate::scene::Scene for RaymarchingScene {
+	fn resize(
+		&mut self,
+		_device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.camera.aspect_ratio =
+			render_target_configuration.width as f32 / render_target_configuration.height as f32;
+		self.update_camera();
+	}
+
+	fn update(&mut self, dt: f32) {
+		self.orbit_angle += self.raymarching_configuration.orbit_speed.to_radians() * dt;
+		self.update_camera();
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&self.uniforms));
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Raymarching scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+}
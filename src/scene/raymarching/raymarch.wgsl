@@ -0,0 +1,184 @@
+struct Primitive {
+	// Transforms points from the world into the space of the primitive, where it's centered on the origin.
+	world_to_primitive: mat4x4<f32>,
+	// The sphere's radius in x, the box's half extents in xyz and rounding in w, or the torus's radii in xy.
+	parameters: vec4<f32>,
+	// The color in xyz and the smoothness in w.
+	color_and_smoothness: vec4<f32>,
+	shape: u32,
+	operation: u32,
+};
+
+struct RaymarchingUniforms {
+	inverse_camera_transformation: mat4x4<f32>,
+	camera_position: vec4<f32>,
+	// The direction towards the sun in xyz and the shadow sharpness in w.
+	sun_direction_and_shadow_sharpness: vec4<f32>,
+	floor_height: f32,
+	has_floor: u32,
+	primitive_count: u32,
+	maximum_step_count: u32,
+	primitives: array<Primitive, 32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: RaymarchingUniforms;
+
+let shape_sphere = 0u;
+let shape_box = 1u;
+let shape_torus = 2u;
+let operation_union = 0u;
+let operation_subtraction = 1u;
+let maximum_distance = 50.0;
+let surface_distance = 0.001;
+let sky_color = vec3<f32>(0.45, 0.6, 0.85);
+let sun_color = vec3<f32>(1.6, 1.45, 1.2);
+
+fn sphere_distance(position: vec3<f32>, radius: f32) -> f32 {
+	return length(position) - radius;
+}
+
+fn box_distance(position: vec3<f32>, half_extents: vec3<f32>, rounding: f32) -> f32 {
+	let offset = abs(position) - half_extents + rounding;
+	return length(max(offset, vec3<f32>(0.0))) + min(max(offset.x, max(offset.y, offset.z)), 0.0) - rounding;
+}
+
+fn torus_distance(position: vec3<f32>, major_radius: f32, minor_radius: f32) -> f32 {
+	let ring_offset = vec2<f32>(length(position.xz) - major_radius, position.y);
+	return length(ring_offset) - minor_radius;
+}
+
+fn primitive_distance(primitive: Primitive, position: vec3<f32>) -> f32 {
+	let local_position = (primitive.world_to_primitive * vec4<f32>(position, 1.0)).xyz;
+	if (primitive.shape == shape_sphere) {
+		return sphere_distance(local_position, primitive.parameters.x);
+	}
+	if (primitive.shape == shape_box) {
+		return box_distance(local_position, primitive.parameters.xyz, primitive.parameters.w);
+	}
+	return torus_distance(local_position, primitive.parameters.x, primitive.parameters.y);
+}
+
+// Combine the distance and color of a primitive with those of the primitives before it, where x is the distance and
+// yzw is the color. Smooth operations use polynomial smoothing over the given distance.
+fn combine(scene: vec4<f32>, primitive: vec4<f32>, operation: u32, smoothness: f32) -> vec4<f32> {
+	let k = max(smoothness, 0.00001);
+	if (operation == operation_union) {
+		let h = clamp(0.5 + 0.5 * (primitive.x - scene.x) / k, 0.0, 1.0);
+		return vec4<f32>(mix(primitive.x, scene.x, h) - k * h * (1.0 - h), mix(primitive.yzw, scene.yzw, h));
+	}
+	if (operation == operation_subtraction) {
+		let h = clamp(0.5 - 0.5 * (scene.x + primitive.x) / k, 0.0, 1.0);
+		return vec4<f32>(mix(scene.x, -primitive.x, h) + k * h * (1.0 - h), scene.yzw);
+	}
+	let h = clamp(0.5 - 0.5 * (primitive.x - scene.x) / k, 0.0, 1.0);
+	return vec4<f32>(mix(primitive.x, scene.x, h) + k * h * (1.0 - h), mix(primitive.yzw, scene.yzw, h));
+}
+
+fn floor_color(position: vec3<f32>) -> vec3<f32> {
+	let checker = (i32(floor(position.x)) + i32(floor(position.z))) & 1;
+	return select(vec3<f32>(0.35), vec3<f32>(0.55), checker == 0);
+}
+
+// Get the distance to the closest surface in x and its color in yzw.
+fn scene_distance(position: vec3<f32>) -> vec4<f32> {
+	let first_primitive = uniforms.primitives[0];
+	var scene = vec4<f32>(primitive_distance(first_primitive, position), first_primitive.color_and_smoothness.xyz);
+	for (var i = 1u; i < uniforms.primitive_count; i = i + 1u) {
+		let primitive = uniforms.primitives[i];
+		scene = combine(
+			scene,
+			vec4<f32>(primitive_distance(primitive, position), primitive.color_and_smoothness.xyz),
+			primitive.operation,
+			primitive.color_and_smoothness.w,
+		);
+	}
+	if (uniforms.has_floor != 0u) {
+		let floor_distance = position.y - uniforms.floor_height;
+		if (floor_distance < scene.x) {
+			scene = vec4<f32>(floor_distance, floor_color(position));
+		}
+	}
+	return scene;
+}
+
+fn surface_normal(position: vec3<f32>) -> vec3<f32> {
+	let offset = vec2<f32>(1.0, -1.0) * 0.0005;
+	return normalize(
+		offset.xyy * scene_distance(position + offset.xyy).x +
+		offset.yyx * scene_distance(position + offset.yyx).x +
+		offset.yxy * scene_distance(position + offset.yxy).x +
+		offset.xxx * scene_distance(position + offset.xxx).x
+	);
+}
+
+// Darken points that can only see part of the sun, estimated from how closely a ray towards the sun passes by
+// surfaces.
+fn soft_shadow(origin: vec3<f32>, direction: vec3<f32>, sharpness: f32) -> f32 {
+	var light = 1.0;
+	var distance = 0.02;
+	for (var i = 0; i < 64 && distance < 20.0; i = i + 1) {
+		let closest_distance = scene_distance(origin + direction * distance).x;
+		if (closest_distance < surface_distance) {
+			return 0.0;
+		}
+		light = min(light, sharpness * closest_distance / distance);
+		distance = distance + clamp(closest_distance, 0.01, 0.5);
+	}
+	return clamp(light, 0.0, 1.0);
+}
+
+// Darken creases and corners by checking how close surfaces are when stepping away along the normal.
+fn ambient_occlusion(position: vec3<f32>, normal: vec3<f32>) -> f32 {
+	var occlusion = 0.0;
+	var weight = 1.0;
+	for (var i = 1; i <= 5; i = i + 1) {
+		let step_distance = 0.03 + 0.12 * f32(i);
+		occlusion = occlusion + weight * (step_distance - scene_distance(position + normal * step_distance).x);
+		weight = weight * 0.7;
+	}
+	return clamp(1.0 - 1.5 * occlusion, 0.0, 1.0);
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	// Turn the pixel into a ray by unprojecting it onto the near and far planes of the camera.
+	let device_coordinates = vec2<f32>(2.0 * input.texture_coordinates.x - 1.0, 1.0 - 2.0 * input.texture_coordinates.y);
+	let near_point = uniforms.inverse_camera_transformation * vec4<f32>(device_coordinates, 0.0, 1.0);
+	let far_point = uniforms.inverse_camera_transformation * vec4<f32>(device_coordinates, 1.0, 1.0);
+	let origin = uniforms.camera_position.xyz;
+	let direction = normalize(far_point.xyz / far_point.w - near_point.xyz / near_point.w);
+
+	let background = sky_color - 0.3 * max(direction.y, 0.0);
+	var distance = 0.0;
+	var hit = vec4<f32>(maximum_distance, vec3<f32>(0.0));
+	for (var i = 0u; i < uniforms.maximum_step_count; i = i + 1u) {
+		let closest = scene_distance(origin + direction * distance);
+		if (closest.x < surface_distance * distance) {
+			hit = vec4<f32>(distance, closest.yzw);
+			break;
+		}
+		distance = distance + closest.x;
+		if (distance > maximum_distance) {
+			break;
+		}
+	}
+	if (hit.x >= maximum_distance) {
+		return vec4<f32>(background, 1.0);
+	}
+
+	let position = origin + direction * hit.x;
+	let normal = surface_normal(position);
+	let sun_direction = normalize(uniforms.sun_direction_and_shadow_sharpness.xyz);
+	let shadow = soft_shadow(position + normal * 0.002, sun_direction, uniforms.sun_direction_and_shadow_sharpness.w);
+	let occlusion = ambient_occlusion(position, normal);
+	let diffuse_amount = max(dot(normal, sun_direction), 0.0) * shadow;
+	let half_direction = normalize(sun_direction - direction);
+	let specular_amount = pow(max(dot(normal, half_direction), 0.0), 48.0) * shadow;
+	let sky_amount = (0.5 + 0.5 * normal.y) * occlusion;
+	let color = hit.yzw * (diffuse_amount * sun_color + sky_amount * 0.35 * sky_color) + 0.4 * specular_amount * sun_color;
+
+	// Fade into the sky with distance.
+	let fog_amount = 1.0 - exp(-0.0025 * hit.x * hit.x);
+	return vec4<f32>(mix(color, background, fog_amount), 1.0);
+}
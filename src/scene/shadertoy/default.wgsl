@@ -0,0 +1,5 @@
+fn mainImage(fragCoord: vec2<f32>) -> vec4<f32> {
+	let uv = fragCoord / iResolution.xy;
+	let color = 0.5 + 0.5 * cos(iTime + uv.xyx + vec3<f32>(0.0, 2.0, 4.0));
+	return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,9 @@
+
+layout(location = 0) out vec4 shadertoy_color;
+
+void main() {
+	vec4 color;
+	// Shadertoy puts the origin of fragment coordinates in the bottom left corner.
+	mainImage(color, vec2(gl_FragCoord.x, iResolution.y - gl_FragCoord.y));
+	shadertoy_color = color;
+}
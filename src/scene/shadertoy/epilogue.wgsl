@@ -0,0 +1,16 @@
+
+@fragment
+fn shadertoy_fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	iResolution = shadertoy_uniforms.resolution;
+	iTime = shadertoy_uniforms.time;
+	iTimeDelta = shadertoy_uniforms.time_delta;
+	iFrameRate = shadertoy_uniforms.frame_rate;
+	iFrame = shadertoy_uniforms.frame;
+	iMouse = shadertoy_uniforms.mouse;
+	iDate = shadertoy_uniforms.date;
+	for (var i = 0; i < 4; i = i + 1) {
+		iChannelResolution[i] = shadertoy_uniforms.channel_resolutions[i].xyz;
+	}
+	// Shadertoy puts the origin of fragment coordinates in the bottom left corner.
+	return mainImage(vec2<f32>(input.position.x, iResolution.y - input.position.y));
+}
@@ -0,0 +1,656 @@
+use crate::config::ShadertoyChannelConfig;
+
+/**
+ * The number of iChannel inputs that Shadertoy shaders can read from.
+ */
+const CHANNEL_COUNT: usize = 4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadertoyUniforms {
+	resolution: [f32; 3],
+	time: f32,
+	mouse: [f32; 4],
+	date: [f32; 4],
+	time_delta: f32,
+	frame: i32,
+	frame_rate: f32,
+	_padding: u32,
+	channel_resolutions: [[f32; 4]; CHANNEL_COUNT],
+}
+
+/**
+ * Where a channel gets its texture from once its configuration has been loaded.
+ */
+enum Channel {
+	Texture {
+		texture: crate::scene::utilities::texture::Texture,
+		width: u32,
+		height: u32,
+	},
+	PreviousFrame,
+}
+
+/**
+ * The languages that Shadertoy shaders can be written in, which is told by the extension of their file.
+ */
+#[derive(Clone, Copy, Debug)]
+enum ShaderLanguage {
+	Wgsl,
+	Glsl,
+}
+
+impl ShaderLanguage {
+	fn from_path(path: &std::path::Path) -> Self {
+		match path.extension().and_then(std::ffi::OsStr::to_str) {
+			Some("frag" | "glsl") => Self::Glsl,
+			_ => Self::Wgsl,
+		}
+	}
+}
+
+/**
+ * Runs a user supplied fragment shader over the whole screen with Shadertoy's inputs available to it.
+ * A WGSL shader has to define `fn mainImage(fragCoord: vec2<f32>) -> vec4<f32>`, where fragCoord is in pixels with the
+ * origin in the bottom left corner, and it can read iResolution, iTime, iTimeDelta, iFrameRate, iFrame, iMouse, iDate,
+ * iChannelResolution, and iChannel0 through iChannel3 along with their samplers iChannel0Sampler through
+ * iChannel3Sampler.
+ * A GLSL shader, in a .frag or .glsl file, defines `void mainImage(out vec4 fragColor, in vec2 fragCoord)` like on
+ * Shadertoy and has the same inputs, except that iChannel0 through iChannel3 are sampler2Ds and iChannelResolution holds
+ * vec4s.
+ */
+pub struct ShadertoyScene {
+	uniforms: ShadertoyUniforms,
+	is_mouse_pressed: bool,
+	render_pipeline: wgpu::RenderPipeline,
+	uniform_buffer: wgpu::Buffer,
+	uniform_bind_group: wgpu::BindGroup,
+	channels: Vec<Channel>,
+	channel_bind_group_layout: wgpu::BindGroupLayout,
+	channel_bind_groups: Vec<wgpu::BindGroup>,
+	frame_textures: [crate::scene::utilities::texture::Texture; 2],
+	frame_bind_group_layout: wgpu::BindGroupLayout,
+	frame_bind_groups: [wgpu::BindGroup; 2],
+	present_render_pipeline: wgpu::RenderPipeline,
+}
+
+impl ShadertoyScene {
+	pub fn new(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		shadertoy_configuration: &crate::config::ShadertoyConfig,
+	) -> Self {
+		// Load the channels, leaving any that aren't configured or can't be loaded black.
+		let channels = (0..CHANNEL_COUNT)
+			.map(
+				|channel_index| match shadertoy_configuration.channels.get(channel_index) {
+					Some(ShadertoyChannelConfig::Image(path)) => Self::load_channel_image(
+						device,
+						queue,
+						shadertoy_configuration,
+						path,
+						channel_index,
+					),
+					Some(ShadertoyChannelConfig::PreviousFrame) => Channel::PreviousFrame,
+					Some(ShadertoyChannelConfig::None) | None => Channel::Texture {
+						texture: crate::scene::utilities::texture::Texture::from_image(
+							device,
+							queue,
+							&image::RgbaImage::new(1, 1),
+							crate::scene::utilities::texture::ColorSpace::Linear,
+							crate::scene::utilities::texture::SamplerPreset::Repeat,
+							"Shadertoy scene empty channel",
+						),
+						width: 1,
+						height: 1,
+					},
+				},
+			)
+			.collect::<Vec<_>>();
+		let mut channel_resolutions = [[0.0; 4]; CHANNEL_COUNT];
+		for (channel_index, channel) in channels.iter().enumerate() {
+			channel_resolutions[channel_index] = match channel {
+				Channel::Texture { width, height, .. } => [*width as f32, *height as f32, 1.0, 0.0],
+				Channel::PreviousFrame => [
+					render_target_configuration.width as f32,
+					render_target_configuration.height as f32,
+					1.0,
+					0.0,
+				],
+			};
+		}
+
+		// Create the uniform buffer and the bind groups for the channels and the frames.
+		let uniforms = ShadertoyUniforms {
+			resolution: [
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+				1.0,
+			],
+			time: 0.0,
+			mouse: [0.0; 4],
+			date: [0.0; 4],
+			time_delta: 0.0,
+			frame: 0,
+			frame_rate: 0.0,
+			_padding: 0,
+			channel_resolutions,
+		};
+		let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Shadertoy scene uniform buffer"),
+			size: std::mem::size_of::<ShadertoyUniforms>() as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let uniform_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Shadertoy scene uniform bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Shadertoy scene uniform bind group"),
+			layout: &uniform_bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: uniform_buffer.as_entire_binding(),
+			}],
+		});
+		let channel_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Shadertoy scene channel bind group layout"),
+				entries: &(0..CHANNEL_COUNT as u32)
+					.flat_map(|channel_index| {
+						[
+							wgpu::BindGroupLayoutEntry {
+								binding: 2 * channel_index,
+								visibility: wgpu::ShaderStages::FRAGMENT,
+								ty: wgpu::BindingType::Texture {
+									sample_type: wgpu::TextureSampleType::Float {
+										filterable: true,
+									},
+									view_dimension: wgpu::TextureViewDimension::D2,
+									multisampled: false,
+								},
+								count: None,
+							},
+							wgpu::BindGroupLayoutEntry {
+								binding: 2 * channel_index + 1,
+								visibility: wgpu::ShaderStages::FRAGMENT,
+								ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+								count: None,
+							},
+						]
+					})
+					.collect::<Vec<_>>(),
+			});
+		let frame_textures = Self::create_frame_textures(device, render_target_configuration);
+		let frame_bind_group_layout = frame_textures[0].create_bind_group_layout(
+			device,
+			"Shadertoy scene frame",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let frame_bind_groups =
+			Self::create_frame_bind_groups(device, &frame_textures, &frame_bind_group_layout);
+		let channel_bind_groups = Self::create_channel_bind_groups(
+			device,
+			&channels,
+			&frame_textures,
+			&channel_bind_group_layout,
+		);
+
+		// Create the pipeline for the user's shader, falling back to the default shader if it can't be used.
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Shadertoy scene pipeline layout"),
+				bind_group_layouts: &[&uniform_bind_group_layout, &channel_bind_group_layout],
+				push_constant_ranges: &[],
+			});
+		let user_shader_source = shadertoy_configuration.shader.as_ref().and_then(|path| {
+			std::fs::read_to_string(path)
+				.map(|source| (ShaderLanguage::from_path(path), source))
+				.map_err(|error| {
+					log::warn!(
+						"Could not read Shadertoy shader {}: {}",
+						path.display(),
+						error
+					)
+				})
+				.ok()
+		});
+		let render_pipeline = user_shader_source
+			.and_then(|(shader_language, source)| {
+				device.push_error_scope(wgpu::ErrorFilter::Validation);
+				let render_pipeline = Self::create_render_pipeline(
+					device,
+					&render_pipeline_layout,
+					shader_language,
+					&source,
+				);
+				match (
+					render_pipeline,
+					pollster::block_on(device.pop_error_scope()),
+				) {
+					(Err(error), _) => {
+						log::warn!("Could not use Shadertoy shader: {}", error);
+						None
+					}
+					(Ok(_), Some(error)) => {
+						log::warn!("Could not use Shadertoy shader: {}", error);
+						None
+					}
+					(Ok(render_pipeline), None) => Some(render_pipeline),
+				}
+			})
+			.unwrap_or_else(|| {
+				Self::create_render_pipeline(
+					device,
+					&render_pipeline_layout,
+					ShaderLanguage::Wgsl,
+					include_str!("default.wgsl"),
+				)
+				.unwrap()
+			});
+
+		// Create the pipeline that copies finished frames into the render target.
+		let present_shader_module =
+			crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+				device,
+				"Shadertoy scene present shader",
+				include_str!("present.wgsl"),
+			);
+		let present_render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Shadertoy scene present pipeline layout"),
+				bind_group_layouts: &[&frame_bind_group_layout],
+				push_constant_ranges: &[],
+			});
+		let present_render_pipeline =
+			crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+				device,
+				"Shadertoy scene present pipeline",
+				&present_shader_module,
+				"fragment_stage",
+				&present_render_pipeline_layout,
+				wgpu::ColorTargetState {
+					format: render_target_configuration.format,
+					blend: None,
+					write_mask: wgpu::ColorWrites::all(),
+				},
+			);
+
+		Self {
+			uniforms,
+			is_mouse_pressed: false,
+			render_pipeline,
+			uniform_buffer,
+			uniform_bind_group,
+			channels,
+			channel_bind_group_layout,
+			channel_bind_groups,
+			frame_textures,
+			frame_bind_group_layout,
+			frame_bind_groups,
+			present_render_pipeline,
+		}
+	}
+
+	fn load_channel_image(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		shadertoy_configuration: &crate::config::ShadertoyConfig,
+		path: &std::path::Path,
+		channel_index: usize,
+	) -> Channel {
+		let color_space = if shadertoy_configuration.srgb_channels {
+			crate::scene::utilities::texture::ColorSpace::Srgb
+		} else {
+			crate::scene::utilities::texture::ColorSpace::Linear
+		};
+		let sampler_preset = match shadertoy_configuration.channel_sampler {
+			crate::config::ShadertoyChannelSampler::Repeat => {
+				crate::scene::utilities::texture::SamplerPreset::Repeat
+			}
+			crate::config::ShadertoyChannelSampler::Clamp => {
+				crate::scene::utilities::texture::SamplerPreset::Clamp
+			}
+			crate::config::ShadertoyChannelSampler::Anisotropic => {
+				crate::scene::utilities::texture::SamplerPreset::Anisotropic
+			}
+		};
+		let texture_and_size = image::image_dimensions(path)
+			.map_err(|error| error.to_string())
+			.and_then(|(width, height)| {
+				crate::scene::utilities::texture::Texture::from_image_file(
+					device,
+					queue,
+					path,
+					color_space,
+					sampler_preset,
+					"Shadertoy scene image channel",
+				)
+				.map(|texture| (texture, width, height))
+			});
+		match texture_and_size {
+			Ok((texture, width, height)) => Channel::Texture {
+				texture,
+				width,
+				height,
+			},
+			Err(error) => {
+				log::warn!(
+					"Could not load image {} for iChannel{}: {}",
+					path.display(),
+					channel_index,
+					error
+				);
+				Channel::Texture {
+					texture: crate::scene::utilities::texture::Texture::from_image(
+						device,
+						queue,
+						&image::RgbaImage::new(1, 1),
+						color_space,
+						sampler_preset,
+						"Shadertoy scene empty channel",
+					),
+					width: 1,
+					height: 1,
+				}
+			}
+		}
+	}
+
+	fn create_render_pipeline(
+		device: &wgpu::Device,
+		render_pipeline_layout: &wgpu::PipelineLayout,
+		shader_language: ShaderLanguage,
+		user_shader_source: &str,
+	) -> Result<wgpu::RenderPipeline, String> {
+		let target = wgpu::ColorTargetState {
+			format: crate::scene::utilities::tonemapping::HDR_FORMAT,
+			blend: None,
+			write_mask: wgpu::ColorWrites::all(),
+		};
+		match shader_language {
+			ShaderLanguage::Wgsl => {
+				let shader_module =
+					crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+						device,
+						"Shadertoy scene shader",
+						&(include_str!("prelude.wgsl").to_owned()
+							+ user_shader_source + include_str!("epilogue.wgsl")),
+					);
+				Ok(
+					crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+						device,
+						"Shadertoy scene pipeline",
+						&shader_module,
+						"shadertoy_fragment_stage",
+						render_pipeline_layout,
+						target,
+					),
+				)
+			}
+			ShaderLanguage::Glsl => {
+				let source = include_str!("prelude.glsl").to_owned()
+					+ user_shader_source
+					+ include_str!("epilogue.glsl");
+				// wgpu panics on GLSL that can't be parsed instead of reporting it like other shader errors, so the
+				// shader is parsed here first.
+				naga::front::glsl::Parser::default()
+					.parse(
+						&naga::front::glsl::Options {
+							stage: naga::ShaderStage::Fragment,
+							defines: naga::FastHashMap::default(),
+						},
+						&source,
+					)
+					.map_err(|errors| {
+						errors
+							.iter()
+							.map(ToString::to_string)
+							.collect::<Vec<_>>()
+							.join("\n")
+					})?;
+				let shader_module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+					label: Some("Shadertoy scene shader"),
+					source: wgpu::ShaderSource::Glsl {
+						shader: source.into(),
+						stage: naga::ShaderStage::Fragment,
+						defines: naga::FastHashMap::default(),
+					},
+				});
+				Ok(
+					crate::scene::utilities::fullscreen::create_fullscreen_pipeline_with_fragment_module(
+						device,
+						"Shadertoy scene pipeline",
+						&shader_module,
+						"main",
+						render_pipeline_layout,
+						target,
+					),
+				)
+			}
+		}
+	}
+
+	/**
+	 * Create the textures that the shader renders into. Frames alternate between them so that the previous frame can
+	 * be read while the current one is being rendered.
+	 */
+	fn create_frame_textures(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) -> [crate::scene::utilities::texture::Texture; 2] {
+		[
+			crate::scene::utilities::texture::Texture::create_render_target(
+				device,
+				crate::scene::utilities::tonemapping::HDR_FORMAT,
+				render_target_configuration.width,
+				render_target_configuration.height,
+				"Shadertoy scene even frame",
+			),
+			crate::scene::utilities::texture::Texture::create_render_target(
+				device,
+				crate::scene::utilities::tonemapping::HDR_FORMAT,
+				render_target_configuration.width,
+				render_target_configuration.height,
+				"Shadertoy scene odd frame",
+			),
+		]
+	}
+
+	fn create_frame_bind_groups(
+		device: &wgpu::Device,
+		frame_textures: &[crate::scene::utilities::texture::Texture; 2],
+		frame_bind_group_layout: &wgpu::BindGroupLayout,
+	) -> [wgpu::BindGroup; 2] {
+		[
+			frame_textures[0].create_bind_group_with_layout(
+				device,
+				frame_bind_group_layout,
+				"Shadertoy scene even frame",
+			),
+			frame_textures[1].create_bind_group_with_layout(
+				device,
+				frame_bind_group_layout,
+				"Shadertoy scene odd frame",
+			),
+		]
+	}
+
+	/**
+	 * Create a channel bind group for rendering into each of the frame textures, where channels that read the previous
+	 * frame are bound to the other frame texture.
+	 */
+	fn create_channel_bind_groups(
+		device: &wgpu::Device,
+		channels: &[Channel],
+		frame_textures: &[crate::scene::utilities::texture::Texture; 2],
+		channel_bind_group_layout: &wgpu::BindGroupLayout,
+	) -> Vec<wgpu::BindGroup> {
+		(0..2)
+			.map(|frame_index| {
+				let channel_textures = channels
+					.iter()
+					.map(|channel| match channel {
+						Channel::Texture { texture, .. } => texture,
+						Channel::PreviousFrame => &frame_textures[1 - frame_index],
+					})
+					.collect::<Vec<_>>();
+				device.create_bind_group(&wgpu::BindGroupDescriptor {
+					label: Some("Shadertoy scene channel bind group"),
+					layout: channel_bind_group_layout,
+					entries: &channel_textures
+						.iter()
+						.enumerate()
+						.flat_map(|(channel_index, texture)| {
+							[
+								wgpu::BindGroupEntry {
+									binding: 2 * channel_index as u32,
+									resource: wgpu::BindingResource::TextureView(
+										&texture.texture_view,
+									),
+								},
+								wgpu::BindGroupEntry {
+									binding: 2 * channel_index as u32 + 1,
+									resource: wgpu::BindingResource::Sampler(&texture.sampler),
+								},
+							]
+						})
+						.collect::<Vec<_>>(),
+				})
+			})
+			.collect()
+	}
+}
+
+impl crate::scene::Scene for ShadertoyScene {
+	fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.uniforms.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+			1.0,
+		];
+		for (channel_index, channel) in self.channels.iter().enumerate() {
+			if let Channel::PreviousFrame = channel {
+				self.uniforms.channel_resolutions[channel_index] = [
+					render_target_configuration.width as f32,
+					render_target_configuration.height as f32,
+					1.0,
+					0.0,
+				];
+			}
+		}
+		self.frame_textures = Self::create_frame_textures(device, render_target_configuration);
+		self.frame_bind_groups = Self::create_frame_bind_groups(
+			device,
+			&self.frame_textures,
+			&self.frame_bind_group_layout,
+		);
+		self.channel_bind_groups = Self::create_channel_bind_groups(
+			device,
+			&self.channels,
+			&self.frame_textures,
+			&self.channel_bind_group_layout,
+		);
+	}
+
+	fn update(&mut self, dt: f32) {
+		use chrono::{Datelike, Timelike};
+		let now = chrono::Local::now();
+		self.uniforms.time += dt;
+		self.uniforms.time_delta = dt;
+		self.uniforms.frame_rate = if dt > 0.0 { 1.0 / dt } else { 0.0 };
+		// Like on Shadertoy, the month starts counting from 0 while the day starts counting from 1.
+		self.uniforms.date = [
+			now.year() as f32,
+			now.month0() as f32,
+			now.day() as f32,
+			now.num_seconds_from_midnight() as f32 + now.nanosecond() as f32 / 1_000_000_000.0,
+		];
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&self.uniforms));
+		let frame_index = self.uniforms.frame as usize % 2;
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Shadertoy scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: &self.frame_textures[frame_index].texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+		render_pass.set_bind_group(1, &self.channel_bind_groups[frame_index], &[]);
+		render_pass.draw(0..3, 0..1);
+		drop(render_pass);
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Shadertoy scene present render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.present_render_pipeline);
+		render_pass.set_bind_group(0, &self.frame_bind_groups[frame_index], &[]);
+		render_pass.draw(0..3, 0..1);
+
+		// The click position is only positive on the frame that the click happened.
+		self.uniforms.mouse[3] = -self.uniforms.mouse[3].abs();
+		self.uniforms.frame += 1;
+	}
+
+	fn cursor_changed(&mut self, cursor_state: &crate::scene::CursorState) {
+		// Like on Shadertoy, iMouse.xy is where the cursor was last dragged to and iMouse.zw is where it was last
+		// clicked, with z being negative when the button isn't held and w being negative after the click's frame.
+		if let Some(position) = cursor_state.position {
+			let position = [position[0], self.uniforms.resolution[1] - position[1]];
+			if cursor_state.is_pressed && !self.is_mouse_pressed {
+				self.uniforms.mouse = [position[0], position[1], position[0], position[1]];
+			} else if cursor_state.is_pressed {
+				self.uniforms.mouse[0] = position[0];
+				self.uniforms.mouse[1] = position[1];
+			}
+		}
+		if !cursor_state.is_pressed {
+			self.uniforms.mouse[2] = -self.uniforms.mouse[2].abs();
+		}
+		self.is_mouse_pressed = cursor_state.is_pressed;
+	}
+
+	fn uses_tonemapping(&self) -> bool {
+		false
+	}
+}
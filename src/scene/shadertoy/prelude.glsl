@@ -0,0 +1,30 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform ShadertoyUniforms {
+	vec3 iResolution;
+	float iTime;
+	vec4 iMouse;
+	vec4 iDate;
+	float iTimeDelta;
+	int iFrame;
+	float iFrameRate;
+	uint shadertoy_padding;
+	// Shadertoy has vec3s here, but the uniforms are laid out with vec4s, whose last component is always zero.
+	vec4 iChannelResolution[4];
+};
+
+layout(set = 1, binding = 0) uniform texture2D iChannel0Texture;
+layout(set = 1, binding = 1) uniform sampler iChannel0Sampler;
+layout(set = 1, binding = 2) uniform texture2D iChannel1Texture;
+layout(set = 1, binding = 3) uniform sampler iChannel1Sampler;
+layout(set = 1, binding = 4) uniform texture2D iChannel2Texture;
+layout(set = 1, binding = 5) uniform sampler iChannel2Sampler;
+layout(set = 1, binding = 6) uniform texture2D iChannel3Texture;
+layout(set = 1, binding = 7) uniform sampler iChannel3Sampler;
+
+// Textures and samplers are bound separately, so the channels are put together wherever they're used.
+#define iChannel0 sampler2D(iChannel0Texture, iChannel0Sampler)
+#define iChannel1 sampler2D(iChannel1Texture, iChannel1Sampler)
+#define iChannel2 sampler2D(iChannel2Texture, iChannel2Sampler)
+#define iChannel3 sampler2D(iChannel3Texture, iChannel3Sampler)
+
@@ -0,0 +1,40 @@
+struct ShadertoyUniforms {
+	resolution: vec3<f32>,
+	time: f32,
+	mouse: vec4<f32>,
+	date: vec4<f32>,
+	time_delta: f32,
+	frame: i32,
+	frame_rate: f32,
+	channel_resolutions: array<vec4<f32>, 4>,
+};
+
+@group(0) @binding(0)
+var<uniform> shadertoy_uniforms: ShadertoyUniforms;
+
+@group(1) @binding(0)
+var iChannel0: texture_2d<f32>;
+@group(1) @binding(1)
+var iChannel0Sampler: sampler;
+@group(1) @binding(2)
+var iChannel1: texture_2d<f32>;
+@group(1) @binding(3)
+var iChannel1Sampler: sampler;
+@group(1) @binding(4)
+var iChannel2: texture_2d<f32>;
+@group(1) @binding(5)
+var iChannel2Sampler: sampler;
+@group(1) @binding(6)
+var iChannel3: texture_2d<f32>;
+@group(1) @binding(7)
+var iChannel3Sampler: sampler;
+
+var<private> iResolution: vec3<f32>;
+var<private> iTime: f32;
+var<private> iTimeDelta: f32;
+var<private> iFrameRate: f32;
+var<private> iFrame: i32;
+var<private> iMouse: vec4<f32>;
+var<private> iDate: vec4<f32>;
+var<private> iChannelResolution: array<vec3<f32>, 4>;
+
@@ -0,0 +1,18 @@
+@group(0) @binding(0)
+var frame_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var frame_sampler: sampler;
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+	let lower = color / 12.92;
+	let higher = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+	return select(higher, lower, color <= vec3<f32>(0.04045));
+}
+
+// Shadertoy shaders output colors that are meant to be displayed as they are, so they have to be converted to linear
+// colors for the rest of the pipeline.
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	let color = textureSample(frame_texture, frame_sampler, input.texture_coordinates);
+	return vec4<f32>(srgb_to_linear(clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0))), 1.0);
+}
@@ -0,0 +1,125 @@
+use rand::seq::SliceRandom;
+
+/**
+ * The file extensions of the images that are shown.
+ */
+const IMAGE_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+
+/**
+ * Decodes the images in a directory in a random order on a background thread, keeping the next one ready so that
+ * showing it never has to wait for it to be decoded. Once every image has been shown, the directory is read again and
+ * shuffled into a new order.
+ */
+pub struct ImageLoader {
+	receiver: std::sync::mpsc::Receiver<image::RgbaImage>,
+}
+
+impl ImageLoader {
+	/**
+	 * Start decoding the images in the directory, shrinking any that are bigger than the maximum size to fit within it.
+	 */
+	pub fn new(directory: std::path::PathBuf, maximum_size: u32) -> Self {
+		// Only one decoded image is kept waiting, since decoded images can take a lot of memory.
+		let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+		let spawn_result = std::thread::Builder::new()
+			.name("slideshow image loader".to_owned())
+			.spawn(move || Self::run(&directory, maximum_size, &sender));
+		if let Err(error) = spawn_result {
+			log::warn!("Could not start the slideshow image loader: {}", error);
+		}
+		Self { receiver }
+	}
+
+	/**
+	 * Get the next image if it has been decoded.
+	 */
+	pub fn try_next_image(&self) -> Option<image::RgbaImage> {
+		self.receiver.try_recv().ok()
+	}
+
+	fn run(
+		directory: &std::path::Path,
+		maximum_size: u32,
+		sender: &std::sync::mpsc::SyncSender<image::RgbaImage>,
+	) {
+		loop {
+			let mut paths = match Self::image_paths(directory) {
+				Ok(paths) => paths,
+				Err(error) => {
+					log::warn!(
+						"Could not read the slideshow directory {}: {}",
+						directory.display(),
+						error
+					);
+					return;
+				}
+			};
+			if paths.is_empty() {
+				log::warn!(
+					"There are no images in the slideshow directory {}",
+					directory.display()
+				);
+				return;
+			}
+			paths.shuffle(&mut rand::thread_rng());
+
+			let mut has_loaded_image = false;
+			for path in paths {
+				let image = match Self::load_image(&path, maximum_size) {
+					Ok(image) => image,
+					Err(error) => {
+						log::warn!(
+							"Could not load slideshow image {}: {}",
+							path.display(),
+							error
+						);
+						continue;
+					}
+				};
+				has_loaded_image = true;
+				// The scene has been dropped once nothing is receiving images anymore.
+				if sender.send(image).is_err() {
+					return;
+				}
+			}
+			if !has_loaded_image {
+				log::warn!(
+					"None of the images in the slideshow directory {} could be loaded",
+					directory.display()
+				);
+				return;
+			}
+		}
+	}
+
+	fn image_paths(directory: &std::path::Path) -> std::io::Result<Vec<std::path::PathBuf>> {
+		let mut paths = Vec::new();
+		for entry in std::fs::read_dir(directory)? {
+			let path = entry?.path();
+			let is_image = path
+				.extension()
+				.and_then(|extension| extension.to_str())
+				.is_some_and(|extension| {
+					IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+				});
+			if is_image && path.is_file() {
+				paths.push(path);
+			}
+		}
+		Ok(paths)
+	}
+
+	fn load_image(path: &std::path::Path, maximum_size: u32) -> Result<image::RgbaImage, String> {
+		let image = crate::scene::utilities::texture::Texture::load_image(path)?;
+		if image.width() <= maximum_size && image.height() <= maximum_size {
+			return Ok(image);
+		}
+		Ok(image::DynamicImage::ImageRgba8(image)
+			.resize(
+				maximum_size,
+				maximum_size,
+				image::imageops::FilterType::Triangle,
+			)
+			.into_rgba8())
+	}
+}
@@ -0,0 +1,285 @@
+mod image_loader;
+
+use rand::Rng;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	view_scale: [f32; 2],
+	view_center: [f32; 2],
+	image_size: [f32; 2],
+	texture_size: f32,
+	opacity: f32,
+}
+
+/**
+ * An image being shown, along with the pan and zoom that it moves through while it is on screen.
+ */
+struct Slide {
+	texture_index: usize,
+	image_size: [u32; 2],
+	/**
+	 * How far the view is zoomed into the image at the start and end of the slide.
+	 */
+	zoom_range: [f32; 2],
+	/**
+	 * Where the view is at the start and end of the slide, from -1 to 1 on each axis across the part of the image that
+	 * is outside of the view.
+	 */
+	pan_range: [glam::Vec2; 2],
+	age: f32,
+}
+
+/**
+ * Shows the images in a directory in a random order, slowly panning and zooming across each one and crossfading from
+ * one to the next. The images are decoded on a background thread and written into one of two textures that are
+ * swapped between, so the scene never has to wait for an image or create any resources while it is running.
+ */
+pub struct SlideshowScene {
+	slideshow_configuration: crate::config::SlideshowConfig,
+	resolution: [f32; 2],
+	image_loader: image_loader::ImageLoader,
+	slides: Vec<Slide>,
+	/**
+	 * An image that has to be written into the texture with the given index before the next frame is drawn.
+	 */
+	pending_image: Option<(usize, image::RgbaImage)>,
+	texture_size: u32,
+	textures: [crate::scene::utilities::texture::Texture; 2],
+	texture_bind_groups: [wgpu::BindGroup; 2],
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl SlideshowScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		slideshow_configuration: &crate::config::SlideshowConfig,
+	) -> Self {
+		let directory = slideshow_configuration
+			.directory
+			.clone()
+			.or_else(|| {
+				std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join("Pictures"))
+			})
+			.unwrap_or_default();
+		// Images are never shown bigger than the screen, so they are shrunk to fit within it on the background thread.
+		let texture_size = render_target_configuration
+			.width
+			.max(render_target_configuration.height)
+			.min(device.limits().max_texture_dimension_2d)
+			.max(1);
+		let image_loader = image_loader::ImageLoader::new(directory, texture_size);
+
+		let textures = [0, 1].map(|_| {
+			crate::scene::utilities::texture::Texture::create_image_texture(
+				device,
+				texture_size,
+				texture_size,
+				"Slideshow scene slide",
+			)
+		});
+		let texture_bind_group_layout = textures[0].create_bind_group_layout(
+			device,
+			"Slideshow scene slide",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let texture_bind_groups = [0, 1].map(|texture_index| {
+			textures[texture_index].create_bind_group_with_layout(
+				device,
+				&texture_bind_group_layout,
+				"Slideshow scene slide",
+			)
+		});
+
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			"Slideshow scene shader",
+			include_str!("slide.wgsl"),
+		);
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Slideshow scene pipeline layout"),
+				bind_group_layouts: &[&texture_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::FRAGMENT,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		// Each slide is blended over the one before it by its opacity, which crossfades between them.
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Slideshow scene pipeline",
+			&shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: render_target_configuration.format,
+				blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+
+		Self {
+			slideshow_configuration: slideshow_configuration.clone(),
+			resolution: [
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+			],
+			image_loader,
+			slides: Vec::new(),
+			pending_image: None,
+			texture_size,
+			textures,
+			texture_bind_groups,
+			render_pipeline,
+		}
+	}
+
+	/**
+	 * Start showing an image, choosing a random pan and zoom for it.
+	 */
+	fn add_slide(&mut self, image: image::RgbaImage) {
+		let mut random_number_generator = rand::thread_rng();
+		let texture_index = self
+			.slides
+			.last()
+			.map_or(0, |last_slide| 1 - last_slide.texture_index);
+		let maximum_zoom = 1.0 + self.slideshow_configuration.zoom_amount.max(0.0);
+		let zoom_range = if random_number_generator.gen() {
+			[1.0, maximum_zoom]
+		} else {
+			[maximum_zoom, 1.0]
+		};
+		let mut random_pan = || {
+			glam::Vec2::new(
+				random_number_generator.gen_range(-1.0..=1.0),
+				random_number_generator.gen_range(-1.0..=1.0),
+			)
+		};
+		let pan_range = [random_pan(), random_pan()];
+		self.slides.push(Slide {
+			texture_index,
+			image_size: [image.width(), image.height()],
+			zoom_range,
+			pan_range,
+			age: 0.0,
+		});
+		self.pending_image = Some((texture_index, image));
+	}
+
+	/**
+	 * Work out which part of a slide's image is on screen at its current age.
+	 */
+	fn push_constant_data(&self, slide: &Slide) -> PushConstantData {
+		let display_duration = self.slideshow_configuration.display_duration;
+		let crossfade_duration = self.slideshow_configuration.crossfade_duration;
+		let progress =
+			(slide.age / (display_duration + crossfade_duration).max(f32::EPSILON)).min(1.0);
+		let zoom = slide.zoom_range[0] + (slide.zoom_range[1] - slide.zoom_range[0]) * progress;
+		let pan = slide.pan_range[0].lerp(slide.pan_range[1], progress);
+
+		// Find how much of the image fits on screen at no zoom. Filling the screen crops the image along one axis, while
+		// fitting the image onto the screen leaves bars along one axis.
+		let screen_aspect_ratio = self.resolution[0] / self.resolution[1];
+		let image_aspect_ratio = slide.image_size[0] as f32 / slide.image_size[1] as f32;
+		let relative_aspect_ratio = glam::Vec2::new(
+			screen_aspect_ratio / image_aspect_ratio,
+			image_aspect_ratio / screen_aspect_ratio,
+		);
+		let unzoomed_view_scale = match self.slideshow_configuration.fit_mode {
+			crate::config::SlideshowFitMode::Fill => relative_aspect_ratio.min(glam::Vec2::ONE),
+			crate::config::SlideshowFitMode::Fit => relative_aspect_ratio.max(glam::Vec2::ONE),
+		};
+		let view_scale = unzoomed_view_scale / zoom;
+		let view_center = 0.5 + 0.5 * pan * (1.0 - view_scale).max(glam::Vec2::ZERO);
+
+		let opacity = if crossfade_duration > 0.0 {
+			(slide.age / crossfade_duration).min(1.0)
+		} else {
+			1.0
+		};
+		PushConstantData {
+			view_scale: view_scale.into(),
+			view_center: view_center.into(),
+			image_size: [slide.image_size[0] as f32, slide.image_size[1] as f32],
+			texture_size: self.texture_size as f32,
+			opacity,
+		}
+	}
+}
+
+impl crate::scene::Scene for SlideshowScene {
+	fn resize(
+		&mut self,
+		_device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		];
+	}
+
+	fn update(&mut self, dt: f32) {
+		for slide in &mut self.slides {
+			slide.age += dt;
+		}
+		// Drop the previous slide once the newest one has completely faded in over it.
+		if self.slides.len() > 1
+			&& self.slides[1].age >= self.slideshow_configuration.crossfade_duration
+		{
+			self.slides.remove(0);
+		}
+		// Move on to the next image once the current one has been shown long enough, unless the next image is still
+		// being decoded, in which case the current one stays up a little longer.
+		let is_ready_for_next_slide = match self.slides.as_slice() {
+			[] => true,
+			[slide] => slide.age >= self.slideshow_configuration.display_duration,
+			_ => false,
+		};
+		if is_ready_for_next_slide {
+			if let Some(image) = self.image_loader.try_next_image() {
+				self.add_slide(image);
+			}
+		}
+	}
+
+	fn uses_tonemapping(&self) -> bool {
+		false
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		if let Some((texture_index, image)) = self.pending_image.take() {
+			self.textures[texture_index].write_image(queue, &image);
+		}
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Slideshow scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		for slide in &self.slides {
+			render_pass.set_bind_group(0, &self.texture_bind_groups[slide.texture_index], &[]);
+			render_pass.set_push_constants(
+				wgpu::ShaderStages::FRAGMENT,
+				0,
+				bytemuck::bytes_of(&self.push_constant_data(slide)),
+			);
+			render_pass.draw(0..3, 0..1);
+		}
+	}
+}
@@ -0,0 +1,31 @@
+struct PushConstantData {
+	// How much of the image the screen spans, and where in the image the center of the screen is, where the image
+	// spans from 0 to 1.
+	view_scale: vec2<f32>,
+	view_center: vec2<f32>,
+	// The size of the image in pixels, which may only fill part of the texture.
+	image_size: vec2<f32>,
+	texture_size: f32,
+	opacity: f32,
+};
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var slide_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var slide_sampler: sampler;
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	let image_position = (input.texture_coordinates - 0.5) * push_constant_data.view_scale + push_constant_data.view_center;
+	// Parts of the screen outside of the image are left black, which only happens when images are fit to the screen.
+	let inside = all(image_position >= vec2<f32>(0.0)) && all(image_position <= vec2<f32>(1.0));
+	// Keep the samples half a pixel inside of the image so that they don't pick up the unused parts of the texture.
+	let pixel_position = clamp(
+		image_position * push_constant_data.image_size,
+		vec2<f32>(0.5),
+		push_constant_data.image_size - 0.5,
+	);
+	let color = textureSample(slide_texture, slide_sampler, pixel_position / push_constant_data.texture_size);
+	return vec4<f32>(color.rgb * f32(inside), push_constant_data.opacity);
+}
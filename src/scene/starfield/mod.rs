@@ -0,0 +1,440 @@
+use rand::Rng;
+use wgpu::util::DeviceExt;
+
+/**
+ * The most shooting stars that can be on screen at once.
+ */
+const MAXIMUM_SHOOTING_STAR_COUNT: usize = 4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct StarInstance {
+	position: [f32; 2],
+	parallax: f32,
+	size: f32,
+	color: [f32; 3],
+	twinkle_phase: f32,
+}
+
+impl StarInstance {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+		0 => Float32x2,
+		1 => Float32,
+		2 => Float32,
+		3 => Float32x3,
+		4 => Float32,
+	];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Instance,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShootingStarInstance {
+	head: [f32; 2],
+	direction: [f32; 2],
+	tail_length: f32,
+	brightness: f32,
+}
+
+impl ShootingStarInstance {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+		0 => Float32x2,
+		1 => Float32x2,
+		2 => Float32,
+		3 => Float32,
+	];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Instance,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct StarfieldUniforms {
+	nebula_colors: [[f32; 4]; 2],
+	resolution: [f32; 2],
+	drift_offset: [f32; 2],
+	nebula_offset: [f32; 2],
+	time: f32,
+	star_size: f32,
+	star_brightness: f32,
+	nebula_brightness: f32,
+	_padding: [f32; 2],
+}
+
+/**
+ * A shooting star streaking across the screen, in pixels.
+ */
+struct ShootingStar {
+	head: glam::Vec2,
+	velocity: glam::Vec2,
+	age: f32,
+	lifetime: f32,
+}
+
+/**
+ * Draws layers of stars drifting past at different speeds in front of a nebula made from noise, with the occasional
+ * shooting star. Everything in it moves slowly, so it still looks smooth at very low frame rates, which makes it the
+ * scene used in low power mode.
+ */
+pub struct StarfieldScene {
+	starfield_configuration: crate::config::StarfieldConfig,
+	uniforms: StarfieldUniforms,
+	shooting_stars: Vec<ShootingStar>,
+	star_count: u32,
+	star_buffer: wgpu::Buffer,
+	shooting_star_buffer: wgpu::Buffer,
+	uniform_buffer: wgpu::Buffer,
+	uniform_bind_group: wgpu::BindGroup,
+	nebula_pipeline: wgpu::RenderPipeline,
+	star_pipeline: wgpu::RenderPipeline,
+	shooting_star_pipeline: wgpu::RenderPipeline,
+}
+
+impl StarfieldScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		starfield_configuration: &crate::config::StarfieldConfig,
+	) -> Self {
+		let mut random_number_generator = rand::thread_rng();
+
+		// Spread the stars evenly across the layers, making those in nearer layers bigger and brighter.
+		let layer_count = starfield_configuration.layer_count.max(1);
+		let stars = (0..starfield_configuration.star_count)
+			.map(|i| {
+				let parallax = (i % layer_count + 1) as f32 / layer_count as f32;
+				let color = if starfield_configuration.star_colors.is_empty() {
+					[1.0; 3]
+				} else {
+					starfield_configuration.star_colors[random_number_generator
+						.gen_range(0..starfield_configuration.star_colors.len())]
+				};
+				let brightness = parallax * random_number_generator.gen_range(0.3..1.0);
+				StarInstance {
+					position: [random_number_generator.gen(), random_number_generator.gen()],
+					parallax,
+					size: parallax * random_number_generator.gen_range(0.5..1.0),
+					color: color.map(|channel| channel * brightness),
+					twinkle_phase: random_number_generator.gen_range(0.0..std::f32::consts::TAU),
+				}
+			})
+			.collect::<Vec<_>>();
+		let star_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+			label: Some("Starfield scene star buffer"),
+			contents: bytemuck::cast_slice(&stars),
+			usage: wgpu::BufferUsages::VERTEX,
+		});
+		let shooting_star_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Starfield scene shooting star buffer"),
+			size: (MAXIMUM_SHOOTING_STAR_COUNT * std::mem::size_of::<ShootingStarInstance>())
+				as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+
+		let [first_nebula_color, second_nebula_color] = starfield_configuration.nebula_colors;
+		let uniforms = StarfieldUniforms {
+			nebula_colors: [
+				[
+					first_nebula_color[0],
+					first_nebula_color[1],
+					first_nebula_color[2],
+					0.0,
+				],
+				[
+					second_nebula_color[0],
+					second_nebula_color[1],
+					second_nebula_color[2],
+					0.0,
+				],
+			],
+			resolution: [
+				render_target_configuration.width as f32,
+				render_target_configuration.height as f32,
+			],
+			drift_offset: [0.0; 2],
+			// Every run shows a different part of the nebula.
+			nebula_offset: [
+				random_number_generator.gen_range(0.0..100.0),
+				random_number_generator.gen_range(0.0..100.0),
+			],
+			time: 0.0,
+			star_size: starfield_configuration.star_size,
+			star_brightness: starfield_configuration.star_brightness,
+			nebula_brightness: starfield_configuration.nebula_brightness,
+			_padding: [0.0; 2],
+		};
+		let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Starfield scene uniform buffer"),
+			size: std::mem::size_of::<StarfieldUniforms>() as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+		let uniform_bind_group_layout =
+			device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+				label: Some("Starfield scene uniform bind group layout"),
+				entries: &[wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Uniform,
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				}],
+			});
+		let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Starfield scene uniform bind group"),
+			layout: &uniform_bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: uniform_buffer.as_entire_binding(),
+			}],
+		});
+		let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label: Some("Starfield scene pipeline layout"),
+			bind_group_layouts: &[&uniform_bind_group_layout],
+			push_constant_ranges: &[],
+		});
+
+		let nebula_shader_module =
+			crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+				device,
+				"Starfield scene nebula shader",
+				&(include_str!("uniforms.wgsl").to_owned() + include_str!("nebula.wgsl")),
+			);
+		let nebula_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Starfield scene nebula pipeline",
+			&nebula_shader_module,
+			"fragment_stage",
+			&pipeline_layout,
+			wgpu::ColorTargetState {
+				format: render_target_configuration.format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+		let star_shader_module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+			label: Some("Starfield scene star shader"),
+			source: wgpu::ShaderSource::Wgsl(
+				(include_str!("uniforms.wgsl").to_owned() + include_str!("stars.wgsl")).into(),
+			),
+		});
+		let star_pipeline = Self::create_sprite_pipeline(
+			device,
+			render_target_configuration,
+			"Starfield scene star pipeline",
+			&pipeline_layout,
+			&star_shader_module,
+			"star",
+			StarInstance::vertex_buffer_layout(),
+		);
+		let shooting_star_pipeline = Self::create_sprite_pipeline(
+			device,
+			render_target_configuration,
+			"Starfield scene shooting star pipeline",
+			&pipeline_layout,
+			&star_shader_module,
+			"shooting_star",
+			ShootingStarInstance::vertex_buffer_layout(),
+		);
+
+		Self {
+			starfield_configuration: starfield_configuration.clone(),
+			uniforms,
+			shooting_stars: Vec::new(),
+			star_count: stars.len() as u32,
+			star_buffer,
+			shooting_star_buffer,
+			uniform_buffer,
+			uniform_bind_group,
+			nebula_pipeline,
+			star_pipeline,
+			shooting_star_pipeline,
+		}
+	}
+
+	/**
+	 * Create a pipeline that additively draws instanced sprites, each one a triangle strip of four vertices, using the
+	 * entry points that start with the given name.
+	 */
+	fn create_sprite_pipeline(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		label: &str,
+		pipeline_layout: &wgpu::PipelineLayout,
+		shader_module: &wgpu::ShaderModule,
+		entry_point_name: &str,
+		instance_buffer_layout: wgpu::VertexBufferLayout,
+	) -> wgpu::RenderPipeline {
+		let additive_blend_component = wgpu::BlendComponent {
+			src_factor: wgpu::BlendFactor::One,
+			dst_factor: wgpu::BlendFactor::One,
+			operation: wgpu::BlendOperation::Add,
+		};
+		device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some(label),
+			layout: Some(pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: shader_module,
+				entry_point: &(entry_point_name.to_owned() + "_vertex_stage"),
+				buffers: &[instance_buffer_layout],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: shader_module,
+				entry_point: &(entry_point_name.to_owned() + "_fragment_stage"),
+				targets: &[wgpu::ColorTargetState {
+					format: render_target_configuration.format,
+					blend: Some(wgpu::BlendState {
+						color: additive_blend_component,
+						alpha: additive_blend_component,
+					}),
+					write_mask: wgpu::ColorWrites::all(),
+				}],
+			}),
+			primitive: wgpu::PrimitiveState {
+				topology: wgpu::PrimitiveTopology::TriangleStrip,
+				..wgpu::PrimitiveState::default()
+			},
+			depth_stencil: None,
+			multisample: wgpu::MultisampleState::default(),
+			multiview: None,
+		})
+	}
+
+	/**
+	 * Start a shooting star somewhere along the top of the screen, heading down and to one side.
+	 */
+	fn spawn_shooting_star(&mut self) {
+		let mut random_number_generator = rand::thread_rng();
+		let [width, height] = self.uniforms.resolution;
+		let heading: f32 = random_number_generator.gen_range(0.3..0.9)
+			* if random_number_generator.gen() {
+				1.0
+			} else {
+				-1.0
+			};
+		let speed = random_number_generator.gen_range(0.6..1.2) * width.max(height);
+		self.shooting_stars.push(ShootingStar {
+			head: glam::Vec2::new(
+				random_number_generator.gen_range(0.0..width),
+				random_number_generator.gen_range(0.0..0.5 * height),
+			),
+			velocity: speed * glam::Vec2::new(heading.sin(), heading.cos()),
+			age: 0.0,
+			lifetime: random_number_generator.gen_range(0.6..1.2),
+		});
+	}
+}
+
+impl crate::scene::Scene for StarfieldScene {
+	fn resize(
+		&mut self,
+		_device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.uniforms.resolution = [
+			render_target_configuration.width as f32,
+			render_target_configuration.height as f32,
+		];
+	}
+
+	fn update(&mut self, dt: f32) {
+		self.uniforms.time += dt;
+		let [drift_x, drift_y] = self.starfield_configuration.drift_velocity;
+		// Keep the offset small, since the shaders only care about it modulo one.
+		self.uniforms.drift_offset = [
+			(self.uniforms.drift_offset[0] + drift_x * dt).rem_euclid(1.0),
+			(self.uniforms.drift_offset[1] + drift_y * dt).rem_euclid(1.0),
+		];
+
+		for shooting_star in &mut self.shooting_stars {
+			shooting_star.head += shooting_star.velocity * dt;
+			shooting_star.age += dt;
+		}
+		self.shooting_stars
+			.retain(|shooting_star| shooting_star.age < shooting_star.lifetime);
+		let shooting_star_interval = self.starfield_configuration.shooting_star_interval;
+		if shooting_star_interval > 0.0
+			&& self.shooting_stars.len() < MAXIMUM_SHOOTING_STAR_COUNT
+			&& rand::thread_rng().gen::<f32>() < dt / shooting_star_interval
+		{
+			self.spawn_shooting_star();
+		}
+	}
+
+	fn uses_bloom(&self) -> bool {
+		true
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&self.uniforms));
+		let shooting_star_instances = self
+			.shooting_stars
+			.iter()
+			.map(|shooting_star| {
+				// Fade the shooting star in quickly and out slowly over its lifetime.
+				let progress = shooting_star.age / shooting_star.lifetime;
+				let brightness = (8.0 * progress).min(1.0) * (1.0 - progress);
+				let speed = shooting_star.velocity.length();
+				ShootingStarInstance {
+					head: shooting_star.head.into(),
+					direction: (shooting_star.velocity / speed).into(),
+					tail_length: 0.15 * speed,
+					brightness: 4.0 * self.starfield_configuration.star_brightness * brightness,
+				}
+			})
+			.collect::<Vec<_>>();
+		queue.write_buffer(
+			&self.shooting_star_buffer,
+			0,
+			bytemuck::cast_slice(&shooting_star_instances),
+		);
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Starfield scene render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+		render_pass.set_pipeline(&self.nebula_pipeline);
+		render_pass.draw(0..3, 0..1);
+		if self.star_count > 0 {
+			render_pass.set_pipeline(&self.star_pipeline);
+			render_pass.set_vertex_buffer(0, self.star_buffer.slice(..));
+			render_pass.draw(0..4, 0..self.star_count);
+		}
+		if !shooting_star_instances.is_empty() {
+			render_pass.set_pipeline(&self.shooting_star_pipeline);
+			render_pass.set_vertex_buffer(0, self.shooting_star_buffer.slice(..));
+			render_pass.draw(0..4, 0..shooting_star_instances.len() as u32);
+		}
+	}
+}
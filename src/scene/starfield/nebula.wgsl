@@ -0,0 +1,47 @@
+// The nebula is much farther away than any of the stars, so it drifts only a little.
+let nebula_parallax = 0.05;
+let background_color = vec3<f32>(0.002, 0.002, 0.008);
+
+fn hash(position: vec2<f32>) -> f32 {
+	return fract(sin(dot(position, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+fn value_noise(position: vec2<f32>) -> f32 {
+	let cell = floor(position);
+	let offset = position - cell;
+	let fade = offset * offset * (3.0 - 2.0 * offset);
+	let bottom = mix(hash(cell), hash(cell + vec2<f32>(1.0, 0.0)), fade.x);
+	let top = mix(hash(cell + vec2<f32>(0.0, 1.0)), hash(cell + vec2<f32>(1.0, 1.0)), fade.x);
+	return mix(bottom, top, fade.y);
+}
+
+// Adds up five octaves of value noise, rotating each one so that their grids don't line up.
+fn fractal_noise(position: vec2<f32>) -> f32 {
+	let rotation = mat2x2<f32>(0.8, 0.6, -0.6, 0.8);
+	var octave_position = position;
+	var amplitude = 0.5;
+	var sum = 0.0;
+	for (var octave = 0; octave < 5; octave = octave + 1) {
+		sum = sum + amplitude * value_noise(octave_position);
+		octave_position = rotation * octave_position * 2.0 + vec2<f32>(13.7, 5.1);
+		amplitude = amplitude * 0.5;
+	}
+	return sum / 0.96875;
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	let aspect_ratio = uniforms.resolution.x / uniforms.resolution.y;
+	let screen_position = input.texture_coordinates - uniforms.drift_offset * nebula_parallax;
+	let position = screen_position * vec2<f32>(aspect_ratio, 1.0) * 2.0 + uniforms.nebula_offset;
+
+	// Warping the noise by more noise gives the clouds their wispy, swirling shapes.
+	let warp = vec2<f32>(fractal_noise(position), fractal_noise(position + vec2<f32>(5.2, 1.3)));
+	let density = smoothstep(0.4, 0.85, fractal_noise(position + 1.5 * warp));
+	let color = mix(
+		uniforms.nebula_colors[0].rgb,
+		uniforms.nebula_colors[1].rgb,
+		clamp(2.0 * warp.x - 0.5, 0.0, 1.0),
+	);
+	return vec4<f32>(background_color + color * density * density * uniforms.nebula_brightness, 1.0);
+}
@@ -0,0 +1,85 @@
+// Stars that would be smaller than this radius in pixels are drawn at this size and dimmed to match, which keeps them
+// from flickering as they drift between pixels.
+let minimum_star_radius = 1.5;
+// The star sprites are bigger than the stars themselves so that their glow fades out before the edges.
+let sprite_scale = 3.0;
+let shooting_star_width = 1.5;
+let shooting_star_color = vec3<f32>(1.0, 0.95, 0.85);
+
+struct StarInput {
+	@location(0) position: vec2<f32>,
+	// How fast the star's layer drifts relative to the nearest layer.
+	@location(1) parallax: f32,
+	@location(2) size: f32,
+	@location(3) color: vec3<f32>,
+	@location(4) twinkle_phase: f32,
+};
+
+struct ShootingStarInput {
+	// The head of the shooting star and the direction it moves in, in pixels.
+	@location(0) head: vec2<f32>,
+	@location(1) direction: vec2<f32>,
+	@location(2) tail_length: f32,
+	@location(3) brightness: f32,
+};
+
+struct FragmentInput {
+	@builtin(position) clip_position: vec4<f32>,
+	@location(0) corner: vec2<f32>,
+	@location(1) color: vec3<f32>,
+};
+
+fn pixel_to_clip_position(pixel_position: vec2<f32>) -> vec4<f32> {
+	let position = pixel_position / uniforms.resolution * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+	return vec4<f32>(position, 0.0, 1.0);
+}
+
+// Each star is drawn as a triangle strip of four vertices making a square sprite. Stars wrap around the edges of the
+// screen as they drift, so every layer is an endless field.
+@vertex
+fn star_vertex_stage(@builtin(vertex_index) vertex_index: u32, star: StarInput) -> FragmentInput {
+	let corner = vec2<f32>(f32(vertex_index & 1u), f32(vertex_index >> 1u)) * 2.0 - 1.0;
+	let screen_position = fract(star.position + uniforms.drift_offset * star.parallax);
+	let radius = star.size * uniforms.star_size;
+	let drawn_radius = max(radius, minimum_star_radius);
+	let coverage = radius * radius / (drawn_radius * drawn_radius);
+	let twinkle = 0.75 + 0.25 * sin(uniforms.time * 1.3 + star.twinkle_phase);
+	return FragmentInput(
+		pixel_to_clip_position(screen_position * uniforms.resolution + corner * drawn_radius * sprite_scale),
+		corner * sprite_scale,
+		star.color * uniforms.star_brightness * coverage * twinkle,
+	);
+}
+
+@fragment
+fn star_fragment_stage(fragment: FragmentInput) -> @location(0) vec4<f32> {
+	let falloff = exp(-dot(fragment.corner, fragment.corner));
+	return vec4<f32>(fragment.color * falloff, 1.0);
+}
+
+// Each shooting star is drawn as a thin quad stretching back from its head along its tail, where the corner holds how
+// far along the tail and how far across it each vertex is.
+@vertex
+fn shooting_star_vertex_stage(
+	@builtin(vertex_index) vertex_index: u32,
+	shooting_star: ShootingStarInput,
+) -> FragmentInput {
+	let along = f32(vertex_index & 1u);
+	let across = f32(vertex_index >> 1u) * 2.0 - 1.0;
+	let perpendicular = vec2<f32>(-shooting_star.direction.y, shooting_star.direction.x);
+	let pixel_position = shooting_star.head
+		- shooting_star.direction * shooting_star.tail_length * (1.0 - along)
+		+ perpendicular * across * shooting_star_width;
+	return FragmentInput(
+		pixel_to_clip_position(pixel_position),
+		vec2<f32>(along, across),
+		shooting_star_color * shooting_star.brightness,
+	);
+}
+
+@fragment
+fn shooting_star_fragment_stage(fragment: FragmentInput) -> @location(0) vec4<f32> {
+	let tail_falloff = fragment.corner.x * fragment.corner.x * fragment.corner.x;
+	let edge_falloff = max(0.0, 1.0 - fragment.corner.y * fragment.corner.y);
+	return vec4<f32>(fragment.color * tail_falloff * edge_falloff, 1.0);
+}
@@ -0,0 +1,13 @@
+struct StarfieldUniforms {
+	nebula_colors: array<vec4<f32>, 2>,
+	resolution: vec2<f32>,
+	// How far the nearest layer of stars has drifted, in screen widths and heights.
+	drift_offset: vec2<f32>,
+	nebula_offset: vec2<f32>,
+	time: f32,
+	star_size: f32,
+	star_brightness: f32,
+	nebula_brightness: f32,
+};
+@group(0) @binding(0)
+var<uniform> uniforms: StarfieldUniforms;
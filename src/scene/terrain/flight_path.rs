@@ -0,0 +1,93 @@
+use rand::Rng;
+
+/**
+ * An endless path that wanders over the terrain, made of a Catmull-Rom spline through control points that are added
+ * ahead of the camera as it flies along.
+ */
+pub struct FlightPath {
+	/**
+	 * The control points around the current segment. The camera is always between the second and third of them, and
+	 * there is one more after those so that the path can be looked ahead along.
+	 */
+	control_points: std::collections::VecDeque<glam::Vec3>,
+	/**
+	 * How far along the current segment the camera is, from 0 to 1.
+	 */
+	segment_progress: f32,
+	heading: f32,
+	control_point_spacing: f32,
+}
+
+impl FlightPath {
+	/**
+	 * Start a path at the origin, using the given function to pick the height of every control point from its
+	 * horizontal position.
+	 */
+	pub fn new(control_point_spacing: f32, altitude: impl Fn(f32, f32) -> f32) -> Self {
+		let mut flight_path = Self {
+			control_points: std::collections::VecDeque::new(),
+			segment_progress: 0.0,
+			heading: 0.0,
+			control_point_spacing,
+		};
+		flight_path
+			.control_points
+			.push_back(glam::Vec3::new(0.0, altitude(0.0, 0.0), 0.0));
+		while flight_path.control_points.len() < 5 {
+			flight_path.add_control_point(&altitude);
+		}
+		flight_path
+	}
+
+	/**
+	 * Add a control point after the last one, turning a little to the left or right.
+	 */
+	fn add_control_point(&mut self, altitude: &impl Fn(f32, f32) -> f32) {
+		self.heading += rand::thread_rng().gen_range(-0.5..=0.5);
+		let last_control_point = *self.control_points.back().unwrap();
+		let x = last_control_point.x + self.heading.cos() * self.control_point_spacing;
+		let z = last_control_point.z + self.heading.sin() * self.control_point_spacing;
+		self.control_points
+			.push_back(glam::Vec3::new(x, altitude(x, z), z));
+	}
+
+	/**
+	 * Move along the path by the given distance. Distance is measured between control points, so the speed varies a
+	 * little along curves.
+	 */
+	pub fn advance(&mut self, distance: f32, altitude: impl Fn(f32, f32) -> f32) {
+		self.segment_progress += distance / self.control_point_spacing;
+		while self.segment_progress >= 1.0 {
+			self.segment_progress -= 1.0;
+			self.control_points.pop_front();
+			self.add_control_point(&altitude);
+		}
+	}
+
+	/**
+	 * Get the point on the path that is the given number of segments ahead of the camera, which has to be less than
+	 * one.
+	 */
+	pub fn point_ahead(&self, segments_ahead: f32) -> glam::Vec3 {
+		let mut progress = self.segment_progress + segments_ahead;
+		let mut first_index = 0;
+		if progress >= 1.0 {
+			progress -= 1.0;
+			first_index = 1;
+		}
+		Self::catmull_rom(
+			[0, 1, 2, 3].map(|i| self.control_points[first_index + i]),
+			progress,
+		)
+	}
+
+	fn catmull_rom(points: [glam::Vec3; 4], t: f32) -> glam::Vec3 {
+		let [p0, p1, p2, p3] = points;
+		let t2 = t * t;
+		let t3 = t2 * t;
+		0.5 * (2.0 * p1
+			+ (p2 - p0) * t
+			+ (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+			+ (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+	}
+}
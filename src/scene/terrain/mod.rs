@@ -0,0 +1,506 @@
+mod flight_path;
+mod noise;
+
+/**
+ * The sky color, which the terrain fades into with distance.
+ */
+const SKY_COLOR: [f32; 3] = [0.55, 0.7, 0.9];
+
+/**
+ * The height of the water and the height that snow starts at, as fractions of the terrain's height scale.
+ */
+const WATER_LEVEL: f32 = 0.12;
+const SNOW_LEVEL: f32 = 0.7;
+
+/**
+ * The smallest size of hills that the configuration can ask for, below which the terrain would just be noise.
+ */
+const MINIMUM_FEATURE_SIZE: f32 = 1.0;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct TerrainVertex {
+	position: [f32; 3],
+	normal: [f32; 3],
+}
+
+impl TerrainVertex {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+		wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Vertex,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	camera_position: [f32; 3],
+	fog_distance: f32,
+	sun_direction: [f32; 3],
+	water_level: f32,
+	sky_color: [f32; 3],
+	snow_level: f32,
+}
+
+/**
+ * A slot holding the vertex buffer of one chunk of terrain. Slots are reused for new chunks as old ones go out of
+ * range, since buffers can't be created while rendering.
+ */
+struct ChunkSlot {
+	vertex_buffer: wgpu::Buffer,
+	/**
+	 * The coordinates of the chunk in the buffer, if it holds one.
+	 */
+	chunk_coordinates: Option<[i32; 2]>,
+	/**
+	 * The center and radius of a sphere surrounding the chunk.
+	 */
+	bounding_sphere: (glam::Vec3A, f32),
+	/**
+	 * The vertices that have been generated for the chunk but not written into the buffer yet.
+	 */
+	pending_vertices: Option<Vec<TerrainVertex>>,
+}
+
+/**
+ * Flies the camera along a wandering path over endless terrain made from fractal noise. The terrain is split into
+ * square chunks that are generated as they come within the view distance and dropped as they leave it, and only the
+ * chunks that the camera can see are drawn.
+ */
+pub struct TerrainScene {
+	terrain_configuration: crate::config::TerrainConfig,
+	noise: noise::GradientNoise,
+	flight_path: flight_path::FlightPath,
+	camera: crate::scene::utilities::camera::Camera,
+	chunk_slots: Vec<ChunkSlot>,
+	index_buffer: wgpu::Buffer,
+	index_count: u32,
+	camera_uniform_buffer: wgpu::Buffer,
+	camera_bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+	multisampled_color_target: crate::scene::utilities::render_target::MultisampledColorTarget,
+	depth_texture: crate::scene::utilities::texture::Texture,
+}
+
+impl TerrainScene {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		terrain_configuration: &crate::config::TerrainConfig,
+	) -> Self {
+		// A chunk needs at least one square, and hills need a size to divide the noise coordinates by.
+		let terrain_configuration = &crate::config::TerrainConfig {
+			chunk_resolution: terrain_configuration.chunk_resolution.max(1),
+			feature_size: terrain_configuration.feature_size.max(MINIMUM_FEATURE_SIZE),
+			..terrain_configuration.clone()
+		};
+		let noise = noise::GradientNoise::random();
+		let flight_path = flight_path::FlightPath::new(
+			terrain_configuration.chunk_size,
+			Self::flight_altitude_function(&noise, terrain_configuration),
+		);
+		let mut camera = crate::scene::utilities::camera::Camera::new(
+			std::f32::consts::FRAC_PI_3,
+			render_target_configuration.width as f32 / render_target_configuration.height as f32,
+		);
+		// Unlike the bouncing cube scene, y points up in this world.
+		camera.up_direction = glam::Vec3A::Y;
+		camera.near_plane_distance = 0.5;
+		camera.far_plane_distance = 2.0 * Self::view_distance(terrain_configuration);
+
+		// Make a slot for every chunk that can be in range at once, all sharing the same indices.
+		let chunk_resolution = terrain_configuration.chunk_resolution;
+		let vertex_count = (chunk_resolution + 1) * (chunk_resolution + 1);
+		let slot_count = (2 * terrain_configuration.view_distance + 1).pow(2) as usize;
+		let chunk_slots = (0..slot_count)
+			.map(|_| ChunkSlot {
+				vertex_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+					label: Some("Terrain scene chunk vertex buffer"),
+					size: (vertex_count as usize * std::mem::size_of::<TerrainVertex>())
+						as wgpu::BufferAddress,
+					usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+					mapped_at_creation: false,
+				}),
+				chunk_coordinates: None,
+				bounding_sphere: (glam::Vec3A::ZERO, 0.0),
+				pending_vertices: None,
+			})
+			.collect::<Vec<_>>();
+		let indices = (0..chunk_resolution)
+			.flat_map(|row| {
+				(0..chunk_resolution).flat_map(move |column| {
+					let top_left = row * (chunk_resolution + 1) + column;
+					let bottom_left = top_left + chunk_resolution + 1;
+					[
+						top_left,
+						bottom_left,
+						top_left + 1,
+						top_left + 1,
+						bottom_left,
+						bottom_left + 1,
+					]
+				})
+			})
+			.collect::<Vec<u32>>();
+		let index_buffer = wgpu::util::DeviceExt::create_buffer_init(
+			device,
+			&wgpu::util::BufferInitDescriptor {
+				label: Some("Terrain scene chunk index buffer"),
+				contents: bytemuck::cast_slice(&indices),
+				usage: wgpu::BufferUsages::INDEX,
+			},
+		);
+
+		let (camera_uniform_buffer, camera_bind_group_layout, camera_bind_group) =
+			camera.create_bind_group(device, "Terrain scene");
+		let shader_module = device.create_shader_module(&wgpu::include_wgsl!("render.wgsl"));
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Terrain scene pipeline layout"),
+				bind_group_layouts: &[&camera_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::FRAGMENT,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("Terrain scene pipeline"),
+			layout: Some(&render_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[TerrainVertex::vertex_buffer_layout()],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: &shader_module,
+				entry_point: "fragment_stage",
+				targets: &[wgpu::ColorTargetState {
+					format: render_target_configuration.format,
+					blend: None,
+					write_mask: wgpu::ColorWrites::all(),
+				}],
+			}),
+			primitive: wgpu::PrimitiveState {
+				cull_mode: Some(wgpu::Face::Back),
+				..wgpu::PrimitiveState::default()
+			},
+			depth_stencil: Some(wgpu::DepthStencilState {
+				format: crate::scene::utilities::texture::Texture::DEPTH_FORMAT,
+				depth_write_enabled: true,
+				depth_compare: wgpu::CompareFunction::Less,
+				stencil: wgpu::StencilState::default(),
+				bias: wgpu::DepthBiasState::default(),
+			}),
+			multisample: render_target_configuration.multisample_state(),
+			multiview: None,
+		});
+		let multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Terrain scene",
+			);
+		let depth_texture = crate::scene::utilities::texture::Texture::create_depth_texture(
+			device,
+			render_target_configuration.width,
+			render_target_configuration.height,
+			render_target_configuration.sample_count,
+			"Terrain scene",
+		);
+
+		let mut terrain_scene = Self {
+			terrain_configuration: terrain_configuration.clone(),
+			noise,
+			flight_path,
+			camera,
+			chunk_slots,
+			index_buffer,
+			index_count: indices.len() as u32,
+			camera_uniform_buffer,
+			camera_bind_group,
+			render_pipeline,
+			multisampled_color_target,
+			depth_texture,
+		};
+		terrain_scene.update_camera();
+		// Load everything in range up front so that the first frames don't show the terrain popping in.
+		while terrain_scene.stream_chunks(usize::MAX) {}
+		terrain_scene
+	}
+
+	/**
+	 * Get how far away from the camera chunks are kept loaded.
+	 */
+	fn view_distance(terrain_configuration: &crate::config::TerrainConfig) -> f32 {
+		terrain_configuration.view_distance as f32 * terrain_configuration.chunk_size
+	}
+
+	fn terrain_height(
+		noise: &noise::GradientNoise,
+		terrain_configuration: &crate::config::TerrainConfig,
+		x: f32,
+		z: f32,
+	) -> f32 {
+		let noise_value = noise.fractal_sample(
+			glam::Vec2::new(x, z) / terrain_configuration.feature_size,
+			terrain_configuration.octave_count,
+		);
+		// Squaring the noise flattens the lowlands into valleys while keeping the peaks sharp.
+		let elevation = 0.5 * noise_value + 0.5;
+		let height = terrain_configuration.height_scale * elevation * elevation;
+		height.max(WATER_LEVEL * terrain_configuration.height_scale)
+	}
+
+	/**
+	 * Make the function that picks the height of the flight path, which keeps it above the highest terrain around
+	 * each control point.
+	 */
+	fn flight_altitude_function<'a>(
+		noise: &'a noise::GradientNoise,
+		terrain_configuration: &'a crate::config::TerrainConfig,
+	) -> impl Fn(f32, f32) -> f32 + 'a {
+		move |x, z| {
+			let sample_distance = 0.5 * terrain_configuration.chunk_size;
+			let highest_terrain = [(0.0, 0.0), (1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)]
+				.iter()
+				.map(|(x_offset, z_offset)| {
+					Self::terrain_height(
+						noise,
+						terrain_configuration,
+						x + x_offset * sample_distance,
+						z + z_offset * sample_distance,
+					)
+				})
+				.fold(f32::MIN, f32::max);
+			highest_terrain + terrain_configuration.flight_altitude
+		}
+	}
+
+	/**
+	 * Generate the vertices of a chunk along with a sphere that surrounds them.
+	 */
+	fn generate_chunk(
+		&self,
+		chunk_coordinates: [i32; 2],
+	) -> (Vec<TerrainVertex>, (glam::Vec3A, f32)) {
+		let chunk_size = self.terrain_configuration.chunk_size;
+		let chunk_resolution = self.terrain_configuration.chunk_resolution;
+		let spacing = chunk_size / chunk_resolution as f32;
+		let origin_x = chunk_coordinates[0] as f32 * chunk_size;
+		let origin_z = chunk_coordinates[1] as f32 * chunk_size;
+		let height =
+			|x: f32, z: f32| Self::terrain_height(&self.noise, &self.terrain_configuration, x, z);
+		let mut minimum_height = f32::MAX;
+		let mut maximum_height = f32::MIN;
+		let vertices = (0..=chunk_resolution)
+			.flat_map(|row| (0..=chunk_resolution).map(move |column| (row, column)))
+			.map(|(row, column)| {
+				let x = origin_x + column as f32 * spacing;
+				let z = origin_z + row as f32 * spacing;
+				let y = height(x, z);
+				minimum_height = minimum_height.min(y);
+				maximum_height = maximum_height.max(y);
+				// Take the normal from the heights around the vertex, even those in neighboring chunks, so that there
+				// are no seams between chunks.
+				let normal = glam::Vec3::new(
+					height(x - spacing, z) - height(x + spacing, z),
+					2.0 * spacing,
+					height(x, z - spacing) - height(x, z + spacing),
+				)
+				.normalize();
+				TerrainVertex {
+					position: [x, y, z],
+					normal: normal.into(),
+				}
+			})
+			.collect::<Vec<_>>();
+		let center = glam::Vec3A::new(
+			origin_x + 0.5 * chunk_size,
+			0.5 * (minimum_height + maximum_height),
+			origin_z + 0.5 * chunk_size,
+		);
+		let radius = glam::Vec3A::new(
+			0.5 * chunk_size,
+			0.5 * (maximum_height - minimum_height),
+			0.5 * chunk_size,
+		)
+		.length();
+		(vertices, (center, radius))
+	}
+
+	/**
+	 * Free the slots of chunks that have gone out of range and generate up to the given number of the closest chunks
+	 * that have come into range. Returns whether any chunks in range are still missing.
+	 */
+	fn stream_chunks(&mut self, maximum_generated_chunk_count: usize) -> bool {
+		let chunk_size = self.terrain_configuration.chunk_size;
+		let view_distance = self.terrain_configuration.view_distance as i32;
+		let camera_chunk = [
+			(self.camera.position.x / chunk_size).floor() as i32,
+			(self.camera.position.z / chunk_size).floor() as i32,
+		];
+		let is_in_range = |chunk_coordinates: [i32; 2]| {
+			(chunk_coordinates[0] - camera_chunk[0]).abs() <= view_distance
+				&& (chunk_coordinates[1] - camera_chunk[1]).abs() <= view_distance
+		};
+		for chunk_slot in &mut self.chunk_slots {
+			if chunk_slot
+				.chunk_coordinates
+				.is_some_and(|chunk_coordinates| !is_in_range(chunk_coordinates))
+			{
+				chunk_slot.chunk_coordinates = None;
+				chunk_slot.pending_vertices = None;
+			}
+		}
+
+		let mut missing_chunks = (-view_distance..=view_distance)
+			.flat_map(|z| (-view_distance..=view_distance).map(move |x| [x, z]))
+			.map(|[x, z]| [camera_chunk[0] + x, camera_chunk[1] + z])
+			.filter(|&chunk_coordinates| {
+				!self
+					.chunk_slots
+					.iter()
+					.any(|chunk_slot| chunk_slot.chunk_coordinates == Some(chunk_coordinates))
+			})
+			.collect::<Vec<_>>();
+		missing_chunks.sort_by_key(|chunk_coordinates| {
+			(chunk_coordinates[0] - camera_chunk[0]).pow(2)
+				+ (chunk_coordinates[1] - camera_chunk[1]).pow(2)
+		});
+		let generated_chunk_count = missing_chunks.len().min(maximum_generated_chunk_count);
+		for &chunk_coordinates in &missing_chunks[..generated_chunk_count] {
+			let (vertices, bounding_sphere) = self.generate_chunk(chunk_coordinates);
+			if let Some(chunk_slot) = self
+				.chunk_slots
+				.iter_mut()
+				.find(|chunk_slot| chunk_slot.chunk_coordinates.is_none())
+			{
+				chunk_slot.chunk_coordinates = Some(chunk_coordinates);
+				chunk_slot.bounding_sphere = bounding_sphere;
+				chunk_slot.pending_vertices = Some(vertices);
+			}
+		}
+		generated_chunk_count < missing_chunks.len()
+	}
+
+	/**
+	 * Put the camera where it is along the flight path, looking a little way further along it.
+	 */
+	fn update_camera(&mut self) {
+		let position = self.flight_path.point_ahead(0.0);
+		let look_at_position = self.flight_path.point_ahead(0.5)
+			- glam::Vec3::Y * 0.2 * self.terrain_configuration.flight_altitude;
+		self.camera.position = position.into();
+		self.camera.look_direction = (look_at_position - position).normalize().into();
+		self.camera.recalculate_transformation_and_view_planes();
+	}
+}
+
+impl crate::scene::Scene for TerrainScene {
+	fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+	) {
+		self.camera.aspect_ratio =
+			render_target_configuration.width as f32 / render_target_configuration.height as f32;
+		self.camera.recalculate_transformation_and_view_planes();
+		self.multisampled_color_target =
+			crate::scene::utilities::render_target::MultisampledColorTarget::new(
+				device,
+				render_target_configuration,
+				"Terrain scene",
+			);
+		self.depth_texture = crate::scene::utilities::texture::Texture::create_depth_texture(
+			device,
+			render_target_configuration.width,
+			render_target_configuration.height,
+			render_target_configuration.sample_count,
+			"Terrain scene",
+		);
+	}
+
+	fn update(&mut self, dt: f32) {
+		self.flight_path.advance(
+			self.terrain_configuration.flight_speed * dt,
+			Self::flight_altitude_function(&self.noise, &self.terrain_configuration),
+		);
+		self.update_camera();
+		self.stream_chunks(self.terrain_configuration.chunks_generated_per_frame as usize);
+	}
+
+	fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		for chunk_slot in &mut self.chunk_slots {
+			if let Some(vertices) = chunk_slot.pending_vertices.take() {
+				queue.write_buffer(
+					&chunk_slot.vertex_buffer,
+					0,
+					bytemuck::cast_slice(&vertices),
+				);
+			}
+		}
+		queue.write_buffer(
+			&self.camera_uniform_buffer,
+			0,
+			bytemuck::bytes_of(&self.camera.transformation),
+		);
+
+		let sun_direction =
+			glam::Vec3::from(self.terrain_configuration.sun_direction).normalize_or_zero();
+		let [sky_red, sky_green, sky_blue] = SKY_COLOR;
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Terrain scene render pass"),
+			color_attachments: &[self.multisampled_color_target.color_attachment(
+				output_texture_view,
+				wgpu::LoadOp::Clear(wgpu::Color {
+					r: sky_red as f64,
+					g: sky_green as f64,
+					b: sky_blue as f64,
+					a: 1.0,
+				}),
+			)],
+			depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+				view: &self.depth_texture.texture_view,
+				depth_ops: Some(wgpu::Operations {
+					load: wgpu::LoadOp::Clear(1.0),
+					store: true,
+				}),
+				stencil_ops: None,
+			}),
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&PushConstantData {
+				camera_position: self.camera.position.into(),
+				fog_distance: Self::view_distance(&self.terrain_configuration),
+				sun_direction: sun_direction.into(),
+				water_level: WATER_LEVEL * self.terrain_configuration.height_scale,
+				sky_color: SKY_COLOR,
+				snow_level: SNOW_LEVEL * self.terrain_configuration.height_scale,
+			}),
+		);
+		render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+		render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+		for chunk_slot in &self.chunk_slots {
+			let (center, radius) = chunk_slot.bounding_sphere;
+			if chunk_slot.chunk_coordinates.is_none() || !self.camera.can_see(center, radius) {
+				continue;
+			}
+			render_pass.set_vertex_buffer(0, chunk_slot.vertex_buffer.slice(..));
+			render_pass.draw_indexed(0..self.index_count, 0, 0..1);
+		}
+	}
+}
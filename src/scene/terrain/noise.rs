@@ -0,0 +1,75 @@
+use rand::Rng;
+
+/**
+ * Two dimensional gradient noise, which varies smoothly between -1 and 1 with features about one unit apart.
+ */
+pub struct GradientNoise {
+	/**
+	 * A shuffled list of the numbers from 0 to 255, repeated twice so that lookups never have to wrap around.
+	 */
+	permutation: [u8; 512],
+}
+
+impl GradientNoise {
+	/**
+	 * Make noise with a random arrangement of gradients, so that every run gets different noise.
+	 */
+	pub fn random() -> Self {
+		let mut random_number_generator = rand::thread_rng();
+		let mut values = [0u8; 256];
+		for (i, value) in values.iter_mut().enumerate() {
+			*value = i as u8;
+		}
+		for i in (1..values.len()).rev() {
+			values.swap(i, random_number_generator.gen_range(0..=i));
+		}
+		let mut permutation = [0u8; 512];
+		for (i, value) in permutation.iter_mut().enumerate() {
+			*value = values[i % 256];
+		}
+		Self { permutation }
+	}
+
+	fn gradient(&self, x: i32, y: i32) -> glam::Vec2 {
+		let hash =
+			self.permutation[self.permutation[(x & 255) as usize] as usize + (y & 255) as usize];
+		let angle = hash as f32 * (std::f32::consts::TAU / 256.0);
+		glam::Vec2::new(angle.cos(), angle.sin())
+	}
+
+	pub fn sample(&self, point: glam::Vec2) -> f32 {
+		let cell = point.floor();
+		let offset = point - cell;
+		let (x, y) = (cell.x as i32, cell.y as i32);
+		// Smooth the interpolation with a quintic curve so that the noise has no visible creases along cell edges.
+		let fade = offset * offset * offset * (offset * (offset * 6.0 - 15.0) + 10.0);
+		let corner = |corner_x: i32, corner_y: i32| {
+			self.gradient(x + corner_x, y + corner_y)
+				.dot(offset - glam::Vec2::new(corner_x as f32, corner_y as f32))
+		};
+		let bottom = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * fade.x;
+		let top = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * fade.x;
+		// The gradients are unit length, so the raw noise only reaches about ±0.7.
+		(bottom + (top - bottom) * fade.y) * std::f32::consts::SQRT_2
+	}
+
+	/**
+	 * Add up octaves of noise, each with twice the frequency and half the amplitude of the one before it, which gives
+	 * both large hills and small bumps. The result stays between -1 and 1.
+	 */
+	pub fn fractal_sample(&self, point: glam::Vec2, octave_count: u32) -> f32 {
+		let mut sum = 0.0;
+		let mut amplitude = 1.0;
+		let mut amplitude_sum = 0.0;
+		let mut frequency = 1.0;
+		for octave in 0..octave_count {
+			// Rotating and shifting every octave keeps the grids of the octaves from lining up.
+			let rotated_point = glam::Mat2::from_angle(octave as f32 * 0.5) * point;
+			sum += amplitude * self.sample(rotated_point * frequency + octave as f32 * 17.0);
+			amplitude_sum += amplitude;
+			amplitude *= 0.5;
+			frequency *= 2.0;
+		}
+		sum / amplitude_sum
+	}
+}
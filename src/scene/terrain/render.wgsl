@@ -0,0 +1,73 @@
+struct VertexInput {
+	@location(0) position: vec3<f32>,
+	@location(1) normal: vec3<f32>,
+};
+
+struct FragmentInput {
+	@builtin(position) clip_position: vec4<f32>,
+	@location(0) world_position: vec3<f32>,
+	@location(1) normal: vec3<f32>,
+};
+
+struct Transform {
+	transformation: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera_transform: Transform;
+
+struct PushConstantData {
+	camera_position: vec3<f32>,
+	// The distance at which the fog has almost entirely hidden the terrain.
+	fog_distance: f32,
+	sun_direction: vec3<f32>,
+	water_level: f32,
+	sky_color: vec3<f32>,
+	snow_level: f32,
+};
+var<push_constant> push_constant_data: PushConstantData;
+
+let sun_color = vec3<f32>(1.5, 1.4, 1.2);
+let grass_color = vec3<f32>(0.18, 0.32, 0.1);
+let rock_color = vec3<f32>(0.3, 0.27, 0.24);
+let snow_color = vec3<f32>(0.9, 0.92, 0.95);
+let water_color = vec3<f32>(0.04, 0.12, 0.2);
+
+@vertex
+fn vertex_stage(vertex: VertexInput) -> FragmentInput {
+	return FragmentInput(
+		camera_transform.transformation * vec4<f32>(vertex.position, 1.0),
+		vertex.position,
+		vertex.normal,
+	);
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	let normal = normalize(input.normal);
+	let height = input.world_position.y;
+
+	// Steep slopes are bare rock, and high flat ground is covered in snow.
+	let steepness = 1.0 - normal.y;
+	var albedo = mix(grass_color, rock_color, smoothstep(0.15, 0.35, steepness));
+	let snow_amount = smoothstep(push_constant_data.snow_level - 2.0, push_constant_data.snow_level + 2.0, height) * (1.0 - smoothstep(0.3, 0.5, steepness));
+	albedo = mix(albedo, snow_color, snow_amount);
+	var shininess = 0.0;
+	if (height <= push_constant_data.water_level + 0.01) {
+		albedo = water_color;
+		shininess = 1.0;
+	}
+
+	let view_direction = normalize(push_constant_data.camera_position - input.world_position);
+	let sun_direction = normalize(push_constant_data.sun_direction);
+	let diffuse_amount = max(dot(normal, sun_direction), 0.0);
+	let half_direction = normalize(view_direction + sun_direction);
+	let specular_amount = shininess * pow(max(dot(normal, half_direction), 0.0), 64.0);
+	let ambient_amount = 0.25 * (0.5 + 0.5 * normal.y);
+	let color = albedo * (diffuse_amount * sun_color + ambient_amount * push_constant_data.sky_color) + specular_amount * sun_color;
+
+	// Fade into the sky with distance so that chunks streaming in at the edge of the view aren't noticeable.
+	let distance = length(push_constant_data.camera_position - input.world_position);
+	let relative_distance = 2.0 * distance / push_constant_data.fog_distance;
+	let fog_amount = 1.0 - exp(-relative_distance * relative_distance);
+	return vec4<f32>(mix(color, push_constant_data.sky_color, fog_amount), 1.0);
+}
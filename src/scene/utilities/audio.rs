@@ -0,0 +1,92 @@
+/**
+ * The WGSL declaration of the `AudioUniforms` struct, which scenes prepend to shaders that bind the audio uniform
+ * buffer.
+ */
+pub const AUDIO_UNIFORMS_SOURCE: &str = include_str!("audio.wgsl");
+
+/**
+ * How quickly the beat value falls off after a beat, per second.
+ */
+const BEAT_DECAY_RATE: f32 = 6.0;
+
+/**
+ * The audio features as they're laid out in a uniform buffer, matching the `AudioUniforms` struct in audio.wgsl.
+ */
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct AudioUniforms {
+	pub bands: [[f32; 4]; 4],
+	pub level: f32,
+	pub beat: f32,
+	pub seconds_since_beat: f32,
+	pub beat_count: u32,
+}
+
+impl AudioUniforms {
+	/**
+	 * Get the uniforms for the features as they are right now. The beat value is worked out here rather than by the
+	 * audio input so that it falls off smoothly from frame to frame.
+	 */
+	pub fn new(audio_features: &crate::audio::AudioFeatures) -> Self {
+		let mut bands = [[0.0; 4]; 4];
+		for (band_index, band) in audio_features.bands.iter().enumerate() {
+			bands[band_index / 4][band_index % 4] = *band;
+		}
+		let seconds_since_beat = audio_features
+			.last_beat_instant
+			.map_or(f32::MAX, |last_beat_instant| {
+				last_beat_instant.elapsed().as_secs_f32()
+			});
+		Self {
+			bands,
+			level: audio_features.level,
+			beat: (-BEAT_DECAY_RATE * seconds_since_beat).exp(),
+			seconds_since_beat,
+			beat_count: audio_features.beat_count,
+		}
+	}
+}
+
+/**
+ * Create the uniform buffer holding the audio uniforms along with a bind group for it. The buffer starts out zeroed,
+ * which is what scenes see when there is no audio input.
+ */
+pub fn create_audio_bind_group(
+	device: &wgpu::Device,
+	scene_name: &str,
+	visibility: wgpu::ShaderStages,
+) -> (wgpu::Buffer, wgpu::BindGroupLayout, wgpu::BindGroup) {
+	let audio_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+		label: Some(&(scene_name.to_owned() + " audio uniform buffer")),
+		size: std::mem::size_of::<AudioUniforms>() as wgpu::BufferAddress,
+		usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+		mapped_at_creation: false,
+	});
+	let audio_bind_group_layout =
+		device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some(&(scene_name.to_owned() + " audio bind group layout")),
+			entries: &[wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility,
+				ty: wgpu::BindingType::Buffer {
+					ty: wgpu::BufferBindingType::Uniform,
+					has_dynamic_offset: false,
+					min_binding_size: None,
+				},
+				count: None,
+			}],
+		});
+	let audio_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+		label: Some(&(scene_name.to_owned() + " audio bind group")),
+		layout: &audio_bind_group_layout,
+		entries: &[wgpu::BindGroupEntry {
+			binding: 0,
+			resource: audio_uniform_buffer.as_entire_binding(),
+		}],
+	});
+	(
+		audio_uniform_buffer,
+		audio_bind_group_layout,
+		audio_bind_group,
+	)
+}
@@ -0,0 +1,11 @@
+struct AudioUniforms {
+	// How loud each of the 16 frequency bands is from 0 to 1, going from the lowest frequencies to the highest four at
+	// a time.
+	bands: array<vec4<f32>, 4>,
+	// The root mean square of the most recent samples.
+	level: f32,
+	// 1 right on a beat, falling off towards 0 until the next one.
+	beat: f32,
+	seconds_since_beat: f32,
+	beat_count: u32,
+};
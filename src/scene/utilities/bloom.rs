@@ -0,0 +1,351 @@
+use crate::scene::utilities::tonemapping::HDR_FORMAT;
+
+/**
+ * The most mip levels that the bloom will blur through. Each level halves the resolution of the previous one.
+ */
+const MAXIMUM_MIP_LEVEL_COUNT: u32 = 6;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	source_texel_size: [f32; 2],
+	filter_radius: [f32; 2],
+	threshold: f32,
+	is_first_downsample: u32,
+}
+
+/**
+ * A physically based bloom that blurs bright parts of a high dynamic range texture by progressively downsampling
+ * them into a mip chain, upsampling back through the chain, and then adding the result on top of the texture.
+ */
+pub struct Bloom {
+	bloom_configuration: crate::config::BloomConfig,
+	hdr_texture_size: [u32; 2],
+	mip_sizes: Vec<[u32; 2]>,
+	mip_texture_views: Vec<wgpu::TextureView>,
+	source_bind_group_layout: wgpu::BindGroupLayout,
+	sampler: wgpu::Sampler,
+	hdr_texture_bind_group: wgpu::BindGroup,
+	mip_bind_groups: Vec<wgpu::BindGroup>,
+	downsample_pipeline: wgpu::RenderPipeline,
+	upsample_pipeline: wgpu::RenderPipeline,
+	composite_pipeline: wgpu::RenderPipeline,
+}
+
+impl Bloom {
+	pub fn new(
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		hdr_texture: &crate::scene::utilities::texture::Texture,
+		bloom_configuration: &crate::config::BloomConfig,
+	) -> Self {
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			"Bloom shader",
+			include_str!("bloom.wgsl"),
+		);
+		let source_bind_group_layout = hdr_texture.create_bind_group_layout(
+			device,
+			"Bloom source",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some("Bloom sampler"),
+			address_mode_u: wgpu::AddressMode::ClampToEdge,
+			address_mode_v: wgpu::AddressMode::ClampToEdge,
+			address_mode_w: wgpu::AddressMode::ClampToEdge,
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			mipmap_filter: wgpu::FilterMode::Nearest,
+			..wgpu::SamplerDescriptor::default()
+		});
+
+		// All passes draw a fullscreen triangle and only differ in their fragment stage and in how they blend.
+		let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label: Some("Bloom pipeline layout"),
+			bind_group_layouts: &[&source_bind_group_layout],
+			push_constant_ranges: &[wgpu::PushConstantRange {
+				stages: wgpu::ShaderStages::FRAGMENT,
+				range: 0..std::mem::size_of::<PushConstantData>() as u32,
+			}],
+		});
+		let create_pipeline = |label: &str, entry_point: &str, blend: Option<wgpu::BlendState>| {
+			crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+				device,
+				label,
+				&shader_module,
+				entry_point,
+				&pipeline_layout,
+				wgpu::ColorTargetState {
+					format: HDR_FORMAT,
+					blend,
+					write_mask: wgpu::ColorWrites::all(),
+				},
+			)
+		};
+		let downsample_pipeline =
+			create_pipeline("Bloom downsample pipeline", "downsample_stage", None);
+		let upsample_pipeline = create_pipeline(
+			"Bloom upsample pipeline",
+			"upsample_stage",
+			Some(wgpu::BlendState {
+				color: wgpu::BlendComponent {
+					src_factor: wgpu::BlendFactor::One,
+					dst_factor: wgpu::BlendFactor::One,
+					operation: wgpu::BlendOperation::Add,
+				},
+				alpha: wgpu::BlendComponent::REPLACE,
+			}),
+		);
+		let composite_pipeline = create_pipeline(
+			"Bloom composite pipeline",
+			"upsample_stage",
+			Some(wgpu::BlendState {
+				color: wgpu::BlendComponent {
+					src_factor: wgpu::BlendFactor::Constant,
+					dst_factor: wgpu::BlendFactor::One,
+					operation: wgpu::BlendOperation::Add,
+				},
+				alpha: wgpu::BlendComponent {
+					src_factor: wgpu::BlendFactor::Zero,
+					dst_factor: wgpu::BlendFactor::One,
+					operation: wgpu::BlendOperation::Add,
+				},
+			}),
+		);
+
+		let (hdr_texture_bind_group, mip_sizes, mip_texture_views, mip_bind_groups) =
+			Self::create_targets(
+				device,
+				surface_configuration,
+				hdr_texture,
+				&source_bind_group_layout,
+				&sampler,
+			);
+		Self {
+			bloom_configuration: *bloom_configuration,
+			hdr_texture_size: [surface_configuration.width, surface_configuration.height],
+			mip_sizes,
+			mip_texture_views,
+			source_bind_group_layout,
+			sampler,
+			hdr_texture_bind_group,
+			mip_bind_groups,
+			downsample_pipeline,
+			upsample_pipeline,
+			composite_pipeline,
+		}
+	}
+
+	/**
+	 * Create the mip chain that the bloom is blurred through along with the bind groups for reading from the high
+	 * dynamic range texture and from each mip level.
+	 */
+	fn create_targets(
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		hdr_texture: &crate::scene::utilities::texture::Texture,
+		source_bind_group_layout: &wgpu::BindGroupLayout,
+		sampler: &wgpu::Sampler,
+	) -> (
+		wgpu::BindGroup,
+		Vec<[u32; 2]>,
+		Vec<wgpu::TextureView>,
+		Vec<wgpu::BindGroup>,
+	) {
+		let create_source_bind_group = |texture_view: &wgpu::TextureView| {
+			device.create_bind_group(&wgpu::BindGroupDescriptor {
+				label: Some("Bloom source bind group"),
+				layout: source_bind_group_layout,
+				entries: &[
+					wgpu::BindGroupEntry {
+						binding: 0,
+						resource: wgpu::BindingResource::TextureView(texture_view),
+					},
+					wgpu::BindGroupEntry {
+						binding: 1,
+						resource: wgpu::BindingResource::Sampler(sampler),
+					},
+				],
+			})
+		};
+		let hdr_texture_bind_group = create_source_bind_group(&hdr_texture.texture_view);
+
+		let mut mip_sizes = Vec::new();
+		let mut mip_size = [
+			(surface_configuration.width / 2).max(1),
+			(surface_configuration.height / 2).max(1),
+		];
+		loop {
+			mip_sizes.push(mip_size);
+			if mip_sizes.len() as u32 == MAXIMUM_MIP_LEVEL_COUNT
+				|| mip_size[0] < 2
+				|| mip_size[1] < 2
+			{
+				break;
+			}
+			mip_size = [mip_size[0] / 2, mip_size[1] / 2];
+		}
+		let mip_texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("Bloom mip chain texture"),
+			size: wgpu::Extent3d {
+				width: mip_sizes[0][0],
+				height: mip_sizes[0][1],
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: mip_sizes.len() as u32,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: HDR_FORMAT,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+		});
+		let mip_texture_views = (0..mip_sizes.len() as u32)
+			.map(|mip_level| {
+				mip_texture.create_view(&wgpu::TextureViewDescriptor {
+					label: Some("Bloom mip chain texture view"),
+					base_mip_level: mip_level,
+					mip_level_count: std::num::NonZeroU32::new(1),
+					..wgpu::TextureViewDescriptor::default()
+				})
+			})
+			.collect::<Vec<_>>();
+		let mip_bind_groups = mip_texture_views
+			.iter()
+			.map(create_source_bind_group)
+			.collect::<Vec<_>>();
+		(
+			hdr_texture_bind_group,
+			mip_sizes,
+			mip_texture_views,
+			mip_bind_groups,
+		)
+	}
+
+	/**
+	 * Recreate the mip chain to match the new size of the surface and the recreated high dynamic range texture.
+	 */
+	pub fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		hdr_texture: &crate::scene::utilities::texture::Texture,
+	) {
+		let (hdr_texture_bind_group, mip_sizes, mip_texture_views, mip_bind_groups) =
+			Self::create_targets(
+				device,
+				surface_configuration,
+				hdr_texture,
+				&self.source_bind_group_layout,
+				&self.sampler,
+			);
+		self.hdr_texture_size = [surface_configuration.width, surface_configuration.height];
+		self.hdr_texture_bind_group = hdr_texture_bind_group;
+		self.mip_sizes = mip_sizes;
+		self.mip_texture_views = mip_texture_views;
+		self.mip_bind_groups = mip_bind_groups;
+	}
+
+	/**
+	 * Draw a fullscreen triangle that reads from the given source into the given target.
+	 */
+	fn run_pass(
+		&self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		pipeline: &wgpu::RenderPipeline,
+		source_bind_group: &wgpu::BindGroup,
+		target_texture_view: &wgpu::TextureView,
+		load: wgpu::LoadOp<wgpu::Color>,
+		push_constant_data: PushConstantData,
+	) {
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Bloom render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: target_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations { load, store: true },
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(pipeline);
+		render_pass.set_bind_group(0, source_bind_group, &[]);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&push_constant_data),
+		);
+		let intensity = self.bloom_configuration.intensity as f64;
+		render_pass.set_blend_constant(wgpu::Color {
+			r: intensity,
+			g: intensity,
+			b: intensity,
+			a: intensity,
+		});
+		render_pass.draw(0..3, 0..1);
+	}
+
+	/**
+	 * Add bloom to the high dynamic range texture in place.
+	 */
+	pub fn render(
+		&self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		hdr_texture_view: &wgpu::TextureView,
+	) {
+		let aspect_ratio = self.hdr_texture_size[0] as f32 / self.hdr_texture_size[1] as f32;
+		let push_constant_data = PushConstantData {
+			source_texel_size: [0.0; 2],
+			filter_radius: [
+				self.bloom_configuration.radius,
+				self.bloom_configuration.radius * aspect_ratio,
+			],
+			threshold: self.bloom_configuration.threshold,
+			is_first_downsample: 0,
+		};
+
+		// Blur the texture by downsampling it through the mip chain.
+		for mip_level in 0..self.mip_texture_views.len() {
+			let (source_bind_group, source_size) = if mip_level == 0 {
+				(&self.hdr_texture_bind_group, self.hdr_texture_size)
+			} else {
+				(
+					&self.mip_bind_groups[mip_level - 1],
+					self.mip_sizes[mip_level - 1],
+				)
+			};
+			self.run_pass(
+				command_encoder,
+				&self.downsample_pipeline,
+				source_bind_group,
+				&self.mip_texture_views[mip_level],
+				wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+				PushConstantData {
+					source_texel_size: [1.0 / source_size[0] as f32, 1.0 / source_size[1] as f32],
+					is_first_downsample: (mip_level == 0) as u32,
+					..push_constant_data
+				},
+			);
+		}
+
+		// Upsample back through the mip chain, accumulating the blur of each level into the level above it.
+		for mip_level in (1..self.mip_texture_views.len()).rev() {
+			self.run_pass(
+				command_encoder,
+				&self.upsample_pipeline,
+				&self.mip_bind_groups[mip_level],
+				&self.mip_texture_views[mip_level - 1],
+				wgpu::LoadOp::Load,
+				push_constant_data,
+			);
+		}
+
+		// Add the accumulated blur on top of the original texture.
+		self.run_pass(
+			command_encoder,
+			&self.composite_pipeline,
+			&self.mip_bind_groups[0],
+			hdr_texture_view,
+			wgpu::LoadOp::Load,
+			push_constant_data,
+		);
+	}
+}
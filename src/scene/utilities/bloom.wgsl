@@ -0,0 +1,94 @@
+struct PushConstantData {
+	source_texel_size: vec2<f32>,
+	filter_radius: vec2<f32>,
+	threshold: f32,
+	is_first_downsample: u32,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var source_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+
+struct FragmentOutput {
+	@location(0) color: vec4<f32>,
+};
+
+fn sample_source(texture_coordinates: vec2<f32>, texel_offset: vec2<f32>) -> vec3<f32> {
+	return textureSample(source_texture, source_sampler, texture_coordinates + texel_offset * push_constant_data.source_texel_size).rgb;
+}
+
+fn luminance(color: vec3<f32>) -> f32 {
+	return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+// Weight a group of samples by its inverse luminance so that single very bright pixels don't turn into flickering
+// blobs in the first downsample.
+fn karis_average(color: vec3<f32>) -> vec3<f32> {
+	return color / (1.0 + luminance(color));
+}
+
+// Only keep the part of the color above the threshold, with a soft knee so that the cutoff isn't noticeable.
+fn apply_threshold(color: vec3<f32>) -> vec3<f32> {
+	let threshold = push_constant_data.threshold;
+	let knee = 0.5 * threshold;
+	let brightness = max(color.r, max(color.g, color.b));
+	var soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+	soft = soft * soft / (4.0 * knee + 0.00001);
+	let contribution = max(soft, brightness - threshold) / max(brightness, 0.00001);
+	return color * contribution;
+}
+
+// Downsample with the 13 tap filter from Call of Duty: Advanced Warfare.
+@fragment
+fn downsample_stage(input: FragmentInput) -> FragmentOutput {
+	let uv = input.texture_coordinates;
+	let a = sample_source(uv, vec2<f32>(-2.0, 2.0));
+	let b = sample_source(uv, vec2<f32>(0.0, 2.0));
+	let c = sample_source(uv, vec2<f32>(2.0, 2.0));
+	let d = sample_source(uv, vec2<f32>(-2.0, 0.0));
+	let e = sample_source(uv, vec2<f32>(0.0, 0.0));
+	let f = sample_source(uv, vec2<f32>(2.0, 0.0));
+	let g = sample_source(uv, vec2<f32>(-2.0, -2.0));
+	let h = sample_source(uv, vec2<f32>(0.0, -2.0));
+	let i = sample_source(uv, vec2<f32>(2.0, -2.0));
+	let j = sample_source(uv, vec2<f32>(-1.0, 1.0));
+	let k = sample_source(uv, vec2<f32>(1.0, 1.0));
+	let l = sample_source(uv, vec2<f32>(-1.0, -1.0));
+	let m = sample_source(uv, vec2<f32>(1.0, -1.0));
+
+	var color: vec3<f32>;
+	if (push_constant_data.is_first_downsample != 0u) {
+		color = karis_average((j + k + l + m) * 0.25) * 0.5;
+		color += karis_average((a + b + d + e) * 0.25) * 0.125;
+		color += karis_average((b + c + e + f) * 0.25) * 0.125;
+		color += karis_average((d + e + g + h) * 0.25) * 0.125;
+		color += karis_average((e + f + h + i) * 0.25) * 0.125;
+		color = apply_threshold(color);
+	} else {
+		color = e * 0.125;
+		color += (a + c + g + i) * 0.03125;
+		color += (b + d + f + h) * 0.0625;
+		color += (j + k + l + m) * 0.125;
+	}
+	return FragmentOutput(vec4<f32>(max(color, vec3<f32>(0.0001)), 1.0));
+}
+
+// Upsample with a 3x3 tent filter whose radius is given in texture coordinates.
+@fragment
+fn upsample_stage(input: FragmentInput) -> FragmentOutput {
+	let uv = input.texture_coordinates;
+	let radius = push_constant_data.filter_radius;
+	var color = textureSample(source_texture, source_sampler, uv).rgb * 4.0;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(0.0, radius.y)).rgb * 2.0;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(-radius.x, 0.0)).rgb * 2.0;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(radius.x, 0.0)).rgb * 2.0;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(0.0, -radius.y)).rgb * 2.0;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(-radius.x, radius.y)).rgb;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(radius.x, radius.y)).rgb;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(-radius.x, -radius.y)).rgb;
+	color += textureSample(source_texture, source_sampler, uv + vec2<f32>(radius.x, -radius.y)).rgb;
+	return FragmentOutput(vec4<f32>(color / 16.0, 1.0));
+}
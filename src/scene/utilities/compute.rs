@@ -0,0 +1,155 @@
+use wgpu::util::DeviceExt;
+
+/**
+ * Create a pipeline that runs the given entry point of a compute shader.
+ */
+pub fn create_compute_pipeline(
+	device: &wgpu::Device,
+	label: &str,
+	shader_module: &wgpu::ShaderModule,
+	entry_point: &str,
+	pipeline_layout: &wgpu::PipelineLayout,
+) -> wgpu::ComputePipeline {
+	device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+		label: Some(label),
+		layout: Some(pipeline_layout),
+		module: shader_module,
+		entry_point,
+	})
+}
+
+/**
+ * Get the number of workgroups that have to be dispatched so that every one of the invocations gets run, where the
+ * workgroup size has to match the one declared in the shader.
+ */
+pub fn workgroup_count(invocation_count: u32, workgroup_size: u32) -> u32 {
+	invocation_count.div_ceil(workgroup_size)
+}
+
+/**
+ * A pair of storage buffers that a compute shader alternates between, reading the previous state from one while
+ * writing the next state into the other. This avoids invocations reading state that other invocations have already
+ * overwritten during the same step.
+ */
+pub struct PingPongBuffers {
+	buffers: [wgpu::Buffer; 2],
+	bind_groups: [wgpu::BindGroup; 2],
+	bind_group_layout: wgpu::BindGroupLayout,
+	/**
+	 * The index of the buffer holding the latest state.
+	 */
+	current_index: usize,
+}
+
+impl PingPongBuffers {
+	/**
+	 * Create both buffers filled with the initial contents. Besides being used as storage, the buffers can be used in
+	 * the ways given by the extra usages, such as being vertex buffers for instanced rendering.
+	 */
+	pub fn new(
+		device: &wgpu::Device,
+		label: &str,
+		contents: &[u8],
+		extra_usages: wgpu::BufferUsages,
+	) -> Self {
+		let buffers = [0, 1].map(|buffer_index| {
+			device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+				label: Some(&format!("{} buffer {}", label, buffer_index)),
+				contents,
+				usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | extra_usages,
+			})
+		});
+		let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some(&(label.to_owned() + " bind group layout")),
+			entries: &[
+				wgpu::BindGroupLayoutEntry {
+					binding: 0,
+					visibility: wgpu::ShaderStages::COMPUTE,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Storage { read_only: true },
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 1,
+					visibility: wgpu::ShaderStages::COMPUTE,
+					ty: wgpu::BindingType::Buffer {
+						ty: wgpu::BufferBindingType::Storage { read_only: false },
+						has_dynamic_offset: false,
+						min_binding_size: None,
+					},
+					count: None,
+				},
+			],
+		});
+		// The bind group at an index reads from the buffer at that index and writes into the other one.
+		let bind_groups = [0, 1].map(|source_index| {
+			device.create_bind_group(&wgpu::BindGroupDescriptor {
+				label: Some(&format!("{} bind group {}", label, source_index)),
+				layout: &bind_group_layout,
+				entries: &[
+					wgpu::BindGroupEntry {
+						binding: 0,
+						resource: buffers[source_index].as_entire_binding(),
+					},
+					wgpu::BindGroupEntry {
+						binding: 1,
+						resource: buffers[1 - source_index].as_entire_binding(),
+					},
+				],
+			})
+		});
+		Self {
+			buffers,
+			bind_groups,
+			bind_group_layout,
+			current_index: 0,
+		}
+	}
+
+	/**
+	 * Get the layout of the bind groups, where binding 0 is the read-only buffer holding the latest state and binding 1
+	 * is the buffer that the next state gets written into.
+	 */
+	pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+		&self.bind_group_layout
+	}
+
+	/**
+	 * Get the bind group for computing the next state from the latest one.
+	 */
+	pub fn bind_group(&self) -> &wgpu::BindGroup {
+		&self.bind_groups[self.current_index]
+	}
+
+	/**
+	 * Get both buffers, which is useful for making bind groups that read either of them.
+	 */
+	pub fn buffers(&self) -> &[wgpu::Buffer; 2] {
+		&self.buffers
+	}
+
+	/**
+	 * Get the index of the buffer holding the latest state.
+	 */
+	pub fn current_index(&self) -> usize {
+		self.current_index
+	}
+
+	/**
+	 * Get the buffer holding the latest state.
+	 */
+	pub fn current_buffer(&self) -> &wgpu::Buffer {
+		&self.buffers[self.current_index]
+	}
+
+	/**
+	 * Make the buffer that the last step wrote into hold the latest state. This should be called after every step
+	 * that used the bind group.
+	 */
+	pub fn swap(&mut self) {
+		self.current_index = 1 - self.current_index;
+	}
+}
@@ -0,0 +1,97 @@
+/**
+ * Create a shader module from the source of a fragment shader that gets run over the whole render target.
+ * The source is prepended with a `vertex_stage` entry point that draws a single triangle covering the render target
+ * and with the `FragmentInput` struct that it outputs, which holds texture coordinates running from (0, 0) in the top
+ * left corner to (1, 1) in the bottom right corner.
+ */
+pub fn create_fullscreen_shader_module(
+	device: &wgpu::Device,
+	label: &str,
+	fragment_source: &str,
+) -> wgpu::ShaderModule {
+	device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+		label: Some(label),
+		source: wgpu::ShaderSource::Wgsl(
+			(include_str!("fullscreen_triangle.wgsl").to_owned() + fragment_source).into(),
+		),
+	})
+}
+
+/**
+ * Create a pipeline that draws a fullscreen triangle with a shader module from create_fullscreen_shader_module.
+ * Render passes using it should draw three vertices without any vertex buffers.
+ */
+pub fn create_fullscreen_pipeline(
+	device: &wgpu::Device,
+	label: &str,
+	shader_module: &wgpu::ShaderModule,
+	fragment_entry_point: &str,
+	pipeline_layout: &wgpu::PipelineLayout,
+	target: wgpu::ColorTargetState,
+) -> wgpu::RenderPipeline {
+	create_pipeline(
+		device,
+		label,
+		shader_module,
+		shader_module,
+		fragment_entry_point,
+		pipeline_layout,
+		target,
+	)
+}
+
+/**
+ * Create a pipeline that draws a fullscreen triangle like create_fullscreen_pipeline, but with a fragment stage from a
+ * module of its own, such as one that wasn't written in WGSL.
+ */
+pub fn create_fullscreen_pipeline_with_fragment_module(
+	device: &wgpu::Device,
+	label: &str,
+	fragment_shader_module: &wgpu::ShaderModule,
+	fragment_entry_point: &str,
+	pipeline_layout: &wgpu::PipelineLayout,
+	target: wgpu::ColorTargetState,
+) -> wgpu::RenderPipeline {
+	let vertex_shader_module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+		label: Some(label),
+		source: wgpu::ShaderSource::Wgsl(include_str!("fullscreen_triangle.wgsl").into()),
+	});
+	create_pipeline(
+		device,
+		label,
+		&vertex_shader_module,
+		fragment_shader_module,
+		fragment_entry_point,
+		pipeline_layout,
+		target,
+	)
+}
+
+fn create_pipeline(
+	device: &wgpu::Device,
+	label: &str,
+	vertex_shader_module: &wgpu::ShaderModule,
+	fragment_shader_module: &wgpu::ShaderModule,
+	fragment_entry_point: &str,
+	pipeline_layout: &wgpu::PipelineLayout,
+	target: wgpu::ColorTargetState,
+) -> wgpu::RenderPipeline {
+	device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+		label: Some(label),
+		layout: Some(pipeline_layout),
+		vertex: wgpu::VertexState {
+			module: vertex_shader_module,
+			entry_point: "vertex_stage",
+			buffers: &[],
+		},
+		fragment: Some(wgpu::FragmentState {
+			module: fragment_shader_module,
+			entry_point: fragment_entry_point,
+			targets: &[target],
+		}),
+		primitive: wgpu::PrimitiveState::default(),
+		depth_stencil: None,
+		multisample: wgpu::MultisampleState::default(),
+		multiview: None,
+	})
+}
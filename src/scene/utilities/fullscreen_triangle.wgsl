@@ -0,0 +1,13 @@
+struct FragmentInput {
+	@builtin(position) position: vec4<f32>,
+	@location(0) texture_coordinates: vec2<f32>,
+};
+
+@vertex
+fn vertex_stage(@builtin(vertex_index) vertex_index: u32) -> FragmentInput {
+	let texture_coordinates = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+	return FragmentInput(
+		vec4<f32>(texture_coordinates * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0),
+		texture_coordinates,
+	);
+}
@@ -0,0 +1,126 @@
+/**
+ * Get the number of mip levels in a full mip chain for a texture of the given size, which ends with a level that is a
+ * single pixel across.
+ */
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+	32 - width.max(height).max(1).leading_zeros()
+}
+
+/**
+ * Fill in every mip level of a texture after the first by repeatedly blitting each level into the next one at half
+ * the size. The texture has to be usable as both a render attachment and a filterable texture binding, and its first
+ * mip level has to already hold the image.
+ */
+pub fn generate_mipmaps(
+	device: &wgpu::Device,
+	queue: &wgpu::Queue,
+	texture: &wgpu::Texture,
+	format: wgpu::TextureFormat,
+	mip_level_count: u32,
+	label: &str,
+) {
+	if mip_level_count <= 1 {
+		return;
+	}
+
+	let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+		label: Some(&(label.to_owned() + " mipmap bind group layout")),
+		entries: &[
+			wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Texture {
+					sample_type: wgpu::TextureSampleType::Float { filterable: true },
+					view_dimension: wgpu::TextureViewDimension::D2,
+					multisampled: false,
+				},
+				count: None,
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding: 1,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+				count: None,
+			},
+		],
+	});
+	let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+		label: Some(&(label.to_owned() + " mipmap pipeline layout")),
+		bind_group_layouts: &[&bind_group_layout],
+		push_constant_ranges: &[],
+	});
+	let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+		device,
+		&(label.to_owned() + " mipmap shader"),
+		include_str!("mipmap.wgsl"),
+	);
+	let pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+		device,
+		&(label.to_owned() + " mipmap pipeline"),
+		&shader_module,
+		"fragment_stage",
+		&pipeline_layout,
+		wgpu::ColorTargetState {
+			format,
+			blend: None,
+			write_mask: wgpu::ColorWrites::all(),
+		},
+	);
+	let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+		label: Some(&(label.to_owned() + " mipmap sampler")),
+		address_mode_u: wgpu::AddressMode::ClampToEdge,
+		address_mode_v: wgpu::AddressMode::ClampToEdge,
+		address_mode_w: wgpu::AddressMode::ClampToEdge,
+		mag_filter: wgpu::FilterMode::Linear,
+		min_filter: wgpu::FilterMode::Linear,
+		mipmap_filter: wgpu::FilterMode::Nearest,
+		..wgpu::SamplerDescriptor::default()
+	});
+
+	let mip_level_views = (0..mip_level_count)
+		.map(|mip_level| {
+			texture.create_view(&wgpu::TextureViewDescriptor {
+				label: Some(&(label.to_owned() + " mip level view")),
+				base_mip_level: mip_level,
+				mip_level_count: std::num::NonZeroU32::new(1),
+				..wgpu::TextureViewDescriptor::default()
+			})
+		})
+		.collect::<Vec<_>>();
+	let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+		label: Some(&(label.to_owned() + " mipmap command encoder")),
+	});
+	let render_pass_label = label.to_owned() + " mipmap render pass";
+	for mip_level_view_pair in mip_level_views.windows(2) {
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some(&(label.to_owned() + " mipmap bind group")),
+			layout: &bind_group_layout,
+			entries: &[
+				wgpu::BindGroupEntry {
+					binding: 0,
+					resource: wgpu::BindingResource::TextureView(&mip_level_view_pair[0]),
+				},
+				wgpu::BindGroupEntry {
+					binding: 1,
+					resource: wgpu::BindingResource::Sampler(&sampler),
+				},
+			],
+		});
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some(&render_pass_label),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: &mip_level_view_pair[1],
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&pipeline);
+		render_pass.set_bind_group(0, &bind_group, &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+	queue.submit(std::iter::once(command_encoder.finish()));
+}
@@ -0,0 +1,11 @@
+@group(0) @binding(0)
+var source_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+
+// Each pixel of a mip level lands between four pixels of the level above it, so a single bilinear sample averages
+// them.
+@fragment
+fn fragment_stage(input: FragmentInput) -> @location(0) vec4<f32> {
+	return textureSample(source_texture, source_sampler, input.texture_coordinates);
+}
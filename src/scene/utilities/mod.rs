@@ -1,2 +1,11 @@
+pub mod audio;
+pub mod bloom;
 pub mod camera;
+pub mod compute;
+pub mod fullscreen;
+pub mod mipmap;
+pub mod post_processing;
+pub mod render_target;
+pub mod text;
 pub mod texture;
+pub mod tonemapping;
@@ -0,0 +1,11 @@
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	let strength = push_constant_data.parameters.x;
+	let texture_coordinates = input.texture_coordinates;
+	// Red and blue are split apart more towards the edges of the screen, like with a real lens.
+	let offset = (texture_coordinates - 0.5) * strength;
+	let color = textureSample(input_texture, input_sampler, texture_coordinates);
+	let red = textureSample(input_texture, input_sampler, texture_coordinates + offset).r;
+	let blue = textureSample(input_texture, input_sampler, texture_coordinates - offset).b;
+	return FragmentOutput(vec4<f32>(red, color.g, blue, color.a));
+}
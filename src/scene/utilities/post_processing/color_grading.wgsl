@@ -0,0 +1,15 @@
+@group(1) @binding(0)
+var lut_texture: texture_3d<f32>;
+@group(1) @binding(1)
+var lut_sampler: sampler;
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	let intensity = push_constant_data.parameters.x;
+	let lut_size = push_constant_data.parameters.y;
+	let color = textureSample(input_texture, input_sampler, input.texture_coordinates);
+	// Sample the centers of the outermost texels of the lookup table for colors at the edges of the range.
+	let lut_coordinates = clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0)) * ((lut_size - 1.0) / lut_size) + 0.5 / lut_size;
+	let graded_color = textureSample(lut_texture, lut_sampler, lut_coordinates).rgb;
+	return FragmentOutput(vec4<f32>(mix(color.rgb, graded_color, intensity), color.a));
+}
@@ -0,0 +1,17 @@
+struct PushConstantData {
+	parameters: vec4<f32>,
+	resolution: vec2<f32>,
+	time: f32,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var input_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var input_sampler: sampler;
+
+struct FragmentOutput {
+	@location(0) color: vec4<f32>,
+};
+
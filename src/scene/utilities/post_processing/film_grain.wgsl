@@ -0,0 +1,19 @@
+fn hash(position: vec3<f32>) -> f32 {
+	var p = fract(position * 0.1031);
+	p += vec3<f32>(dot(p, p.zyx + 31.32));
+	return fract((p.x + p.y) * p.z);
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	let intensity = push_constant_data.parameters.x;
+	let grain_size = max(push_constant_data.parameters.y, 1.0);
+	let frames_per_second = push_constant_data.parameters.z;
+	let color = textureSample(input_texture, input_sampler, input.texture_coordinates);
+	let grain_position = floor(input.position.xy / grain_size);
+	let noise = hash(vec3<f32>(grain_position, floor(push_constant_data.time * frames_per_second))) - 0.5;
+	// Grain is most noticeable in the midtones, so fade it out towards black and white.
+	let luminance = dot(color.rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+	let midtone_weight = 1.0 - pow(2.0 * luminance - 1.0, 2.0);
+	return FragmentOutput(vec4<f32>(color.rgb + noise * intensity * midtone_weight, color.a));
+}
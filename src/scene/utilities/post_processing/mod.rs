@@ -0,0 +1,503 @@
+use crate::config::PostProcessPassConfig;
+
+/**
+ * The format of the textures that post-processing passes read from and write to. The colors in them are already
+ * tonemapped and encoded for display, which is what effects like color grading lookup tables expect.
+ */
+pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	parameters: [f32; 4],
+	resolution: [f32; 2],
+	time: f32,
+	_padding: u32,
+}
+
+struct PostProcessPass {
+	label: &'static str,
+	render_pipeline: wgpu::RenderPipeline,
+	parameters: [f32; 4],
+	lookup_table_bind_group: Option<wgpu::BindGroup>,
+}
+
+/**
+ * Applies an ordered list of fullscreen effects to a frame after it has been tonemapped, before presenting it.
+ * Each pass reads the output of the previous one, so the chain ping-pongs between two textures the size of the
+ * surface.
+ */
+pub struct PostProcessChain {
+	passes: Vec<PostProcessPass>,
+	input_bind_group_layout: wgpu::BindGroupLayout,
+	ping_pong_textures: [crate::scene::utilities::texture::Texture; 2],
+	ping_pong_bind_groups: [wgpu::BindGroup; 2],
+	present_render_pipeline: wgpu::RenderPipeline,
+	decode_srgb: bool,
+	resolution: [f32; 2],
+	time: f32,
+}
+
+impl PostProcessChain {
+	pub fn new(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		pass_configurations: &[PostProcessPassConfig],
+	) -> Self {
+		let ping_pong_textures = Self::create_ping_pong_textures(device, surface_configuration);
+		let input_bind_group_layout = ping_pong_textures[0].create_bind_group_layout(
+			device,
+			"Post-processing input",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let ping_pong_bind_groups = Self::create_ping_pong_bind_groups(
+			device,
+			&ping_pong_textures,
+			&input_bind_group_layout,
+		);
+
+		let passes = pass_configurations
+			.iter()
+			.filter_map(|pass_configuration| {
+				Self::create_pass(device, queue, &input_bind_group_layout, pass_configuration)
+			})
+			.collect::<Vec<_>>();
+		let present_render_pipeline = Self::create_pipeline(
+			device,
+			"Post-processing present",
+			include_str!("present.wgsl"),
+			&[&input_bind_group_layout],
+			surface_configuration.format,
+		);
+
+		Self {
+			passes,
+			input_bind_group_layout,
+			ping_pong_textures,
+			ping_pong_bind_groups,
+			present_render_pipeline,
+			// The passes work with display encoded colors, so they have to be decoded when presenting to a surface that
+			// encodes the written colors itself.
+			decode_srgb: surface_configuration.format.describe().srgb,
+			resolution: [
+				surface_configuration.width as f32,
+				surface_configuration.height as f32,
+			],
+			time: 0.0,
+		}
+	}
+
+	fn create_ping_pong_textures(
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+	) -> [crate::scene::utilities::texture::Texture; 2] {
+		[
+			crate::scene::utilities::texture::Texture::create_render_target(
+				device,
+				FORMAT,
+				surface_configuration.width,
+				surface_configuration.height,
+				"Post-processing ping",
+			),
+			crate::scene::utilities::texture::Texture::create_render_target(
+				device,
+				FORMAT,
+				surface_configuration.width,
+				surface_configuration.height,
+				"Post-processing pong",
+			),
+		]
+	}
+
+	fn create_ping_pong_bind_groups(
+		device: &wgpu::Device,
+		ping_pong_textures: &[crate::scene::utilities::texture::Texture; 2],
+		input_bind_group_layout: &wgpu::BindGroupLayout,
+	) -> [wgpu::BindGroup; 2] {
+		[
+			ping_pong_textures[0].create_bind_group_with_layout(
+				device,
+				input_bind_group_layout,
+				"Post-processing ping",
+			),
+			ping_pong_textures[1].create_bind_group_with_layout(
+				device,
+				input_bind_group_layout,
+				"Post-processing pong",
+			),
+		]
+	}
+
+	/**
+	 * Create a fullscreen pipeline for a pass whose shader source only contains its fragment stage. The push constants
+	 * and input texture bindings that all passes share are declared for it.
+	 */
+	fn create_pipeline(
+		device: &wgpu::Device,
+		label: &str,
+		fragment_source: &str,
+		bind_group_layouts: &[&wgpu::BindGroupLayout],
+		format: wgpu::TextureFormat,
+	) -> wgpu::RenderPipeline {
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			&(label.to_owned() + " shader"),
+			&(include_str!("common.wgsl").to_owned() + fragment_source),
+		);
+		let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label: Some(&(label.to_owned() + " pipeline layout")),
+			bind_group_layouts,
+			push_constant_ranges: &[wgpu::PushConstantRange {
+				stages: wgpu::ShaderStages::FRAGMENT,
+				range: 0..std::mem::size_of::<PushConstantData>() as u32,
+			}],
+		});
+		crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			&(label.to_owned() + " pipeline"),
+			&shader_module,
+			"fragment_stage",
+			&pipeline_layout,
+			wgpu::ColorTargetState {
+				format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		)
+	}
+
+	/**
+	 * Create the pass described by the given configuration. If the pass can't be created, a warning is logged and the
+	 * pass is left out of the chain.
+	 */
+	fn create_pass(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		input_bind_group_layout: &wgpu::BindGroupLayout,
+		pass_configuration: &PostProcessPassConfig,
+	) -> Option<PostProcessPass> {
+		let (label, fragment_source, parameters, lookup_table) = match pass_configuration {
+			PostProcessPassConfig::Vignette(vignette_configuration) => (
+				"Vignette",
+				include_str!("vignette.wgsl"),
+				[
+					vignette_configuration.intensity,
+					vignette_configuration.radius,
+					vignette_configuration.softness,
+					0.0,
+				],
+				None,
+			),
+			PostProcessPassConfig::ColorGrading(color_grading_configuration) => {
+				let lookup_table =
+					match load_cube_lookup_table(&color_grading_configuration.lookup_table) {
+						Ok(lookup_table) => lookup_table,
+						Err(error) => {
+							log::warn!(
+								"Skipping color grading with lookup table {}: {}",
+								color_grading_configuration.lookup_table.display(),
+								error
+							);
+							return None;
+						}
+					};
+				(
+					"Color grading",
+					include_str!("color_grading.wgsl"),
+					[
+						color_grading_configuration.intensity,
+						lookup_table.0 as f32,
+						0.0,
+						0.0,
+					],
+					Some(lookup_table),
+				)
+			}
+			PostProcessPassConfig::FilmGrain(film_grain_configuration) => (
+				"Film grain",
+				include_str!("film_grain.wgsl"),
+				[
+					film_grain_configuration.intensity,
+					film_grain_configuration.grain_size,
+					film_grain_configuration.frames_per_second,
+					0.0,
+				],
+				None,
+			),
+			PostProcessPassConfig::ChromaticAberration(chromatic_aberration_configuration) => (
+				"Chromatic aberration",
+				include_str!("chromatic_aberration.wgsl"),
+				[chromatic_aberration_configuration.strength, 0.0, 0.0, 0.0],
+				None,
+			),
+		};
+
+		let (render_pipeline, lookup_table_bind_group) = match lookup_table {
+			Some((size, data)) => {
+				let lookup_table_texture = create_lookup_table_texture(device, queue, size, &data);
+				let (lookup_table_bind_group_layout, lookup_table_bind_group) =
+					lookup_table_texture.create_bind_group(
+						device,
+						"Color grading lookup table",
+						wgpu::ShaderStages::FRAGMENT,
+					);
+				let render_pipeline = Self::create_pipeline(
+					device,
+					label,
+					fragment_source,
+					&[input_bind_group_layout, &lookup_table_bind_group_layout],
+					FORMAT,
+				);
+				(render_pipeline, Some(lookup_table_bind_group))
+			}
+			None => {
+				let render_pipeline = Self::create_pipeline(
+					device,
+					label,
+					fragment_source,
+					&[input_bind_group_layout],
+					FORMAT,
+				);
+				(render_pipeline, None)
+			}
+		};
+		Some(PostProcessPass {
+			label,
+			render_pipeline,
+			parameters,
+			lookup_table_bind_group,
+		})
+	}
+
+	/**
+	 * Get the view of the texture that the first pass reads from, which the tonemapped frame should be rendered into.
+	 */
+	pub fn input_texture_view(&self) -> &wgpu::TextureView {
+		&self.ping_pong_textures[0].texture_view
+	}
+
+	/**
+	 * Recreate the ping-pong textures so that they match the size of the surface.
+	 */
+	pub fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+	) {
+		self.ping_pong_textures = Self::create_ping_pong_textures(device, surface_configuration);
+		self.ping_pong_bind_groups = Self::create_ping_pong_bind_groups(
+			device,
+			&self.ping_pong_textures,
+			&self.input_bind_group_layout,
+		);
+		self.resolution = [
+			surface_configuration.width as f32,
+			surface_configuration.height as f32,
+		];
+	}
+
+	/**
+	 * Advance the time that animated effects like film grain use.
+	 */
+	pub fn update(&mut self, dt: f32) {
+		self.time += dt;
+	}
+
+	/**
+	 * Run all passes over the input texture and present the result into the output texture view.
+	 */
+	pub fn render(
+		&self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		for (pass_index, pass) in self.passes.iter().enumerate() {
+			let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: Some(pass.label),
+				color_attachments: &[wgpu::RenderPassColorAttachment {
+					view: &self.ping_pong_textures[(pass_index + 1) % 2].texture_view,
+					resolve_target: None,
+					ops: wgpu::Operations {
+						load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+						store: true,
+					},
+				}],
+				depth_stencil_attachment: None,
+			});
+			render_pass.set_pipeline(&pass.render_pipeline);
+			render_pass.set_push_constants(
+				wgpu::ShaderStages::FRAGMENT,
+				0,
+				bytemuck::bytes_of(&PushConstantData {
+					parameters: pass.parameters,
+					resolution: self.resolution,
+					time: self.time,
+					_padding: 0,
+				}),
+			);
+			render_pass.set_bind_group(0, &self.ping_pong_bind_groups[pass_index % 2], &[]);
+			if let Some(lookup_table_bind_group) = &pass.lookup_table_bind_group {
+				render_pass.set_bind_group(1, lookup_table_bind_group, &[]);
+			}
+			render_pass.draw(0..3, 0..1);
+		}
+
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Post-processing present render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.present_render_pipeline);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&PushConstantData {
+				parameters: [self.decode_srgb as u32 as f32, 0.0, 0.0, 0.0],
+				resolution: self.resolution,
+				time: self.time,
+				_padding: 0,
+			}),
+		);
+		render_pass.set_bind_group(0, &self.ping_pong_bind_groups[self.passes.len() % 2], &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+}
+
+/**
+ * Load a 3D color lookup table from a .cube file, which is the format that most color grading tools export.
+ * The size of the lookup table along each axis is returned along with its texels in RGBA order.
+ */
+fn load_cube_lookup_table(path: &std::path::Path) -> Result<(u32, Vec<u8>), String> {
+	let contents = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+	let mut size = None;
+	let mut domain_minimum = [0.0f32; 3];
+	let mut domain_maximum = [1.0f32; 3];
+	let mut entries = Vec::new();
+	for line in contents.lines() {
+		let words = line.split_whitespace().collect::<Vec<_>>();
+		let parse_triple = |words: &[&str]| -> Result<[f32; 3], String> {
+			match words {
+				[x, y, z] => Ok([
+					x.parse().map_err(|_| format!("invalid number {}", x))?,
+					y.parse().map_err(|_| format!("invalid number {}", y))?,
+					z.parse().map_err(|_| format!("invalid number {}", z))?,
+				]),
+				_ => Err(format!("expected three numbers in line {:?}", line)),
+			}
+		};
+		match words.as_slice() {
+			[] => (),
+			[first, ..] if first.starts_with('#') => (),
+			["LUT_3D_SIZE", lookup_table_size] => {
+				size = Some(
+					lookup_table_size
+						.parse::<u32>()
+						.map_err(|_| format!("invalid size {}", lookup_table_size))?,
+				)
+			}
+			["LUT_1D_SIZE", ..] => return Err("1D lookup tables are not supported".to_owned()),
+			["DOMAIN_MIN", rest @ ..] => domain_minimum = parse_triple(rest)?,
+			["DOMAIN_MAX", rest @ ..] => domain_maximum = parse_triple(rest)?,
+			[first, ..] if first.parse::<f32>().is_ok() => entries.push(parse_triple(&words)?),
+			// Keywords like TITLE don't affect the lookup table.
+			_ => (),
+		}
+	}
+
+	let size = size.ok_or("missing LUT_3D_SIZE")?;
+	if size < 2 || entries.len() != (size * size * size) as usize {
+		return Err(format!(
+			"expected {} entries for a size of {}, but found {}",
+			size * size * size,
+			size,
+			entries.len()
+		));
+	}
+	// Entries are scaled by the width of the domain, so a domain without any width can't be used.
+	if (0..3).any(|channel| domain_maximum[channel] <= domain_minimum[channel]) {
+		return Err(format!(
+			"DOMAIN_MAX {:?} has to be above DOMAIN_MIN {:?} on every channel",
+			domain_maximum, domain_minimum
+		));
+	}
+	let data = entries
+		.iter()
+		.flat_map(|entry| {
+			let mut texel = [u8::MAX; 4];
+			for channel in 0..3 {
+				let normalized = (entry[channel] - domain_minimum[channel])
+					/ (domain_maximum[channel] - domain_minimum[channel]);
+				texel[channel] = (normalized.clamp(0.0, 1.0) * 255.0).round() as u8;
+			}
+			texel
+		})
+		.collect::<Vec<_>>();
+	Ok((size, data))
+}
+
+/**
+ * Upload a lookup table from load_cube_lookup_table into a 3D texture. The red channel of the input color varies
+ * fastest in the data, so it indexes the width of the texture.
+ */
+fn create_lookup_table_texture(
+	device: &wgpu::Device,
+	queue: &wgpu::Queue,
+	size: u32,
+	data: &[u8],
+) -> crate::scene::utilities::texture::Texture {
+	let texture_extent = wgpu::Extent3d {
+		width: size,
+		height: size,
+		depth_or_array_layers: size,
+	};
+	let texture = device.create_texture(&wgpu::TextureDescriptor {
+		label: Some("Color grading lookup table texture"),
+		size: texture_extent,
+		mip_level_count: 1,
+		sample_count: 1,
+		dimension: wgpu::TextureDimension::D3,
+		format: wgpu::TextureFormat::Rgba8Unorm,
+		usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+	});
+	queue.write_texture(
+		wgpu::ImageCopyTexture {
+			texture: &texture,
+			mip_level: 0,
+			origin: wgpu::Origin3d::ZERO,
+			aspect: wgpu::TextureAspect::All,
+		},
+		data,
+		wgpu::ImageDataLayout {
+			offset: 0,
+			bytes_per_row: std::num::NonZeroU32::new(4 * size),
+			rows_per_image: std::num::NonZeroU32::new(size),
+		},
+		texture_extent,
+	);
+	let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+	let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+		label: Some("Color grading lookup table sampler"),
+		address_mode_u: wgpu::AddressMode::ClampToEdge,
+		address_mode_v: wgpu::AddressMode::ClampToEdge,
+		address_mode_w: wgpu::AddressMode::ClampToEdge,
+		mag_filter: wgpu::FilterMode::Linear,
+		min_filter: wgpu::FilterMode::Linear,
+		..wgpu::SamplerDescriptor::default()
+	});
+	crate::scene::utilities::texture::Texture {
+		texture,
+		texture_view,
+		sampler,
+		sample_type: wgpu::TextureSampleType::Float { filterable: true },
+		view_dimension: wgpu::TextureViewDimension::D3,
+		sampler_binding_type: wgpu::SamplerBindingType::Filtering,
+	}
+}
@@ -0,0 +1,14 @@
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+	let lower = color / 12.92;
+	let higher = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+	return select(higher, lower, color <= vec3<f32>(0.04045));
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	let color = textureSample(input_texture, input_sampler, input.texture_coordinates);
+	if (push_constant_data.parameters.x != 0.0) {
+		return FragmentOutput(vec4<f32>(srgb_to_linear(color.rgb), color.a));
+	}
+	return FragmentOutput(color);
+}
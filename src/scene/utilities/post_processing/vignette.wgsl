@@ -0,0 +1,11 @@
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	let intensity = push_constant_data.parameters.x;
+	let radius = push_constant_data.parameters.y;
+	let softness = push_constant_data.parameters.z;
+	let color = textureSample(input_texture, input_sampler, input.texture_coordinates);
+	let aspect_ratio = push_constant_data.resolution.x / push_constant_data.resolution.y;
+	let distance_from_center = length((input.texture_coordinates - 0.5) * vec2<f32>(aspect_ratio, 1.0));
+	let vignette = 1.0 - smoothstep(radius - softness, radius, distance_from_center);
+	return FragmentOutput(vec4<f32>(color.rgb * mix(1.0, vignette, intensity), color.a));
+}
@@ -0,0 +1,120 @@
+/**
+ * Describe the texture that a scene renders into.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct RenderTargetConfiguration {
+	pub format: wgpu::TextureFormat,
+	pub width: u32,
+	pub height: u32,
+	pub sample_count: u32,
+}
+
+impl RenderTargetConfiguration {
+	/**
+	 * The sample counts that can be asked for, from fewest to most.
+	 */
+	const SUPPORTED_SAMPLE_COUNTS: [u32; 4] = [1, 2, 4, 8];
+
+	/**
+	 * Make a configuration for rendering into a texture of the given format with as many samples as possible without
+	 * going over the requested sample count. Whether the device can render to the format with a sample count can't be
+	 * asked, so a multisampled texture is made with each count in turn, stepping down until one is accepted. Support
+	 * doesn't depend on the size, so the textures are a single pixel rather than the size of the target.
+	 */
+	pub fn new(
+		device: &wgpu::Device,
+		format: wgpu::TextureFormat,
+		width: u32,
+		height: u32,
+		requested_sample_count: u32,
+	) -> Self {
+		let sample_count = Self::SUPPORTED_SAMPLE_COUNTS
+			.into_iter()
+			.rev()
+			.filter(|&sample_count| sample_count > 1 && sample_count <= requested_sample_count)
+			.find(|&sample_count| {
+				let probe_configuration = Self {
+					format,
+					width: 1,
+					height: 1,
+					sample_count,
+				};
+				device.push_error_scope(wgpu::ErrorFilter::Validation);
+				MultisampledColorTarget::new(device, &probe_configuration, "Sample count probe");
+				pollster::block_on(device.pop_error_scope()).is_none()
+			})
+			.unwrap_or(1);
+		if sample_count != requested_sample_count {
+			log::warn!(
+				"{} samples per pixel are not supported for {:?}, so {} will be used instead",
+				requested_sample_count,
+				format,
+				sample_count
+			);
+		}
+		Self {
+			format,
+			width,
+			height,
+			sample_count,
+		}
+	}
+
+	pub fn multisample_state(&self) -> wgpu::MultisampleState {
+		wgpu::MultisampleState {
+			count: self.sample_count,
+			..wgpu::MultisampleState::default()
+		}
+	}
+}
+
+/**
+ * A color texture that gets rendered into instead of the output texture when multisampling, so that it can be
+ * resolved into the output texture. When not multisampling, the output texture is rendered into directly.
+ */
+pub struct MultisampledColorTarget {
+	texture: Option<crate::scene::utilities::texture::Texture>,
+}
+
+impl MultisampledColorTarget {
+	pub fn new(
+		device: &wgpu::Device,
+		render_target_configuration: &RenderTargetConfiguration,
+		scene_name: &str,
+	) -> Self {
+		let texture = if render_target_configuration.sample_count > 1 {
+			Some(
+				crate::scene::utilities::texture::Texture::create_multisampled_color_texture(
+					device,
+					render_target_configuration,
+					scene_name,
+				),
+			)
+		} else {
+			None
+		};
+		Self { texture }
+	}
+
+	/**
+	 * Make the color attachment for a render pass whose results should end up in the output texture view.
+	 */
+	pub fn color_attachment<'a>(
+		&'a self,
+		output_texture_view: &'a wgpu::TextureView,
+		load: wgpu::LoadOp<wgpu::Color>,
+	) -> wgpu::RenderPassColorAttachment<'a> {
+		match &self.texture {
+			Some(texture) => wgpu::RenderPassColorAttachment {
+				view: &texture.texture_view,
+				resolve_target: Some(output_texture_view),
+				ops: wgpu::Operations { load, store: false },
+			},
+			None => wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations { load, store: true },
+			},
+		}
+	}
+}
@@ -0,0 +1,194 @@
+use ab_glyph::Font;
+
+/**
+ * The width and height of the atlas texture in pixels.
+ */
+pub const ATLAS_SIZE: u32 = 1024;
+
+/**
+ * The empty space left around every glyph in the atlas, which keeps neighboring glyphs from bleeding into each other
+ * when they are sampled.
+ */
+const GLYPH_PADDING: u32 = 1;
+
+/**
+ * Where a rasterized glyph is in the atlas and where it goes relative to its position on the baseline.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct AtlasGlyph {
+	pub atlas_position: [u32; 2],
+	pub size: [u32; 2],
+	pub offset: [f32; 2],
+}
+
+/**
+ * A rasterized glyph that still has to be written into the atlas texture.
+ */
+struct PendingUpload {
+	atlas_position: [u32; 2],
+	size: [u32; 2],
+	coverage: Vec<u8>,
+}
+
+/**
+ * A texture holding the coverage of every glyph that has been drawn so far, which are rasterized on the CPU the first
+ * time they are needed at each size. Glyphs are packed into rows from top to bottom.
+ */
+pub struct GlyphAtlas {
+	texture: crate::scene::utilities::texture::Texture,
+	/**
+	 * The glyphs that have been rasterized so far, by glyph ID and pixel size. Glyphs without any outline, such as
+	 * spaces, have no place in the atlas.
+	 */
+	glyphs: std::collections::HashMap<(ab_glyph::GlyphId, u32), Option<AtlasGlyph>>,
+	pending_uploads: Vec<PendingUpload>,
+	row_position: [u32; 2],
+	row_height: u32,
+	has_warned_about_being_full: bool,
+}
+
+impl GlyphAtlas {
+	pub fn new(device: &wgpu::Device, label: &str) -> Self {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(&(label.to_owned() + " glyph atlas texture")),
+			size: wgpu::Extent3d {
+				width: ATLAS_SIZE,
+				height: ATLAS_SIZE,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: wgpu::TextureFormat::R8Unorm,
+			usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		// Glyphs are drawn pixel for pixel, so there is no need to filter them.
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(&(label.to_owned() + " glyph atlas sampler")),
+			..wgpu::SamplerDescriptor::default()
+		});
+		Self {
+			texture: crate::scene::utilities::texture::Texture {
+				texture,
+				texture_view,
+				sampler,
+				sample_type: wgpu::TextureSampleType::Float { filterable: true },
+				view_dimension: wgpu::TextureViewDimension::D2,
+				sampler_binding_type: wgpu::SamplerBindingType::Filtering,
+			},
+			glyphs: std::collections::HashMap::new(),
+			pending_uploads: Vec::new(),
+			row_position: [0, 0],
+			row_height: 0,
+			has_warned_about_being_full: false,
+		}
+	}
+
+	pub fn texture(&self) -> &crate::scene::utilities::texture::Texture {
+		&self.texture
+	}
+
+	/**
+	 * Get where a glyph is in the atlas at the given pixel size, rasterizing it first if it hasn't been drawn at that
+	 * size before. Returns None for glyphs that have nothing to draw or that no longer fit into the atlas.
+	 */
+	pub fn glyph(
+		&mut self,
+		font: &ab_glyph::FontVec,
+		glyph_id: ab_glyph::GlyphId,
+		pixel_size: u32,
+	) -> Option<AtlasGlyph> {
+		if let Some(atlas_glyph) = self.glyphs.get(&(glyph_id, pixel_size)) {
+			return *atlas_glyph;
+		}
+		let atlas_glyph = self.rasterize(font, glyph_id, pixel_size);
+		self.glyphs.insert((glyph_id, pixel_size), atlas_glyph);
+		atlas_glyph
+	}
+
+	fn rasterize(
+		&mut self,
+		font: &ab_glyph::FontVec,
+		glyph_id: ab_glyph::GlyphId,
+		pixel_size: u32,
+	) -> Option<AtlasGlyph> {
+		let outlined_glyph = font.outline_glyph(glyph_id.with_scale(pixel_size as f32))?;
+		let bounds = outlined_glyph.px_bounds();
+		let size = [bounds.width() as u32, bounds.height() as u32];
+		if size[0] == 0 || size[1] == 0 {
+			return None;
+		}
+		let atlas_position = self.allocate(size)?;
+		let mut coverage = vec![0; (size[0] * size[1]) as usize];
+		outlined_glyph.draw(|x, y, glyph_coverage| {
+			if x < size[0] && y < size[1] {
+				coverage[(y * size[0] + x) as usize] =
+					(glyph_coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+			}
+		});
+		self.pending_uploads.push(PendingUpload {
+			atlas_position,
+			size,
+			coverage,
+		});
+		Some(AtlasGlyph {
+			atlas_position,
+			size,
+			offset: [bounds.min.x, bounds.min.y],
+		})
+	}
+
+	/**
+	 * Find a free space for a glyph of the given size, starting a new row when it doesn't fit into the current one.
+	 */
+	fn allocate(&mut self, size: [u32; 2]) -> Option<[u32; 2]> {
+		let padded_size = [size[0] + GLYPH_PADDING, size[1] + GLYPH_PADDING];
+		if self.row_position[0] + padded_size[0] > ATLAS_SIZE {
+			self.row_position = [0, self.row_position[1] + self.row_height];
+			self.row_height = 0;
+		}
+		if padded_size[0] > ATLAS_SIZE || self.row_position[1] + padded_size[1] > ATLAS_SIZE {
+			if !self.has_warned_about_being_full {
+				log::warn!("The glyph atlas is full, so some text will be missing characters");
+				self.has_warned_about_being_full = true;
+			}
+			return None;
+		}
+		let atlas_position = self.row_position;
+		self.row_position[0] += padded_size[0];
+		self.row_height = self.row_height.max(padded_size[1]);
+		Some(atlas_position)
+	}
+
+	/**
+	 * Write the glyphs that have been rasterized since the last call into the atlas texture.
+	 */
+	pub fn upload(&mut self, queue: &wgpu::Queue) {
+		for pending_upload in self.pending_uploads.drain(..) {
+			queue.write_texture(
+				wgpu::ImageCopyTexture {
+					texture: &self.texture.texture,
+					mip_level: 0,
+					origin: wgpu::Origin3d {
+						x: pending_upload.atlas_position[0],
+						y: pending_upload.atlas_position[1],
+						z: 0,
+					},
+					aspect: wgpu::TextureAspect::All,
+				},
+				&pending_upload.coverage,
+				wgpu::ImageDataLayout {
+					offset: 0,
+					bytes_per_row: std::num::NonZeroU32::new(pending_upload.size[0]),
+					rows_per_image: std::num::NonZeroU32::new(pending_upload.size[1]),
+				},
+				wgpu::Extent3d {
+					width: pending_upload.size[0],
+					height: pending_upload.size[1],
+					depth_or_array_layers: 1,
+				},
+			);
+		}
+	}
+}
@@ -0,0 +1,455 @@
+mod glyph_atlas;
+
+use ab_glyph::{Font, ScaleFont};
+
+/**
+ * The most glyphs that can be drawn in a single frame.
+ */
+const MAXIMUM_GLYPH_COUNT: usize = 4096;
+
+/**
+ * Fonts that are tried in order when no font is configured and fontconfig can't find one.
+ */
+const FALLBACK_FONT_PATHS: [&str; 4] = [
+	"/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+	"/usr/share/fonts/TTF/DejaVuSans.ttf",
+	"/usr/share/fonts/dejavu/DejaVuSans.ttf",
+	"/usr/share/fonts/liberation/LiberationSans-Regular.ttf",
+];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct GlyphInstance {
+	position: [f32; 2],
+	size: [f32; 2],
+	atlas_position: [f32; 2],
+	color: [f32; 4],
+}
+
+impl GlyphInstance {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+		0 => Float32x2,
+		1 => Float32x2,
+		2 => Float32x2,
+		3 => Float32x4,
+	];
+
+	fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Instance,
+			attributes: &Self::ATTRIBUTES,
+		}
+	}
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	resolution: [f32; 2],
+	atlas_size: [f32; 2],
+}
+
+/**
+ * Which point of a block of text is placed at its position. The anchor also decides how the lines in the block are
+ * aligned with each other, so text anchored on the right is right aligned.
+ */
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAnchor {
+	#[default]
+	TopLeft,
+	Top,
+	TopRight,
+	Left,
+	Center,
+	Right,
+	BottomLeft,
+	Bottom,
+	BottomRight,
+}
+
+impl TextAnchor {
+	/**
+	 * How far across and down the block of text its position is, from 0 to 1 on each axis.
+	 */
+	pub fn factors(self) -> [f32; 2] {
+		match self {
+			Self::TopLeft => [0.0, 0.0],
+			Self::Top => [0.5, 0.0],
+			Self::TopRight => [1.0, 0.0],
+			Self::Left => [0.0, 0.5],
+			Self::Center => [0.5, 0.5],
+			Self::Right => [1.0, 0.5],
+			Self::BottomLeft => [0.0, 1.0],
+			Self::Bottom => [0.5, 1.0],
+			Self::BottomRight => [1.0, 1.0],
+		}
+	}
+}
+
+/**
+ * How a string is drawn.
+ */
+#[derive(Clone, Debug)]
+pub struct TextOptions {
+	/**
+	 * Where the anchor of the text goes, in pixels from the top left corner of the screen.
+	 */
+	pub position: [f32; 2],
+	/**
+	 * The height of the font in pixels.
+	 */
+	pub size: f32,
+	/**
+	 * The color of the text, including how opaque it is.
+	 */
+	pub color: [f32; 4],
+	pub anchor: TextAnchor,
+	/**
+	 * How wide a line can get in pixels before it is wrapped at the last space. Lines are only broken at newlines when
+	 * this is None.
+	 */
+	pub maximum_width: Option<f32>,
+}
+
+impl Default for TextOptions {
+	fn default() -> Self {
+		Self {
+			position: [0.0, 0.0],
+			size: 16.0,
+			color: [1.0, 1.0, 1.0, 1.0],
+			anchor: TextAnchor::default(),
+			maximum_width: None,
+		}
+	}
+}
+
+/**
+ * A glyph placed on a line, at a distance from the start of the line in pixels.
+ */
+struct LaidOutGlyph {
+	glyph_id: ab_glyph::GlyphId,
+	x: f32,
+}
+
+struct LaidOutLine {
+	glyphs: Vec<LaidOutGlyph>,
+	width: f32,
+}
+
+/**
+ * Draws strings on top of whatever has already been rendered. Strings are queued up during a frame and then drawn all
+ * at once as instanced quads that copy their glyphs out of a glyph atlas.
+ */
+pub struct TextRenderer {
+	font: ab_glyph::FontVec,
+	glyph_atlas: glyph_atlas::GlyphAtlas,
+	atlas_bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+	instance_buffer: wgpu::Buffer,
+	glyph_instances: Vec<GlyphInstance>,
+	resolution: [f32; 2],
+	has_warned_about_glyph_count: bool,
+	label: String,
+}
+
+impl TextRenderer {
+	pub fn new(
+		device: &wgpu::Device,
+		format: wgpu::TextureFormat,
+		font: ab_glyph::FontVec,
+		label: &str,
+	) -> Self {
+		let glyph_atlas = glyph_atlas::GlyphAtlas::new(device, label);
+		let (atlas_bind_group_layout, atlas_bind_group) = glyph_atlas.texture().create_bind_group(
+			device,
+			&(label.to_owned() + " glyph atlas"),
+			wgpu::ShaderStages::FRAGMENT,
+		);
+
+		let shader_module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+			label: Some(&(label.to_owned() + " text shader")),
+			source: wgpu::ShaderSource::Wgsl(include_str!("text.wgsl").into()),
+		});
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some(&(label.to_owned() + " text pipeline layout")),
+				bind_group_layouts: &[&atlas_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::VERTEX,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some(&(label.to_owned() + " text pipeline")),
+			layout: Some(&render_pipeline_layout),
+			vertex: wgpu::VertexState {
+				module: &shader_module,
+				entry_point: "vertex_stage",
+				buffers: &[GlyphInstance::vertex_buffer_layout()],
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: &shader_module,
+				entry_point: "fragment_stage",
+				targets: &[wgpu::ColorTargetState {
+					format,
+					blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+					write_mask: wgpu::ColorWrites::all(),
+				}],
+			}),
+			primitive: wgpu::PrimitiveState {
+				topology: wgpu::PrimitiveTopology::TriangleStrip,
+				..wgpu::PrimitiveState::default()
+			},
+			depth_stencil: None,
+			multisample: wgpu::MultisampleState::default(),
+			multiview: None,
+		});
+		let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some(&(label.to_owned() + " glyph instance buffer")),
+			size: (MAXIMUM_GLYPH_COUNT * std::mem::size_of::<GlyphInstance>())
+				as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false,
+		});
+
+		Self {
+			font,
+			glyph_atlas,
+			atlas_bind_group,
+			render_pipeline,
+			instance_buffer,
+			glyph_instances: Vec::new(),
+			resolution: [1.0, 1.0],
+			has_warned_about_glyph_count: false,
+			label: label.to_owned(),
+		}
+	}
+
+	/**
+	 * Set the size of the textures that text is drawn onto, in pixels.
+	 */
+	pub fn resize(&mut self, width: u32, height: u32) {
+		self.resolution = [width.max(1) as f32, height.max(1) as f32];
+	}
+
+	/**
+	 * Get the width and height in pixels that a string takes up when it is drawn with the given options.
+	 */
+	pub fn measure_text(&self, text: &str, options: &TextOptions) -> [f32; 2] {
+		let pixel_size = options.size.round().max(1.0);
+		let lines = self.lay_out(text, pixel_size, options.maximum_width);
+		self.block_size(&lines, pixel_size)
+	}
+
+	/**
+	 * Lay out a string and add its glyphs to the ones that are drawn on the next call to render.
+	 */
+	pub fn queue_text(&mut self, text: &str, options: &TextOptions) {
+		// Glyphs are rasterized at whole pixel sizes so that the same size always hits the same atlas entries.
+		let pixel_size = options.size.round().max(1.0);
+		let lines = self.lay_out(text, pixel_size, options.maximum_width);
+		let block_size = self.block_size(&lines, pixel_size);
+		let scaled_font = self.font.as_scaled(ab_glyph::PxScale::from(pixel_size));
+		let line_height = scaled_font.ascent() - scaled_font.descent() + scaled_font.line_gap();
+		let ascent = scaled_font.ascent();
+
+		let anchor_factors = options.anchor.factors();
+		let block_left = options.position[0] - anchor_factors[0] * block_size[0];
+		let block_top = options.position[1] - anchor_factors[1] * block_size[1];
+		for (line_index, line) in lines.iter().enumerate() {
+			let line_left = block_left + anchor_factors[0] * (block_size[0] - line.width);
+			let baseline = block_top + ascent + line_index as f32 * line_height;
+			for laid_out_glyph in &line.glyphs {
+				let atlas_glyph = match self.glyph_atlas.glyph(
+					&self.font,
+					laid_out_glyph.glyph_id,
+					pixel_size as u32,
+				) {
+					Some(atlas_glyph) => atlas_glyph,
+					None => continue,
+				};
+				if self.glyph_instances.len() >= MAXIMUM_GLYPH_COUNT {
+					if !self.has_warned_about_glyph_count {
+						log::warn!(
+							"{} is drawing more than {} glyphs, so some text will be missing",
+							self.label,
+							MAXIMUM_GLYPH_COUNT
+						);
+						self.has_warned_about_glyph_count = true;
+					}
+					return;
+				}
+				// Glyphs are placed on whole pixels so that they are copied out of the atlas without being blurred.
+				self.glyph_instances.push(GlyphInstance {
+					position: [
+						(line_left + laid_out_glyph.x).round() + atlas_glyph.offset[0],
+						baseline.round() + atlas_glyph.offset[1],
+					],
+					size: [atlas_glyph.size[0] as f32, atlas_glyph.size[1] as f32],
+					atlas_position: [
+						atlas_glyph.atlas_position[0] as f32,
+						atlas_glyph.atlas_position[1] as f32,
+					],
+					color: options.color,
+				});
+			}
+		}
+	}
+
+	/**
+	 * Split a string into lines at newlines and, when there is a maximum width, at the last space before a line gets
+	 * too wide, placing each glyph along its line with kerning.
+	 */
+	fn lay_out(&self, text: &str, pixel_size: f32, maximum_width: Option<f32>) -> Vec<LaidOutLine> {
+		let scaled_font = self.font.as_scaled(ab_glyph::PxScale::from(pixel_size));
+		let line_width = |glyphs: &[LaidOutGlyph]| {
+			glyphs.last().map_or(0.0, |last_glyph| {
+				last_glyph.x + scaled_font.h_advance(last_glyph.glyph_id)
+			})
+		};
+
+		let mut lines = Vec::new();
+		for paragraph in text.split('\n') {
+			let mut glyphs: Vec<LaidOutGlyph> = Vec::new();
+			let mut caret = 0.0;
+			let mut previous_glyph_id = None;
+			// The index of the first glyph after the last space, which is where the line is broken if it gets too wide.
+			let mut break_index = None;
+			for character in paragraph.chars() {
+				let glyph_id = scaled_font.glyph_id(character);
+				if let Some(previous_glyph_id) = previous_glyph_id {
+					caret += scaled_font.kern(previous_glyph_id, glyph_id);
+				}
+				previous_glyph_id = Some(glyph_id);
+				let advance = scaled_font.h_advance(glyph_id);
+				if character.is_whitespace() {
+					caret += advance;
+					break_index = Some(glyphs.len());
+					continue;
+				}
+				if let (Some(maximum_width), Some(index)) = (maximum_width, break_index) {
+					if caret + advance > maximum_width && index > 0 {
+						let wrapped_glyphs = glyphs.split_off(index);
+						lines.push(LaidOutLine {
+							width: line_width(&glyphs),
+							glyphs,
+						});
+						let wrapped_start = wrapped_glyphs.first().map_or(caret, |glyph| glyph.x);
+						glyphs = wrapped_glyphs
+							.into_iter()
+							.map(|glyph| LaidOutGlyph {
+								glyph_id: glyph.glyph_id,
+								x: glyph.x - wrapped_start,
+							})
+							.collect();
+						caret -= wrapped_start;
+						break_index = None;
+					}
+				}
+				glyphs.push(LaidOutGlyph { glyph_id, x: caret });
+				caret += advance;
+			}
+			lines.push(LaidOutLine {
+				width: line_width(&glyphs),
+				glyphs,
+			});
+		}
+		lines
+	}
+
+	fn block_size(&self, lines: &[LaidOutLine], pixel_size: f32) -> [f32; 2] {
+		let scaled_font = self.font.as_scaled(ab_glyph::PxScale::from(pixel_size));
+		let line_height = scaled_font.ascent() - scaled_font.descent() + scaled_font.line_gap();
+		let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+		let height = scaled_font.ascent() - scaled_font.descent()
+			+ lines.len().saturating_sub(1) as f32 * line_height;
+		[width, height]
+	}
+
+	/**
+	 * Draw every string that has been queued since the last call over the existing contents of the output texture.
+	 */
+	pub fn render(
+		&mut self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		queue: &wgpu::Queue,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		self.glyph_atlas.upload(queue);
+		if self.glyph_instances.is_empty() {
+			return;
+		}
+		queue.write_buffer(
+			&self.instance_buffer,
+			0,
+			bytemuck::cast_slice(&self.glyph_instances),
+		);
+
+		let render_pass_label = self.label.clone() + " text render pass";
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some(&render_pass_label),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Load,
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::VERTEX,
+			0,
+			bytemuck::bytes_of(&PushConstantData {
+				resolution: self.resolution,
+				atlas_size: [
+					glyph_atlas::ATLAS_SIZE as f32,
+					glyph_atlas::ATLAS_SIZE as f32,
+				],
+			}),
+		);
+		render_pass.set_bind_group(0, &self.atlas_bind_group, &[]);
+		render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+		render_pass.draw(0..4, 0..self.glyph_instances.len() as u32);
+		drop(render_pass);
+
+		self.glyph_instances.clear();
+	}
+}
+
+/**
+ * Load a TTF or OTF font from a file. Without a path, the system's default sans serif font is asked for from
+ * fontconfig, falling back to a few common locations if that doesn't work.
+ */
+pub fn load_font(path: Option<&std::path::Path>) -> Result<ab_glyph::FontVec, String> {
+	let path = match path {
+		Some(path) => path.to_owned(),
+		None => default_font_path().ok_or_else(|| {
+			"Could not find a default font, so one has to be configured".to_owned()
+		})?,
+	};
+	let font_data = std::fs::read(&path)
+		.map_err(|error| format!("Could not read the font {}: {}", path.display(), error))?;
+	ab_glyph::FontVec::try_from_vec(font_data)
+		.map_err(|error| format!("Could not load the font {}: {}", path.display(), error))
+}
+
+fn default_font_path() -> Option<std::path::PathBuf> {
+	let fontconfig_path = std::process::Command::new("fc-match")
+		.args(["-f", "%{file}", "sans-serif"])
+		.output()
+		.ok()
+		.filter(|output| output.status.success())
+		.map(|output| std::path::PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+		.filter(|path| path.is_file());
+	fontconfig_path.or_else(|| {
+		FALLBACK_FONT_PATHS
+			.iter()
+			.map(std::path::PathBuf::from)
+			.find(|path| path.is_file())
+	})
+}
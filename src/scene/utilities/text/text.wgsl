@@ -0,0 +1,45 @@
+struct PushConstantData {
+	resolution: vec2<f32>,
+	atlas_size: vec2<f32>,
+};
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var atlas_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var atlas_sampler: sampler;
+
+struct GlyphInput {
+	// The top left corner and size of the glyph on screen, in pixels.
+	@location(0) position: vec2<f32>,
+	@location(1) size: vec2<f32>,
+	// The top left corner of the glyph in the atlas, in pixels.
+	@location(2) atlas_position: vec2<f32>,
+	@location(3) color: vec4<f32>,
+};
+
+struct FragmentInput {
+	@builtin(position) clip_position: vec4<f32>,
+	@location(0) atlas_coordinates: vec2<f32>,
+	@location(1) color: vec4<f32>,
+};
+
+// Each glyph is drawn as a triangle strip of four vertices making a quad that is copied out of the atlas pixel for
+// pixel.
+@vertex
+fn vertex_stage(@builtin(vertex_index) vertex_index: u32, glyph: GlyphInput) -> FragmentInput {
+	let corner = vec2<f32>(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+	let pixel_position = glyph.position + corner * glyph.size;
+	let position = pixel_position / push_constant_data.resolution * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+	return FragmentInput(
+		vec4<f32>(position, 0.0, 1.0),
+		(glyph.atlas_position + corner * glyph.size) / push_constant_data.atlas_size,
+		glyph.color,
+	);
+}
+
+@fragment
+fn fragment_stage(fragment: FragmentInput) -> @location(0) vec4<f32> {
+	let coverage = textureSample(atlas_texture, atlas_sampler, fragment.atlas_coordinates).r;
+	return vec4<f32>(fragment.color.rgb, fragment.color.a * coverage);
+}
@@ -1,3 +1,55 @@
+/**
+ * The color space that the colors of an image are stored in. Images that are meant to be looked at, like photos, are
+ * usually sRGB, while images holding other data, like normal maps, are usually linear.
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum ColorSpace {
+	Srgb,
+	Linear,
+}
+
+/**
+ * The ways that textures made from images can be sampled, which are chosen when they are created.
+ */
+#[derive(Clone, Copy, Debug)]
+pub enum SamplerPreset {
+	/**
+	 * Repeat the image outside of its edges.
+	 */
+	Repeat,
+	/**
+	 * Stretch the pixels along the edges of the image outside of it.
+	 */
+	Clamp,
+	/**
+	 * Repeat the image, filtering it anisotropically so that it stays sharp when seen at glancing angles.
+	 */
+	Anisotropic,
+}
+
+impl SamplerPreset {
+	fn create_sampler(self, device: &wgpu::Device, label: &str) -> wgpu::Sampler {
+		let address_mode = match self {
+			Self::Repeat | Self::Anisotropic => wgpu::AddressMode::Repeat,
+			Self::Clamp => wgpu::AddressMode::ClampToEdge,
+		};
+		device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(&(label.to_owned() + " sampler")),
+			address_mode_u: address_mode,
+			address_mode_v: address_mode,
+			address_mode_w: address_mode,
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			mipmap_filter: wgpu::FilterMode::Linear,
+			anisotropy_clamp: match self {
+				Self::Anisotropic => std::num::NonZeroU8::new(16),
+				Self::Repeat | Self::Clamp => None,
+			},
+			..wgpu::SamplerDescriptor::default()
+		})
+	}
+}
+
 pub struct Texture {
 	pub texture: wgpu::Texture,
 	pub texture_view: wgpu::TextureView,
@@ -14,6 +66,7 @@ impl Texture {
 		device: &wgpu::Device,
 		width: u32,
 		height: u32,
+		sample_count: u32,
 		scene_name: &str,
 	) -> Texture {
 		let texture_extent = wgpu::Extent3d {
@@ -26,7 +79,7 @@ impl Texture {
 			label: Some(texture_descriptor_label),
 			size: texture_extent,
 			mip_level_count: 1,
-			sample_count: 1,
+			sample_count,
 			dimension: wgpu::TextureDimension::D2,
 			format: Self::DEPTH_FORMAT,
 			usage: wgpu::TextureUsages::RENDER_ATTACHMENT
@@ -56,15 +109,404 @@ impl Texture {
 		}
 	}
 
+	/**
+	 * Create a texture that a scene can render into with multiple samples per pixel before resolving it.
+	 */
+	pub fn create_multisampled_color_texture(
+		device: &wgpu::Device,
+		render_target_configuration: &crate::scene::utilities::render_target::RenderTargetConfiguration,
+		scene_name: &str,
+	) -> Texture {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(&(scene_name.to_owned() + " multisampled color texture")),
+			size: wgpu::Extent3d {
+				width: render_target_configuration.width,
+				height: render_target_configuration.height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: render_target_configuration.sample_count,
+			dimension: wgpu::TextureDimension::D2,
+			format: render_target_configuration.format,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(&(scene_name.to_owned() + " multisampled color sampler")),
+			..wgpu::SamplerDescriptor::default()
+		});
+		Self {
+			texture,
+			texture_view,
+			sampler,
+			sample_type: wgpu::TextureSampleType::Float { filterable: false },
+			view_dimension: wgpu::TextureViewDimension::D2,
+			sampler_binding_type: wgpu::SamplerBindingType::NonFiltering,
+		}
+	}
+
+	/**
+	 * Create a texture that can be rendered into and then sampled from, such as an intermediate render target.
+	 */
+	pub fn create_render_target(
+		device: &wgpu::Device,
+		format: wgpu::TextureFormat,
+		width: u32,
+		height: u32,
+		label: &str,
+	) -> Texture {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(&(label.to_owned() + " texture")),
+			size: wgpu::Extent3d {
+				width,
+				height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(&(label.to_owned() + " sampler")),
+			address_mode_u: wgpu::AddressMode::ClampToEdge,
+			address_mode_v: wgpu::AddressMode::ClampToEdge,
+			address_mode_w: wgpu::AddressMode::ClampToEdge,
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			mipmap_filter: wgpu::FilterMode::Nearest,
+			..wgpu::SamplerDescriptor::default()
+		});
+		Self {
+			texture,
+			texture_view,
+			sampler,
+			sample_type: wgpu::TextureSampleType::Float { filterable: true },
+			view_dimension: wgpu::TextureViewDimension::D2,
+			sampler_binding_type: wgpu::SamplerBindingType::Filtering,
+		}
+	}
+
+	/**
+	 * Create a texture that compute shaders can write into as a storage texture and sample from, and that can be copied
+	 * to and from.
+	 */
+	pub fn create_storage_texture(
+		device: &wgpu::Device,
+		format: wgpu::TextureFormat,
+		width: u32,
+		height: u32,
+		label: &str,
+	) -> Texture {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(&(label.to_owned() + " texture")),
+			size: wgpu::Extent3d {
+				width,
+				height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format,
+			usage: wgpu::TextureUsages::STORAGE_BINDING
+				| wgpu::TextureUsages::TEXTURE_BINDING
+				| wgpu::TextureUsages::COPY_SRC
+				| wgpu::TextureUsages::COPY_DST,
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(&(label.to_owned() + " sampler")),
+			address_mode_u: wgpu::AddressMode::ClampToEdge,
+			address_mode_v: wgpu::AddressMode::ClampToEdge,
+			address_mode_w: wgpu::AddressMode::ClampToEdge,
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			mipmap_filter: wgpu::FilterMode::Nearest,
+			..wgpu::SamplerDescriptor::default()
+		});
+		Self {
+			texture,
+			texture_view,
+			sampler,
+			sample_type: wgpu::TextureSampleType::Float { filterable: true },
+			view_dimension: wgpu::TextureViewDimension::D2,
+			sampler_binding_type: wgpu::SamplerBindingType::Filtering,
+		}
+	}
+
+	/**
+	 * Create a texture holding an image that shaders can sample from, with a full mip chain that is generated on the
+	 * GPU.
+	 */
+	pub fn from_image(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		image: &image::RgbaImage,
+		color_space: ColorSpace,
+		sampler_preset: SamplerPreset,
+		label: &str,
+	) -> Texture {
+		let format = match color_space {
+			ColorSpace::Srgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+			ColorSpace::Linear => wgpu::TextureFormat::Rgba8Unorm,
+		};
+		Self::create_mipmapped(
+			device,
+			queue,
+			format,
+			[image.width(), image.height()],
+			image.as_raw(),
+			sampler_preset,
+			label,
+		)
+	}
+
+	/**
+	 * Create a texture holding a high dynamic range image that shaders can sample from, with a full mip chain that is
+	 * generated on the GPU. The image is stored as 16 bit floats, which keeps it filterable.
+	 */
+	pub fn from_hdr_image(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		image: &image::Rgba32FImage,
+		sampler_preset: SamplerPreset,
+		label: &str,
+	) -> Texture {
+		let half_float_bits = image
+			.as_raw()
+			.iter()
+			.map(|&value| half::f16::from_f32(value).to_bits())
+			.collect::<Vec<u16>>();
+		Self::create_mipmapped(
+			device,
+			queue,
+			wgpu::TextureFormat::Rgba16Float,
+			[image.width(), image.height()],
+			bytemuck::cast_slice(&half_float_bits),
+			sampler_preset,
+			label,
+		)
+	}
+
+	/**
+	 * Load an image file into a texture with from_image, or with from_hdr_image for Radiance HDR and OpenEXR files, in
+	 * which case the color space is ignored since those are always linear.
+	 */
+	pub fn from_image_file(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		path: &std::path::Path,
+		color_space: ColorSpace,
+		sampler_preset: SamplerPreset,
+		label: &str,
+	) -> Result<Texture, String> {
+		let is_hdr = path
+			.extension()
+			.and_then(|extension| extension.to_str())
+			.is_some_and(|extension| {
+				extension.eq_ignore_ascii_case("hdr") || extension.eq_ignore_ascii_case("exr")
+			});
+		if is_hdr {
+			let image = Self::load_hdr_image(path)?;
+			Ok(Self::from_hdr_image(
+				device,
+				queue,
+				&image,
+				sampler_preset,
+				label,
+			))
+		} else {
+			let image = Self::load_image(path)?;
+			Ok(Self::from_image(
+				device,
+				queue,
+				&image,
+				color_space,
+				sampler_preset,
+				label,
+			))
+		}
+	}
+
+	/**
+	 * Create a texture with a full mip chain, upload the pixels of its first mip level and generate the rest from it.
+	 */
+	fn create_mipmapped(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		format: wgpu::TextureFormat,
+		[width, height]: [u32; 2],
+		pixels: &[u8],
+		sampler_preset: SamplerPreset,
+		label: &str,
+	) -> Texture {
+		let size = wgpu::Extent3d {
+			width,
+			height,
+			depth_or_array_layers: 1,
+		};
+		let mip_level_count = crate::scene::utilities::mipmap::mip_level_count(width, height);
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(&(label.to_owned() + " texture")),
+			size,
+			mip_level_count,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format,
+			usage: wgpu::TextureUsages::TEXTURE_BINDING
+				| wgpu::TextureUsages::COPY_DST
+				| wgpu::TextureUsages::RENDER_ATTACHMENT,
+		});
+		queue.write_texture(
+			wgpu::ImageCopyTexture {
+				texture: &texture,
+				mip_level: 0,
+				origin: wgpu::Origin3d::ZERO,
+				aspect: wgpu::TextureAspect::All,
+			},
+			pixels,
+			wgpu::ImageDataLayout {
+				offset: 0,
+				bytes_per_row: std::num::NonZeroU32::new(
+					format.describe().block_size as u32 * width,
+				),
+				rows_per_image: std::num::NonZeroU32::new(height),
+			},
+			size,
+		);
+		crate::scene::utilities::mipmap::generate_mipmaps(
+			device,
+			queue,
+			&texture,
+			format,
+			mip_level_count,
+			label,
+		);
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = sampler_preset.create_sampler(device, label);
+		Self {
+			texture,
+			texture_view,
+			sampler,
+			sample_type: wgpu::TextureSampleType::Float { filterable: true },
+			view_dimension: wgpu::TextureViewDimension::D2,
+			sampler_binding_type: wgpu::SamplerBindingType::Filtering,
+		}
+	}
+
+	/**
+	 * Read an image file in any of the formats that the image crate can decode, such as PNG, JPEG or WebP.
+	 */
+	pub fn load_image(path: &std::path::Path) -> Result<image::RgbaImage, String> {
+		image::open(path)
+			.map(|image| image.into_rgba8())
+			.map_err(|error| error.to_string())
+	}
+
+	/**
+	 * Read a high dynamic range image file, such as a Radiance HDR or OpenEXR file, keeping its colors as floats.
+	 */
+	pub fn load_hdr_image(path: &std::path::Path) -> Result<image::Rgba32FImage, String> {
+		image::open(path)
+			.map(|image| image.into_rgba32f())
+			.map_err(|error| error.to_string())
+	}
+
+	/**
+	 * Create an sRGB texture that images can later be written into with write_image, which is useful for scenes that
+	 * show images loaded after they are created. Images can be smaller than the texture, in which case they are put in
+	 * its top left corner.
+	 */
+	pub fn create_image_texture(
+		device: &wgpu::Device,
+		width: u32,
+		height: u32,
+		label: &str,
+	) -> Texture {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(&(label.to_owned() + " texture")),
+			size: wgpu::Extent3d {
+				width,
+				height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: wgpu::TextureFormat::Rgba8UnormSrgb,
+			usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(&(label.to_owned() + " sampler")),
+			address_mode_u: wgpu::AddressMode::ClampToEdge,
+			address_mode_v: wgpu::AddressMode::ClampToEdge,
+			address_mode_w: wgpu::AddressMode::ClampToEdge,
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			mipmap_filter: wgpu::FilterMode::Nearest,
+			..wgpu::SamplerDescriptor::default()
+		});
+		Self {
+			texture,
+			texture_view,
+			sampler,
+			sample_type: wgpu::TextureSampleType::Float { filterable: true },
+			view_dimension: wgpu::TextureViewDimension::D2,
+			sampler_binding_type: wgpu::SamplerBindingType::Filtering,
+		}
+	}
+
+	/**
+	 * Write an image into the top left corner of a texture made by create_image_texture.
+	 */
+	pub fn write_image(&self, queue: &wgpu::Queue, image: &image::RgbaImage) {
+		queue.write_texture(
+			wgpu::ImageCopyTexture {
+				texture: &self.texture,
+				mip_level: 0,
+				origin: wgpu::Origin3d::ZERO,
+				aspect: wgpu::TextureAspect::All,
+			},
+			image.as_raw(),
+			wgpu::ImageDataLayout {
+				offset: 0,
+				bytes_per_row: std::num::NonZeroU32::new(4 * image.width()),
+				rows_per_image: std::num::NonZeroU32::new(image.height()),
+			},
+			wgpu::Extent3d {
+				width: image.width(),
+				height: image.height(),
+				depth_or_array_layers: 1,
+			},
+		);
+	}
+
 	pub fn create_bind_group(
 		&self,
 		device: &wgpu::Device,
 		label: &str,
 		visibility: wgpu::ShaderStages,
 	) -> (wgpu::BindGroupLayout, wgpu::BindGroup) {
-		let label = label.to_owned() + " bind group";
-		let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-			label: Some(&(label.clone() + " layout")),
+		let layout = self.create_bind_group_layout(device, label, visibility);
+		let group = self.create_bind_group_with_layout(device, &layout, label);
+		(layout, group)
+	}
+
+	/**
+	 * Create the layout of a bind group that binds this texture and its sampler.
+	 */
+	pub fn create_bind_group_layout(
+		&self,
+		device: &wgpu::Device,
+		label: &str,
+		visibility: wgpu::ShaderStages,
+	) -> wgpu::BindGroupLayout {
+		device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some(&(label.to_owned() + " bind group layout")),
 			entries: &[
 				wgpu::BindGroupLayoutEntry {
 					binding: 0,
@@ -83,10 +525,22 @@ impl Texture {
 					count: None,
 				},
 			],
-		});
-		let group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-			label: Some(&label),
-			layout: &layout,
+		})
+	}
+
+	/**
+	 * Create a bind group for this texture and its sampler using an existing layout, which lets pipelines keep working
+	 * when the texture is recreated.
+	 */
+	pub fn create_bind_group_with_layout(
+		&self,
+		device: &wgpu::Device,
+		layout: &wgpu::BindGroupLayout,
+		label: &str,
+	) -> wgpu::BindGroup {
+		device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some(&(label.to_owned() + " bind group")),
+			layout,
 			entries: &[
 				wgpu::BindGroupEntry {
 					binding: 0,
@@ -97,7 +551,6 @@ impl Texture {
 					resource: wgpu::BindingResource::Sampler(&self.sampler),
 				},
 			],
-		});
-		(layout, group)
+		})
 	}
 }
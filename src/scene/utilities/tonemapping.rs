@@ -0,0 +1,156 @@
+/**
+ * The format of the high dynamic range texture that scenes render into before being tonemapped.
+ */
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PushConstantData {
+	exposure: f32,
+	tonemapping_operator: u32,
+	encode_srgb: u32,
+}
+
+/**
+ * Owns the high dynamic range texture that scenes render into and maps it into the displayable range.
+ */
+pub struct Tonemapper {
+	tonemapping_configuration: crate::config::TonemappingConfig,
+	encode_srgb: bool,
+	hdr_texture: crate::scene::utilities::texture::Texture,
+	hdr_texture_bind_group_layout: wgpu::BindGroupLayout,
+	hdr_texture_bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Tonemapper {
+	pub fn new(
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		output_format: wgpu::TextureFormat,
+		tonemapping_configuration: &crate::config::TonemappingConfig,
+	) -> Self {
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			"Tonemapping shader",
+			include_str!("tonemapping.wgsl"),
+		);
+		let hdr_texture = crate::scene::utilities::texture::Texture::create_render_target(
+			device,
+			HDR_FORMAT,
+			surface_configuration.width,
+			surface_configuration.height,
+			"Tonemapping HDR",
+		);
+		let hdr_texture_bind_group_layout = hdr_texture.create_bind_group_layout(
+			device,
+			"Tonemapping HDR",
+			wgpu::ShaderStages::FRAGMENT,
+		);
+		let hdr_texture_bind_group = hdr_texture.create_bind_group_with_layout(
+			device,
+			&hdr_texture_bind_group_layout,
+			"Tonemapping HDR",
+		);
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Tonemapping pipeline layout"),
+				bind_group_layouts: &[&hdr_texture_bind_group_layout],
+				push_constant_ranges: &[wgpu::PushConstantRange {
+					stages: wgpu::ShaderStages::FRAGMENT,
+					range: 0..std::mem::size_of::<PushConstantData>() as u32,
+				}],
+			});
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Tonemapping pipeline",
+			&shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: output_format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+		Self {
+			tonemapping_configuration: *tonemapping_configuration,
+			// Outputs with an sRGB format encode the written colors themselves, but otherwise the shader has to.
+			encode_srgb: !output_format.describe().srgb,
+			hdr_texture,
+			hdr_texture_bind_group_layout,
+			hdr_texture_bind_group,
+			render_pipeline,
+		}
+	}
+
+	/**
+	 * Get the high dynamic range texture that scenes should render into.
+	 */
+	pub fn hdr_texture(&self) -> &crate::scene::utilities::texture::Texture {
+		&self.hdr_texture
+	}
+
+	/**
+	 * Recreate the high dynamic range texture so that it matches the size of the surface.
+	 */
+	pub fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+	) {
+		self.hdr_texture = crate::scene::utilities::texture::Texture::create_render_target(
+			device,
+			HDR_FORMAT,
+			surface_configuration.width,
+			surface_configuration.height,
+			"Tonemapping HDR",
+		);
+		self.hdr_texture_bind_group = self.hdr_texture.create_bind_group_with_layout(
+			device,
+			&self.hdr_texture_bind_group_layout,
+			"Tonemapping HDR",
+		);
+	}
+
+	/**
+	 * Tonemap the high dynamic range texture into the output texture view. Scenes that don't use tonemapping have their
+	 * colors passed through as they are instead.
+	 */
+	pub fn render(
+		&self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		output_texture_view: &wgpu::TextureView,
+		uses_tonemapping: bool,
+	) {
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Tonemapping render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_push_constants(
+			wgpu::ShaderStages::FRAGMENT,
+			0,
+			bytemuck::bytes_of(&PushConstantData {
+				exposure: self.tonemapping_configuration.exposure,
+				tonemapping_operator: match self.tonemapping_configuration.operator {
+					_ if !uses_tonemapping => 3,
+					crate::config::TonemappingOperator::Reinhard => 0,
+					crate::config::TonemappingOperator::Aces => 1,
+					crate::config::TonemappingOperator::Agx => 2,
+				},
+				encode_srgb: self.encode_srgb as u32,
+			}),
+		);
+		render_pass.set_bind_group(0, &self.hdr_texture_bind_group, &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+}
@@ -0,0 +1,95 @@
+struct PushConstantData {
+	exposure: f32,
+	tonemapping_operator: u32,
+	encode_srgb: u32,
+};
+
+var<push_constant> push_constant_data: PushConstantData;
+
+@group(0) @binding(0)
+var hdr_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var hdr_sampler: sampler;
+
+struct FragmentOutput {
+	@location(0) color: vec4<f32>,
+};
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+	return color / (vec3<f32>(1.0) + color);
+}
+
+// Stephen Hill's fit of the ACES reference rendering and output device transforms.
+fn aces(color: vec3<f32>) -> vec3<f32> {
+	let input_matrix = mat3x3<f32>(
+		vec3<f32>(0.59719, 0.07600, 0.02840),
+		vec3<f32>(0.35458, 0.90834, 0.13383),
+		vec3<f32>(0.04823, 0.01566, 0.83777),
+	);
+	let output_matrix = mat3x3<f32>(
+		vec3<f32>(1.60475, -0.10208, -0.00327),
+		vec3<f32>(-0.53108, 1.10813, -0.07276),
+		vec3<f32>(-0.07367, -0.00605, 1.07602),
+	);
+	let v = input_matrix * color;
+	let a = v * (v + 0.0245786) - 0.000090537;
+	let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+	return clamp(output_matrix * (a / b), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Minimal AgX with the default look, using a polynomial approximation of the contrast curve.
+fn agx(color: vec3<f32>) -> vec3<f32> {
+	let inset_matrix = mat3x3<f32>(
+		vec3<f32>(0.842479062253094, 0.0423282422610123, 0.0423756549057051),
+		vec3<f32>(0.0784335999999992, 0.878468636469772, 0.0784336),
+		vec3<f32>(0.0792237451477643, 0.0791661274605434, 0.879142973793104),
+	);
+	let outset_matrix = mat3x3<f32>(
+		vec3<f32>(1.19687900512017, -0.0528968517574562, -0.0529716355144438),
+		vec3<f32>(-0.0980208811401368, 1.15190312990417, -0.0980434501171241),
+		vec3<f32>(-0.0990297440797205, -0.0989611768448433, 1.15107367264116),
+	);
+	let minimum_ev = -12.47393;
+	let maximum_ev = 4.026069;
+	var v = inset_matrix * color;
+	v = clamp(log2(max(v, vec3<f32>(1e-10))), vec3<f32>(minimum_ev), vec3<f32>(maximum_ev));
+	v = (v - minimum_ev) / (maximum_ev - minimum_ev);
+	let v2 = v * v;
+	let v4 = v2 * v2;
+	v = 15.5 * v4 * v2 - 40.14 * v4 * v + 31.96 * v4 - 6.868 * v2 * v + 0.4298 * v2 + 0.1191 * v - 0.00232;
+	v = outset_matrix * v;
+	// The curve produces display encoded values, so undo the display's 2.2 gamma to get back to linear values.
+	return pow(clamp(v, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(2.2));
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+	let lower = color * 12.92;
+	let higher = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+	return select(higher, lower, color < vec3<f32>(0.0031308));
+}
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	let hdr_color = textureSample(hdr_texture, hdr_sampler, input.texture_coordinates);
+	let exposed_color = max(hdr_color.rgb * push_constant_data.exposure, vec3<f32>(0.0));
+	var color: vec3<f32>;
+	switch (push_constant_data.tonemapping_operator) {
+		case 3u: {
+			// Scenes that don't use tonemapping already output displayable colors, so they only get clamped.
+			color = clamp(hdr_color.rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+		}
+		case 0u: {
+			color = reinhard(exposed_color);
+		}
+		case 1u: {
+			color = aces(exposed_color);
+		}
+		default: {
+			color = agx(exposed_color);
+		}
+	}
+	if (push_constant_data.encode_srgb != 0u) {
+		color = linear_to_srgb(color);
+	}
+	return FragmentOutput(vec4<f32>(color, 1.0));
+}
@@ -0,0 +1,11 @@
+@group(0) @binding(0)
+var mirror_texture: texture_2d<f32>;
+
+struct FragmentOutput {
+	@location(0) color: vec4<f32>,
+};
+
+@fragment
+fn fragment_stage(input: FragmentInput) -> FragmentOutput {
+	return FragmentOutput(textureLoad(mirror_texture, vec2<i32>(input.position.xy), 0));
+}
@@ -0,0 +1,410 @@
+/**
+ * Captures the frame that is being shown so that it can be saved as a PNG. When the surface can be copied from, the
+ * frame is copied straight out of it. Otherwise a frame that is being captured is drawn into a mirror texture instead,
+ * which is then drawn onto the surface and copied from.
+ *
+ * Copies are read back on their own threads so that the render loop doesn't wait for them, but they only finish once
+ * the device has been polled, which the window does while any are in flight.
+ */
+pub struct FrameCapture {
+	format: wgpu::TextureFormat,
+	width: u32,
+	height: u32,
+	/**
+	 * The mirror texture and what draws it onto the surface, which are only there when the surface can't be copied
+	 * from.
+	 */
+	mirror: Option<Mirror>,
+	readbacks_in_flight: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+struct Mirror {
+	texture: wgpu::Texture,
+	texture_view: wgpu::TextureView,
+	bind_group_layout: wgpu::BindGroupLayout,
+	bind_group: wgpu::BindGroup,
+	render_pipeline: wgpu::RenderPipeline,
+}
+
+impl FrameCapture {
+	pub fn new(device: &wgpu::Device, surface_configuration: &wgpu::SurfaceConfiguration) -> Self {
+		let mirror = if surface_configuration
+			.usage
+			.contains(wgpu::TextureUsages::COPY_SRC)
+		{
+			None
+		} else {
+			Some(Mirror::new(device, surface_configuration))
+		};
+		Self {
+			format: surface_configuration.format,
+			width: surface_configuration.width,
+			height: surface_configuration.height,
+			mirror,
+			readbacks_in_flight: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+		}
+	}
+
+	/**
+	 * Recreate the mirror texture so that it matches the size of the surface.
+	 */
+	pub fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+	) {
+		self.width = surface_configuration.width;
+		self.height = surface_configuration.height;
+		if let Some(mirror) = &mut self.mirror {
+			mirror.resize(device, surface_configuration);
+		}
+	}
+
+	/**
+	 * Get the texture view that a frame being captured should be drawn into, which is the mirror texture if there is
+	 * one and the surface otherwise.
+	 */
+	pub fn target_texture_view<'a>(
+		&'a self,
+		output_texture_view: &'a wgpu::TextureView,
+	) -> &'a wgpu::TextureView {
+		self.mirror
+			.as_ref()
+			.map_or(output_texture_view, |mirror| &mirror.texture_view)
+	}
+
+	/**
+	 * Whether there are captured frames that are still being read back, in which case the device has to be polled for
+	 * them to finish.
+	 */
+	pub fn has_readbacks_in_flight(&self) -> bool {
+		self.readbacks_in_flight
+			.load(std::sync::atomic::Ordering::Acquire)
+			> 0
+	}
+
+	/**
+	 * Draw the mirror texture onto the surface if there is one, and copy the frame into a buffer, which it can be read
+	 * back from once the commands have been submitted.
+	 */
+	pub fn finish(
+		&self,
+		device: &wgpu::Device,
+		command_encoder: &mut wgpu::CommandEncoder,
+		output_texture: &wgpu::Texture,
+		output_texture_view: &wgpu::TextureView,
+	) -> CapturedFrame {
+		let source_texture = match &self.mirror {
+			Some(mirror) => {
+				mirror.render(command_encoder, output_texture_view);
+				&mirror.texture
+			}
+			None => output_texture,
+		};
+
+		// Rows of texture copies have to be aligned, so the rows in the buffer are padded out past the pixels.
+		let padded_bytes_per_row = (self.width * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+			* wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+		let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Frame capture readback buffer"),
+			size: (padded_bytes_per_row * self.height) as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+			mapped_at_creation: false,
+		});
+		command_encoder.copy_texture_to_buffer(
+			wgpu::ImageCopyTexture {
+				texture: source_texture,
+				mip_level: 0,
+				origin: wgpu::Origin3d::ZERO,
+				aspect: wgpu::TextureAspect::All,
+			},
+			wgpu::ImageCopyBuffer {
+				buffer: &buffer,
+				layout: wgpu::ImageDataLayout {
+					offset: 0,
+					bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+					rows_per_image: std::num::NonZeroU32::new(self.height),
+				},
+			},
+			wgpu::Extent3d {
+				width: self.width,
+				height: self.height,
+				depth_or_array_layers: 1,
+			},
+		);
+		CapturedFrame {
+			buffer,
+			format: self.format,
+			width: self.width,
+			height: self.height,
+			padded_bytes_per_row,
+			readbacks_in_flight: self.readbacks_in_flight.clone(),
+		}
+	}
+}
+
+impl Mirror {
+	fn new(device: &wgpu::Device, surface_configuration: &wgpu::SurfaceConfiguration) -> Self {
+		let shader_module = crate::scene::utilities::fullscreen::create_fullscreen_shader_module(
+			device,
+			"Frame capture shader",
+			include_str!("mirror.wgsl"),
+		);
+		let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some("Frame capture mirror bind group layout"),
+			entries: &[wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Texture {
+					sample_type: wgpu::TextureSampleType::Float { filterable: false },
+					view_dimension: wgpu::TextureViewDimension::D2,
+					multisampled: false,
+				},
+				count: None,
+			}],
+		});
+		let render_pipeline_layout =
+			device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+				label: Some("Frame capture pipeline layout"),
+				bind_group_layouts: &[&bind_group_layout],
+				push_constant_ranges: &[],
+			});
+		let render_pipeline = crate::scene::utilities::fullscreen::create_fullscreen_pipeline(
+			device,
+			"Frame capture pipeline",
+			&shader_module,
+			"fragment_stage",
+			&render_pipeline_layout,
+			wgpu::ColorTargetState {
+				format: surface_configuration.format,
+				blend: None,
+				write_mask: wgpu::ColorWrites::all(),
+			},
+		);
+		let (texture, texture_view, bind_group) =
+			Self::create_texture(device, surface_configuration, &bind_group_layout);
+		Self {
+			texture,
+			texture_view,
+			bind_group_layout,
+			bind_group,
+			render_pipeline,
+		}
+	}
+
+	fn create_texture(
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		bind_group_layout: &wgpu::BindGroupLayout,
+	) -> (wgpu::Texture, wgpu::TextureView, wgpu::BindGroup) {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("Frame capture mirror texture"),
+			size: wgpu::Extent3d {
+				width: surface_configuration.width,
+				height: surface_configuration.height,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: surface_configuration.format,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+				| wgpu::TextureUsages::TEXTURE_BINDING
+				| wgpu::TextureUsages::COPY_SRC,
+		});
+		let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Frame capture mirror bind group"),
+			layout: bind_group_layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: wgpu::BindingResource::TextureView(&texture_view),
+			}],
+		});
+		(texture, texture_view, bind_group)
+	}
+
+	fn resize(
+		&mut self,
+		device: &wgpu::Device,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+	) {
+		let (texture, texture_view, bind_group) =
+			Self::create_texture(device, surface_configuration, &self.bind_group_layout);
+		self.texture = texture;
+		self.texture_view = texture_view;
+		self.bind_group = bind_group;
+	}
+
+	fn render(
+		&self,
+		command_encoder: &mut wgpu::CommandEncoder,
+		output_texture_view: &wgpu::TextureView,
+	) {
+		let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+			label: Some("Frame capture render pass"),
+			color_attachments: &[wgpu::RenderPassColorAttachment {
+				view: output_texture_view,
+				resolve_target: None,
+				ops: wgpu::Operations {
+					load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+					store: true,
+				},
+			}],
+			depth_stencil_attachment: None,
+		});
+		render_pass.set_pipeline(&self.render_pipeline);
+		render_pass.set_bind_group(0, &self.bind_group, &[]);
+		render_pass.draw(0..3, 0..1);
+	}
+}
+
+/**
+ * A frame that has been copied into a buffer on the GPU.
+ */
+pub struct CapturedFrame {
+	buffer: wgpu::Buffer,
+	format: wgpu::TextureFormat,
+	width: u32,
+	height: u32,
+	padded_bytes_per_row: u32,
+	readbacks_in_flight: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl CapturedFrame {
+	/**
+	 * Read the frame back into an image on another thread and hand it to `on_read` there. This should only be called
+	 * after the commands that copy the frame have been submitted, and the frame is only read once the device is
+	 * polled after that.
+	 */
+	pub fn read_image_in_background(
+		self,
+		on_read: impl FnOnce(Result<image::RgbImage, String>) + Send + 'static,
+	) {
+		let readbacks_in_flight = self.readbacks_in_flight.clone();
+		readbacks_in_flight.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+		let spawn_result = std::thread::Builder::new()
+			.name("frame capture".to_owned())
+			.spawn(move || {
+				let image = self.read_image();
+				self.readbacks_in_flight
+					.fetch_sub(1, std::sync::atomic::Ordering::AcqRel);
+				on_read(image);
+			});
+		if let Err(error) = spawn_result {
+			readbacks_in_flight.fetch_sub(1, std::sync::atomic::Ordering::AcqRel);
+			log::warn!("Could not read back a captured frame: {}", error);
+		}
+	}
+
+	fn read_image(&self) -> Result<image::RgbImage, String> {
+		let is_bgra = match self.format {
+			wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+			wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+			format => return Err(format!("frames in the format {:?} can't be saved", format)),
+		};
+		let buffer_slice = self.buffer.slice(..);
+		pollster::block_on(buffer_slice.map_async(wgpu::MapMode::Read))
+			.map_err(|error| error.to_string())?;
+
+		let mut pixels = Vec::with_capacity((self.width * self.height * 4) as usize);
+		for row in buffer_slice
+			.get_mapped_range()
+			.chunks_exact(self.padded_bytes_per_row as usize)
+		{
+			pixels.extend_from_slice(&row[..(self.width * 4) as usize]);
+		}
+		self.buffer.unmap();
+		if is_bgra {
+			for pixel in pixels.chunks_exact_mut(4) {
+				pixel.swap(0, 2);
+			}
+		}
+		// The surface isn't transparent, so the alpha channel is left out rather than saving whatever is in it.
+		Ok(image::DynamicImage::ImageRgba8(
+			image::RgbaImage::from_raw(self.width, self.height, pixels)
+				.ok_or_else(|| "the frame is the wrong size".to_owned())?,
+		)
+		.into_rgb8())
+	}
+}
+
+/**
+ * Where a captured frame goes.
+ */
+pub enum ScreenshotDestination {
+	/**
+	 * A PNG file at the path.
+	 */
+	File(std::path::PathBuf),
+	/**
+	 * PNG data that is sent back to whatever asked for the frame.
+	 */
+	Png(std::sync::mpsc::Sender<Vec<u8>>),
+}
+
+impl ScreenshotDestination {
+	/**
+	 * Encode the frame and send it where it goes, returning what the command that asked for it is answered with.
+	 */
+	pub fn deliver(self, image: &image::RgbImage) -> crate::control::ControlResponse {
+		match self {
+			Self::File(path) => save_image(image, &path)
+				.map(|_| serde_json::json!({ "path": path }))
+				.map_err(|error| {
+					format!(
+						"Could not save a screenshot to {}: {}",
+						path.display(),
+						error
+					)
+				}),
+			Self::Png(png_sender) => encode_png(image)
+				.map(|png| {
+					let _ = png_sender.send(png);
+					serde_json::Value::Null
+				})
+				.map_err(|error| format!("Could not encode the frame: {}", error)),
+		}
+	}
+}
+
+/**
+ * Encode a captured frame as a PNG in memory.
+ */
+pub fn encode_png(image: &image::RgbImage) -> Result<Vec<u8>, String> {
+	let mut png = std::io::Cursor::new(Vec::new());
+	image
+		.write_to(&mut png, image::ImageOutputFormat::Png)
+		.map_err(|error| error.to_string())?;
+	Ok(png.into_inner())
+}
+
+/**
+ * Save a captured frame as a PNG, making the directory that it goes into if it doesn't exist yet.
+ */
+pub fn save_image(image: &image::RgbImage, path: &std::path::Path) -> Result<(), String> {
+	if let Some(directory) = path.parent() {
+		std::fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+	}
+	image
+		.save_with_format(path, image::ImageFormat::Png)
+		.map_err(|error| error.to_string())
+}
+
+/**
+ * Get where a screenshot goes when no path is given for it, which is a file named after the current time in the
+ * pictures directory.
+ */
+pub fn default_path() -> std::path::PathBuf {
+	let pictures_directory = std::env::var_os("XDG_PICTURES_DIR")
+		.map(std::path::PathBuf::from)
+		.filter(|pictures_directory| pictures_directory.is_absolute())
+		.or_else(|| {
+			std::env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join("Pictures"))
+		})
+		.unwrap_or_else(std::env::temp_dir);
+	pictures_directory.join(format!(
+		"custom_background_{}.png",
+		chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+	))
+}
@@ -0,0 +1,62 @@
+/**
+ * The signals that shut the background down.
+ */
+const TERMINATION_SIGNALS: [libc::c_int; 2] =
+	[signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT];
+
+/**
+ * Listen for signals on a thread of its own and turn them into commands for the window, so that they're handled
+ * between frames just like commands from the control socket:
+ *
+ * - SIGTERM and SIGINT shut the background down cleanly. A second one exits right away, in case the window is stuck.
+ * - SIGHUP loads the config file again.
+ * - SIGUSR1 saves a screenshot into the pictures directory.
+ * - SIGUSR2 pauses the scene, or resumes it if it's paused.
+ */
+pub fn listen(control_sender: crate::control::ControlSender) -> Result<(), String> {
+	let is_terminating = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+	for signal in TERMINATION_SIGNALS {
+		// The conditional shutdown has to be registered first so that it sees the flag from before this signal.
+		signal_hook::flag::register_conditional_shutdown(signal, 1, is_terminating.clone())
+			.map_err(|error| error.to_string())?;
+		signal_hook::flag::register(signal, is_terminating.clone())
+			.map_err(|error| error.to_string())?;
+	}
+	let mut signals = signal_hook::iterator::Signals::new(TERMINATION_SIGNALS.into_iter().chain([
+		signal_hook::consts::SIGHUP,
+		signal_hook::consts::SIGUSR1,
+		signal_hook::consts::SIGUSR2,
+	]))
+	.map_err(|error| error.to_string())?;
+	std::thread::Builder::new()
+		.name("signals".to_owned())
+		.spawn(move || {
+			for signal in signals.forever() {
+				let (signal_name, command) = match signal {
+					signal_hook::consts::SIGTERM => {
+						("SIGTERM", crate::control::ControlCommand::Shutdown)
+					}
+					signal_hook::consts::SIGINT => {
+						("SIGINT", crate::control::ControlCommand::Shutdown)
+					}
+					signal_hook::consts::SIGHUP => {
+						("SIGHUP", crate::control::ControlCommand::ReloadConfig)
+					}
+					signal_hook::consts::SIGUSR1 => {
+						("SIGUSR1", crate::control::ControlCommand::Screenshot(None))
+					}
+					signal_hook::consts::SIGUSR2 => {
+						("SIGUSR2", crate::control::ControlCommand::TogglePause)
+					}
+					_ => continue,
+				};
+				match control_sender.send(command) {
+					Ok(serde_json::Value::Null) => (),
+					Ok(result) => log::info!("Handled {}: {}", signal_name, result),
+					Err(error) => log::warn!("Could not handle {}: {}", signal_name, error),
+				}
+			}
+		})
+		.map_err(|error| error.to_string())?;
+	Ok(())
+}
@@ -0,0 +1,267 @@
+/**
+ * The version of the state file. It has to be bumped whenever the state of any scene changes shape, so that files from
+ * older versions are ignored rather than misread.
+ */
+const STATE_VERSION: u32 = 1;
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct StateFile {
+	version: u32,
+	scenes: std::collections::HashMap<crate::config::SceneKind, serde_json::Value>,
+}
+
+/**
+ * The saved simulation state of every scene that has any, which is kept in a JSON file so that scenes carry on where
+ * they left off when the background restarts. Scenes keep their own state while others are shown, so switching back
+ * to one picks it up again too.
+ */
+pub struct SceneStateStore {
+	path: std::path::PathBuf,
+	scene_states: std::collections::HashMap<crate::config::SceneKind, serde_json::Value>,
+}
+
+impl SceneStateStore {
+	/**
+	 * Read the state file. A file that is missing, can't be read or is from another version is treated as empty, so
+	 * its state is replaced the next time it's saved.
+	 */
+	pub fn load(path: std::path::PathBuf) -> Self {
+		let scene_states = match Self::read(&path) {
+			Ok(scene_states) => scene_states,
+			Err(error) => {
+				log::warn!("{}, so scenes start over", error);
+				std::collections::HashMap::new()
+			}
+		};
+		Self { path, scene_states }
+	}
+
+	fn read(
+		path: &std::path::Path,
+	) -> Result<std::collections::HashMap<crate::config::SceneKind, serde_json::Value>, String> {
+		let contents = match std::fs::read_to_string(path) {
+			Ok(contents) => contents,
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+				return Ok(std::collections::HashMap::new())
+			}
+			Err(error) => {
+				return Err(format!(
+					"Could not read the state file {}: {}",
+					path.display(),
+					error
+				))
+			}
+		};
+		// The version is checked before anything else is read since the rest of an older file might not have the same
+		// shape.
+		let state_file = serde_json::from_str::<serde_json::Value>(&contents)
+			.map_err(|error| format!("The state file {} is invalid: {}", path.display(), error))?;
+		let version = state_file
+			.get("version")
+			.and_then(serde_json::Value::as_u64);
+		if version != Some(STATE_VERSION as u64) {
+			log::info!(
+				"The state file {} is from another version, so scenes start over",
+				path.display()
+			);
+			return Ok(std::collections::HashMap::new());
+		}
+		serde_json::from_value::<StateFile>(state_file)
+			.map(|state_file| state_file.scenes)
+			.map_err(|error| format!("The state file {} is invalid: {}", path.display(), error))
+	}
+
+	pub fn scene_state(&self, scene_kind: crate::config::SceneKind) -> Option<&serde_json::Value> {
+		self.scene_states.get(&scene_kind)
+	}
+
+	pub fn set_scene_state(
+		&mut self,
+		scene_kind: crate::config::SceneKind,
+		scene_state: serde_json::Value,
+	) {
+		self.scene_states.insert(scene_kind, scene_state);
+	}
+
+	/**
+	 * Write the state file. It's written next to where it goes and then moved into place, so that a crash while
+	 * saving leaves the previous state rather than half of a file.
+	 */
+	pub fn save(&self) -> Result<(), String> {
+		let state_file = StateFile {
+			version: STATE_VERSION,
+			scenes: self.scene_states.clone(),
+		};
+		let contents = serde_json::to_string(&state_file).map_err(|error| error.to_string())?;
+		if let Some(directory) = self.path.parent() {
+			std::fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+		}
+		let temporary_path = self.path.with_extension("json.tmp");
+		std::fs::write(&temporary_path, contents)
+			.and_then(|_| std::fs::rename(&temporary_path, &self.path))
+			.map_err(|error| error.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{SceneStateStore, STATE_VERSION};
+	use crate::config::SceneKind;
+
+	/**
+	 * A directory for state files, which is removed once the test is done with it.
+	 */
+	struct StateDirectory(std::path::PathBuf);
+
+	impl StateDirectory {
+		fn new(name: &str) -> Self {
+			let path =
+				std::env::temp_dir().join(format!("scene-state-{}-{}", std::process::id(), name));
+			let _ = std::fs::remove_dir_all(&path);
+			Self(path)
+		}
+
+		fn state_path(&self) -> std::path::PathBuf {
+			self.0.join("state.json")
+		}
+	}
+
+	impl Drop for StateDirectory {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_dir_all(&self.0);
+		}
+	}
+
+	fn write_state_file(state_directory: &StateDirectory, contents: &str) {
+		std::fs::create_dir_all(&state_directory.0).unwrap();
+		std::fs::write(state_directory.state_path(), contents).unwrap();
+	}
+
+	#[test]
+	fn round_trips_scene_states() {
+		let state_directory = StateDirectory::new("round-trip");
+		let mut scene_state_store = SceneStateStore::load(state_directory.state_path());
+		assert!(scene_state_store
+			.scene_state(SceneKind::BouncingCube)
+			.is_none());
+		scene_state_store
+			.set_scene_state(SceneKind::BouncingCube, serde_json::json!({ "angle": 1.5 }));
+		scene_state_store.save().unwrap();
+
+		let scene_state_store = SceneStateStore::load(state_directory.state_path());
+		assert_eq!(
+			scene_state_store.scene_state(SceneKind::BouncingCube),
+			Some(&serde_json::json!({ "angle": 1.5 }))
+		);
+		assert!(scene_state_store.scene_state(SceneKind::Boids).is_none());
+	}
+
+	#[test]
+	fn saves_without_leaving_a_temporary_file_behind() {
+		let state_directory = StateDirectory::new("temporary-file");
+		let mut scene_state_store = SceneStateStore::load(state_directory.state_path());
+		scene_state_store.set_scene_state(SceneKind::BouncingCube, serde_json::json!([1, 2, 3]));
+		scene_state_store.save().unwrap();
+		scene_state_store.set_scene_state(SceneKind::BouncingCube, serde_json::json!([4, 5, 6]));
+		scene_state_store.save().unwrap();
+
+		let file_names = std::fs::read_dir(&state_directory.0)
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name())
+			.collect::<Vec<_>>();
+		assert_eq!(file_names, vec!["state.json"]);
+		let state_file = serde_json::from_str::<serde_json::Value>(
+			&std::fs::read_to_string(state_directory.state_path()).unwrap(),
+		)
+		.unwrap();
+		assert_eq!(state_file["version"], STATE_VERSION);
+		assert_eq!(
+			state_file["scenes"]["bouncing_cube"],
+			serde_json::json!([4, 5, 6])
+		);
+	}
+
+	#[test]
+	fn ignores_state_files_from_other_versions() {
+		let state_directory = StateDirectory::new("version");
+		write_state_file(
+			&state_directory,
+			&serde_json::json!({
+				"version": STATE_VERSION + 1,
+				"scenes": { "bouncing_cube": { "angle": 1.5 } },
+			})
+			.to_string(),
+		);
+		let scene_state_store = SceneStateStore::load(state_directory.state_path());
+		assert!(scene_state_store
+			.scene_state(SceneKind::BouncingCube)
+			.is_none());
+
+		// Files from versions that had no version field are ignored too, whatever shape the rest of them has.
+		write_state_file(&state_directory, r#"{"bouncing_cube": 3}"#);
+		let scene_state_store = SceneStateStore::load(state_directory.state_path());
+		assert!(scene_state_store
+			.scene_state(SceneKind::BouncingCube)
+			.is_none());
+	}
+
+	#[test]
+	fn ignores_corrupt_state_files() {
+		let state_directory = StateDirectory::new("corrupt");
+		let state_file = serde_json::json!({
+			"version": STATE_VERSION,
+			"scenes": { "bouncing_cube": { "angle": 1.5 } },
+		})
+		.to_string();
+		for contents in [
+			&state_file[..state_file.len() / 2],
+			"",
+			"not json",
+			&format!(r#"{{"version": {}, "scenes": 5}}"#, STATE_VERSION),
+			&format!(
+				r#"{{"version": {}, "scenes": {{"no_such_scene": 1}}}}"#,
+				STATE_VERSION
+			),
+		] {
+			write_state_file(&state_directory, contents);
+			let scene_state_store = SceneStateStore::load(state_directory.state_path());
+			assert!(scene_state_store
+				.scene_state(SceneKind::BouncingCube)
+				.is_none());
+		}
+	}
+
+	#[test]
+	fn restores_the_bouncing_cube_from_a_saved_state() {
+		let state_directory = StateDirectory::new("bouncing-cube");
+		let mut bouncing_cube_model =
+			crate::scene::bouncing_cube::bouncing_cube_model::BouncingCubeSceneInformation::new(
+				1920.0, 1080.0,
+			);
+		bouncing_cube_model.update(0.5);
+		let mut scene_state_store = SceneStateStore::load(state_directory.state_path());
+		scene_state_store.set_scene_state(
+			SceneKind::BouncingCube,
+			serde_json::to_value(bouncing_cube_model.state()).unwrap(),
+		);
+		scene_state_store.save().unwrap();
+
+		let scene_state_store = SceneStateStore::load(state_directory.state_path());
+		let state = serde_json::from_value(
+			scene_state_store
+				.scene_state(SceneKind::BouncingCube)
+				.unwrap()
+				.clone(),
+		)
+		.unwrap();
+		let mut restored_model =
+			crate::scene::bouncing_cube::bouncing_cube_model::BouncingCubeSceneInformation::new(
+				1920.0, 1080.0,
+			);
+		restored_model.restore_state(&state).unwrap();
+		assert_eq!(
+			serde_json::to_value(restored_model.state()).unwrap(),
+			serde_json::to_value(bouncing_cube_model.state()).unwrap()
+		);
+	}
+}
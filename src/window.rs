@@ -1,7 +1,6 @@
-use crate::scene::bouncing_cube::BouncingCubeScene;
 use crate::scene::Scene;
 use winit::{
-	event::{Event, WindowEvent},
+	event::{ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent},
 	event_loop::{ControlFlow, EventLoop},
 	platform::unix::{WindowBuilderExtUnix, XWindowType},
 	window::{Window, WindowBuilder},
@@ -11,10 +10,92 @@ pub struct DemoWindow {
 	window: Window,
 	window_size: winit::dpi::PhysicalSize<u32>,
 	surface_configuration: wgpu::SurfaceConfiguration,
+	render_target_configuration: crate::scene::utilities::render_target::RenderTargetConfiguration,
 	surface: wgpu::Surface,
 	device: wgpu::Device,
 	queue: wgpu::Queue,
 	scene: Box<dyn Scene>,
+	tonemapper: crate::scene::utilities::tonemapping::Tonemapper,
+	bloom: Option<crate::scene::utilities::bloom::Bloom>,
+	post_process_chain: Option<crate::scene::utilities::post_processing::PostProcessChain>,
+	cursor_state: crate::scene::CursorState,
+	/**
+	 * The shortest time between frames, which is only set in low power mode.
+	 */
+	minimum_frame_interval: Option<std::time::Duration>,
+	clock_overlay: Option<crate::overlay::clock::ClockOverlay>,
+	system_stats_overlay: Option<crate::overlay::system_stats::SystemStatsOverlay>,
+	audio_input: Option<crate::audio::AudioInput>,
+	config: crate::config::Config,
+	/**
+	 * The values that have been set through the control socket, which are applied over the configuration file until
+	 * the demo is restarted.
+	 */
+	config_overrides: Vec<(String, toml::Value)>,
+	is_paused: bool,
+	/**
+	 * Whether the event loop should exit, which is set by commands since they're handled without access to the
+	 * control flow.
+	 */
+	is_shutting_down: bool,
+	/**
+	 * The time between frames, averaged over the last few frames.
+	 */
+	average_frame_interval: f32,
+	control_request_receiver: std::sync::mpsc::Receiver<crate::control::ControlRequest>,
+	/**
+	 * Only held onto so that the control socket stays open while the window does and is removed along with it.
+	 */
+	_control_socket: Option<crate::control::socket::ControlSocket>,
+	_control_http_server: Option<crate::control::http::ControlHttpServer>,
+	/**
+	 * Where the timing of every frame is sent, for clients that are following along.
+	 */
+	frame_timing_senders: Vec<std::sync::mpsc::SyncSender<crate::control::FrameTiming>>,
+	/**
+	 * What captures frames for screenshots, which is only made once the first screenshot is asked for.
+	 */
+	frame_capture: Option<crate::screenshot::FrameCapture>,
+	/**
+	 * The screenshots that will be taken of the next frame, along with where they go and the commands that asked for
+	 * them, which screenshots taken with the keyboard don't have.
+	 */
+	pending_screenshots: Vec<(
+		crate::screenshot::ScreenshotDestination,
+		Option<crate::control::ControlRequest>,
+	)>,
+	/**
+	 * The saved state of the scenes, which is None when it's turned off or there is nowhere to save it.
+	 */
+	scene_state_store: Option<crate::state::SceneStateStore>,
+	last_state_save_instant: std::time::Instant,
+}
+
+/**
+ * The key that saves a screenshot into the pictures directory. Background windows can't be focused, so it only works
+ * in windowed mode.
+ */
+const SCREENSHOT_KEY: VirtualKeyCode = VirtualKeyCode::F12;
+
+/**
+ * How often the device is polled while captured frames are being read back in low power mode, where frames would
+ * otherwise be too far apart for the readbacks to finish promptly.
+ */
+const READBACK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(5);
+
+/**
+ * The parts of the window that are made from the configuration, which are all made again when it changes.
+ */
+struct ConfiguredParts {
+	render_target_configuration: crate::scene::utilities::render_target::RenderTargetConfiguration,
+	scene: Box<dyn Scene>,
+	tonemapper: crate::scene::utilities::tonemapping::Tonemapper,
+	bloom: Option<crate::scene::utilities::bloom::Bloom>,
+	post_process_chain: Option<crate::scene::utilities::post_processing::PostProcessChain>,
+	minimum_frame_interval: Option<std::time::Duration>,
+	clock_overlay: Option<crate::overlay::clock::ClockOverlay>,
+	system_stats_overlay: Option<crate::overlay::system_stats::SystemStatsOverlay>,
+	audio_input: Option<crate::audio::AudioInput>,
 }
 
 impl DemoWindow {
@@ -22,7 +103,11 @@ impl DemoWindow {
 	 * Create a new DemoWindow.
 	 * Creating a background window assumes that X is being used.
 	 */
-	pub async fn new(event_loop: &EventLoop<()>, is_background: bool) -> Self {
+	pub async fn new(
+		event_loop: &EventLoop<()>,
+		is_background: bool,
+		config: crate::config::Config,
+	) -> Self {
 		// Create the window.
 		let window = if is_background {
 			// Create a window for the background that isn't managed by window managers and that has the size of the
@@ -48,7 +133,11 @@ impl DemoWindow {
 		// Create the device and the queue.
 		let adapter = instance
 			.request_adapter(&wgpu::RequestAdapterOptions {
-				power_preference: wgpu::PowerPreference::default(),
+				power_preference: if config.render.low_power {
+					wgpu::PowerPreference::LowPower
+				} else {
+					wgpu::PowerPreference::default()
+				},
 				force_fallback_adapter: false,
 				compatible_surface: Some(&surface),
 			})
@@ -69,30 +158,465 @@ impl DemoWindow {
 			.await
 			.unwrap();
 
-		// Configure the surface.
+		// Configure the surface. Whether it can be copied from for screenshots can't be asked, and configuring it with a
+		// usage that it doesn't allow can't be recovered from, so screenshots are drawn into a mirror texture unless
+		// copying from the surface has been turned on.
+		let surface_usage = if config.render.copy_surface {
+			wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+		} else {
+			wgpu::TextureUsages::RENDER_ATTACHMENT
+		};
 		let surface_configuration = wgpu::SurfaceConfiguration {
-			usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+			usage: surface_usage,
 			format: surface.get_preferred_format(&adapter).unwrap(),
 			width: window.inner_size().width,
 			height: window.inner_size().height,
 			present_mode: wgpu::PresentMode::Fifo,
 		};
 		surface.configure(&device, &surface_configuration);
+		let ConfiguredParts {
+			render_target_configuration,
+			scene,
+			tonemapper,
+			bloom,
+			post_process_chain,
+			minimum_frame_interval,
+			clock_overlay,
+			system_stats_overlay,
+			audio_input,
+		} = Self::create_configured_parts(&device, &queue, &surface_configuration, &config);
 
-		// Make the scene
-		let scene = Box::new(BouncingCubeScene::new(&device, &surface_configuration));
+		let (control_sender, control_request_receiver) = crate::control::channel(event_loop);
+		let control_socket = if config.control.enabled {
+			match config.control.socket_path() {
+				Some(socket_path) => {
+					match crate::control::socket::ControlSocket::new(
+						socket_path,
+						control_sender.clone(),
+					) {
+						Ok(control_socket) => Some(control_socket),
+						Err(error) => {
+							log::warn!("Could not open the control socket: {}", error);
+							None
+						}
+					}
+				}
+				None => {
+					log::warn!(
+						"Could not open the control socket since $XDG_RUNTIME_DIR isn't set and no socket path was configured"
+					);
+					None
+				}
+			}
+		} else {
+			None
+		};
+		if let Err(error) = crate::signals::listen(control_sender.clone()) {
+			log::warn!("Could not listen for signals: {}", error);
+		}
+		let control_http_server = config.control.http_port.and_then(|http_port| {
+			crate::control::http::ControlHttpServer::new(http_port, control_sender)
+				.map_err(|error| log::warn!("Could not start the HTTP API: {}", error))
+				.ok()
+		});
 
-		Self {
+		let scene_state_store = if config.state.enabled {
+			let state_path = config.state.path();
+			if state_path.is_none() {
+				log::warn!(
+					"Could not restore the scene since there is nowhere to find the state file without $HOME or $XDG_STATE_HOME"
+				);
+			}
+			state_path.map(crate::state::SceneStateStore::load)
+		} else {
+			None
+		};
+
+		let mut demo_window = Self {
 			window,
 			window_size,
 			surface_configuration,
+			render_target_configuration,
 			surface,
 			device,
 			queue,
 			scene,
+			tonemapper,
+			bloom,
+			post_process_chain,
+			cursor_state: crate::scene::CursorState::default(),
+			minimum_frame_interval,
+			clock_overlay,
+			system_stats_overlay,
+			audio_input,
+			config,
+			config_overrides: Vec::new(),
+			is_paused: false,
+			is_shutting_down: false,
+			average_frame_interval: 0.0,
+			control_request_receiver,
+			_control_socket: control_socket,
+			_control_http_server: control_http_server,
+			frame_timing_senders: Vec::new(),
+			frame_capture: None,
+			pending_screenshots: Vec::new(),
+			scene_state_store,
+			last_state_save_instant: std::time::Instant::now(),
+		};
+		demo_window.restore_scene_state();
+		demo_window
+	}
+
+	/**
+	 * Make everything that depends on the configuration. Whether a low power adapter is asked for can't change after
+	 * the window has been made, so only the rest of the low power settings come from here.
+	 */
+	fn create_configured_parts(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		surface_configuration: &wgpu::SurfaceConfiguration,
+		config: &crate::config::Config,
+	) -> ConfiguredParts {
+		let render_target_configuration =
+			crate::scene::utilities::render_target::RenderTargetConfiguration::new(
+				device,
+				crate::scene::utilities::tonemapping::HDR_FORMAT,
+				surface_configuration.width,
+				surface_configuration.height,
+				config.render.sample_count,
+			);
+
+		// Make the post-processing that the scene renders through.
+		let post_process_chain = if config.post_processing.is_empty() {
+			None
+		} else {
+			Some(
+				crate::scene::utilities::post_processing::PostProcessChain::new(
+					device,
+					queue,
+					surface_configuration,
+					&config.post_processing,
+				),
+			)
+		};
+		let tonemapper = crate::scene::utilities::tonemapping::Tonemapper::new(
+			device,
+			surface_configuration,
+			if post_process_chain.is_some() {
+				crate::scene::utilities::post_processing::FORMAT
+			} else {
+				surface_configuration.format
+			},
+			&config.tonemapping,
+		);
+		let bloom = if config.bloom.enabled {
+			Some(crate::scene::utilities::bloom::Bloom::new(
+				device,
+				surface_configuration,
+				tonemapper.hdr_texture(),
+				&config.bloom,
+			))
+		} else {
+			None
+		};
+
+		let clock_overlay = if config.clock.enabled {
+			match crate::overlay::clock::ClockOverlay::new(
+				device,
+				surface_configuration.format,
+				surface_configuration.width,
+				surface_configuration.height,
+				&config.clock,
+			) {
+				Ok(clock_overlay) => Some(clock_overlay),
+				Err(error) => {
+					log::warn!("Could not show the clock: {}", error);
+					None
+				}
+			}
+		} else {
+			None
+		};
+
+		let system_stats_overlay = if config.system_stats.enabled {
+			match crate::overlay::system_stats::SystemStatsOverlay::new(
+				device,
+				surface_configuration.format,
+				surface_configuration.width,
+				surface_configuration.height,
+				&config.system_stats,
+			) {
+				Ok(system_stats_overlay) => Some(system_stats_overlay),
+				Err(error) => {
+					log::warn!("Could not show the system stats: {}", error);
+					None
+				}
+			}
+		} else {
+			None
+		};
+
+		// Make the scene
+		let scene = crate::scene::create_scene(device, queue, &render_target_configuration, config);
+
+		ConfiguredParts {
+			render_target_configuration,
+			scene,
+			tonemapper,
+			bloom,
+			post_process_chain,
+			minimum_frame_interval: if config.render.low_power {
+				Some(std::time::Duration::from_secs_f32(
+					1.0 / config.render.low_power_frame_rate.max(0.1),
+				))
+			} else {
+				None
+			},
+			clock_overlay,
+			system_stats_overlay,
+			audio_input: config
+				.audio
+				.source
+				.clone()
+				.map(|source| crate::audio::AudioInput::new(source, &config.audio)),
 		}
 	}
 
+	/**
+	 * Load the configuration file again with the overrides applied over it, and remake everything that depends on it.
+	 * Nothing changes if the configuration can't be loaded.
+	 */
+	fn reload_config(&mut self) -> Result<(), String> {
+		let config = crate::config::Config::load_with_overrides(&self.config_overrides)?;
+		let ConfiguredParts {
+			render_target_configuration,
+			scene,
+			tonemapper,
+			bloom,
+			post_process_chain,
+			minimum_frame_interval,
+			clock_overlay,
+			system_stats_overlay,
+			audio_input,
+		} = Self::create_configured_parts(
+			&self.device,
+			&self.queue,
+			&self.surface_configuration,
+			&config,
+		);
+		// The state of the scene is handed over to the new one, which might be the same kind of scene, or goes back into
+		// the store to be picked up again when the scene is switched back to.
+		self.store_scene_state();
+		self.render_target_configuration = render_target_configuration;
+		self.scene = scene;
+		self.scene.cursor_changed(&self.cursor_state);
+		self.tonemapper = tonemapper;
+		self.bloom = bloom;
+		self.post_process_chain = post_process_chain;
+		self.minimum_frame_interval = minimum_frame_interval;
+		self.clock_overlay = clock_overlay;
+		self.system_stats_overlay = system_stats_overlay;
+		self.audio_input = audio_input;
+		self.config = config;
+		self.restore_scene_state();
+		Ok(())
+	}
+
+	/**
+	 * Put the state of the scene into the store without saving it.
+	 */
+	fn store_scene_state(&mut self) {
+		if let Some(scene_state_store) = &mut self.scene_state_store {
+			if let Some(scene_state) = self.scene.save_state() {
+				scene_state_store.set_scene_state(self.config.scene_kind(), scene_state);
+			}
+		}
+	}
+
+	/**
+	 * Put the state of the scene into the store and write the state file.
+	 */
+	fn save_scene_state(&mut self) {
+		self.store_scene_state();
+		self.last_state_save_instant = std::time::Instant::now();
+		if let Some(scene_state_store) = &self.scene_state_store {
+			if let Err(error) = scene_state_store.save() {
+				log::warn!("Could not save the state of the scene: {}", error);
+			}
+		}
+	}
+
+	/**
+	 * Let the scene carry on from the state in the store, if there is any for it.
+	 */
+	fn restore_scene_state(&mut self) {
+		let scene_kind = self.config.scene_kind();
+		let scene_state = self
+			.scene_state_store
+			.as_ref()
+			.and_then(|scene_state_store| scene_state_store.scene_state(scene_kind))
+			.cloned();
+		if let Some(scene_state) = scene_state {
+			if let Err(error) = self.scene.restore_state(scene_state) {
+				log::warn!(
+					"Could not restore the state of the scene, so it starts over: {}",
+					error
+				);
+			}
+		}
+	}
+
+	/**
+	 * Whether it's time to save the state of the scene again in case the background crashes.
+	 */
+	fn is_state_save_due(&self) -> bool {
+		self.scene_state_store.is_some()
+			&& self.config.state.save_interval > 0.0
+			&& self.last_state_save_instant.elapsed().as_secs_f32()
+				>= self.config.state.save_interval
+	}
+
+	/**
+	 * Set a value over the configuration file and apply it. Keys that aren't in the configuration are rejected rather
+	 * than ignored like they are in the file, and the value is dropped again if the configuration can't be loaded with
+	 * it.
+	 */
+	fn set_config_override(&mut self, key: String, value: toml::Value) -> Result<(), String> {
+		self.config_value(&key)?;
+		let previous_overrides = self.config_overrides.clone();
+		self.config_overrides
+			.retain(|(override_key, _)| *override_key != key);
+		self.config_overrides.push((key, value));
+		let result = self.reload_config();
+		if result.is_err() {
+			self.config_overrides = previous_overrides;
+		}
+		result
+	}
+
+	/**
+	 * Carry out a command from the control socket or the HTTP API. Screenshots are answered once the next frame has
+	 * been drawn, while everything else is answered right away.
+	 */
+	fn handle_control_request(&mut self, control_request: crate::control::ControlRequest) {
+		let response = match &control_request.command {
+			crate::control::ControlCommand::Status => Ok(self.status()),
+			crate::control::ControlCommand::ListScenes => Ok(serde_json::json!({
+				"current": self.config.scene_kind(),
+				"scenes": crate::config::SceneKind::ALL,
+			})),
+			crate::control::ControlCommand::SwitchScene(scene_kind) => {
+				self.switch_scene(*scene_kind)
+			}
+			crate::control::ControlCommand::NextScene => {
+				self.switch_scene(self.config.scene_kind().next())
+			}
+			crate::control::ControlCommand::Pause => {
+				self.is_paused = true;
+				Ok(serde_json::Value::Null)
+			}
+			crate::control::ControlCommand::Resume => {
+				self.is_paused = false;
+				Ok(serde_json::Value::Null)
+			}
+			crate::control::ControlCommand::TogglePause => {
+				self.is_paused = !self.is_paused;
+				Ok(serde_json::json!({ "paused": self.is_paused }))
+			}
+			crate::control::ControlCommand::ReloadConfig => {
+				self.reload_config().map(|_| serde_json::Value::Null)
+			}
+			crate::control::ControlCommand::GetParameter { key } => self.config_value(key),
+			crate::control::ControlCommand::SetParameter { key, value } => self
+				.set_config_override(key.clone(), value.clone())
+				.map(|_| serde_json::Value::Null),
+			crate::control::ControlCommand::Screenshot(path) => {
+				let path = path.clone().unwrap_or_else(crate::screenshot::default_path);
+				self.queue_screenshot(
+					crate::screenshot::ScreenshotDestination::File(path),
+					Some(control_request),
+				);
+				return;
+			}
+			crate::control::ControlCommand::CaptureFrame(png_sender) => {
+				let png_sender = png_sender.clone();
+				self.queue_screenshot(
+					crate::screenshot::ScreenshotDestination::Png(png_sender),
+					Some(control_request),
+				);
+				return;
+			}
+			crate::control::ControlCommand::SubscribeFrameTimings(frame_timing_sender) => {
+				self.frame_timing_senders.push(frame_timing_sender.clone());
+				Ok(serde_json::Value::Null)
+			}
+			crate::control::ControlCommand::Shutdown => {
+				self.is_shutting_down = true;
+				Ok(serde_json::Value::Null)
+			}
+		};
+		control_request.respond(response);
+	}
+
+	fn queue_screenshot(
+		&mut self,
+		destination: crate::screenshot::ScreenshotDestination,
+		control_request: Option<crate::control::ControlRequest>,
+	) {
+		self.pending_screenshots
+			.push((destination, control_request));
+		// Frames might be far apart in low power mode, so one is drawn right away.
+		self.window.request_redraw();
+	}
+
+	/**
+	 * Get the value in use for a dotted key of the configuration. The scene is the one being shown, the same as in the
+	 * status, rather than nothing when the configuration leaves it out.
+	 */
+	fn config_value(&self, key: &str) -> crate::control::ControlResponse {
+		let mut value = serde_json::to_value(&self.config).map_err(|error| error.to_string())?;
+		value["scene"] =
+			serde_json::to_value(self.config.scene_kind()).map_err(|error| error.to_string())?;
+		for key_part in key.split('.') {
+			value = value
+				.get_mut(key_part)
+				.map(serde_json::Value::take)
+				.ok_or_else(|| format!("There is no config value at {}", key))?;
+		}
+		Ok(value)
+	}
+
+	fn switch_scene(
+		&mut self,
+		scene_kind: crate::config::SceneKind,
+	) -> crate::control::ControlResponse {
+		let scene_name = toml::Value::try_from(scene_kind).map_err(|error| error.to_string())?;
+		self.set_config_override("scene".to_owned(), scene_name.clone())?;
+		Ok(serde_json::json!({ "scene": scene_name }))
+	}
+
+	/**
+	 * Get what the control socket reports about the demo.
+	 */
+	fn status(&self) -> serde_json::Value {
+		serde_json::json!({
+			"scene": self.config.scene_kind(),
+			"paused": self.is_paused,
+			"width": self.surface_configuration.width,
+			"height": self.surface_configuration.height,
+			"frames_per_second": if self.average_frame_interval > 0.0 {
+				1.0 / self.average_frame_interval
+			} else {
+				0.0
+			},
+			"overrides": self
+				.config_overrides
+				.iter()
+				.map(|(key, value)| (key.clone(), serde_json::to_value(value).unwrap_or_default()))
+				.collect::<serde_json::Map<String, serde_json::Value>>(),
+		})
+	}
+
 	/**
 	 * Handle updating this struct when the user requests a window resize.
 	 */
@@ -101,8 +625,32 @@ impl DemoWindow {
 		self.surface_configuration.height = new_size.height;
 		self.surface
 			.configure(&self.device, &self.surface_configuration);
+		self.render_target_configuration.width = new_size.width;
+		self.render_target_configuration.height = new_size.height;
 		self.window_size = new_size;
-		self.scene.resize(&self.device, &self.surface_configuration);
+		self.tonemapper
+			.resize(&self.device, &self.surface_configuration);
+		if let Some(bloom) = &mut self.bloom {
+			bloom.resize(
+				&self.device,
+				&self.surface_configuration,
+				self.tonemapper.hdr_texture(),
+			);
+		}
+		if let Some(post_process_chain) = &mut self.post_process_chain {
+			post_process_chain.resize(&self.device, &self.surface_configuration);
+		}
+		if let Some(clock_overlay) = &mut self.clock_overlay {
+			clock_overlay.resize(new_size.width, new_size.height);
+		}
+		if let Some(system_stats_overlay) = &mut self.system_stats_overlay {
+			system_stats_overlay.resize(new_size.width, new_size.height);
+		}
+		if let Some(frame_capture) = &mut self.frame_capture {
+			frame_capture.resize(&self.device, &self.surface_configuration);
+		}
+		self.scene
+			.resize(&self.device, &self.render_target_configuration);
 	}
 
 	/**
@@ -118,19 +666,104 @@ impl DemoWindow {
 				.create_command_encoder(&wgpu::CommandEncoderDescriptor {
 					label: Some("Default command encoder"),
 				});
+		// Frames that screenshots are taken of are copied out of the surface, or drawn into the mirror texture of the
+		// frame capture when the surface can't be copied from.
+		if !self.pending_screenshots.is_empty() && self.frame_capture.is_none() {
+			self.frame_capture = Some(crate::screenshot::FrameCapture::new(
+				&self.device,
+				&self.surface_configuration,
+			));
+		}
+		let frame_capture = self
+			.frame_capture
+			.as_ref()
+			.filter(|_| !self.pending_screenshots.is_empty());
+		let target_texture_view = frame_capture.map_or(&output_texture_view, |frame_capture| {
+			frame_capture.target_texture_view(&output_texture_view)
+		});
+		let hdr_texture_view = &self.tonemapper.hdr_texture().texture_view;
 		self.scene
-			.render(&mut command_encoder, &self.queue, &output_texture_view);
+			.render(&mut command_encoder, &self.queue, hdr_texture_view);
+		if let Some(bloom) = &self.bloom {
+			if self.scene.uses_bloom() {
+				bloom.render(&mut command_encoder, hdr_texture_view);
+			}
+		}
+		match &self.post_process_chain {
+			Some(post_process_chain) => {
+				self.tonemapper.render(
+					&mut command_encoder,
+					post_process_chain.input_texture_view(),
+					self.scene.uses_tonemapping(),
+				);
+				post_process_chain.render(&mut command_encoder, target_texture_view);
+			}
+			None => self.tonemapper.render(
+				&mut command_encoder,
+				target_texture_view,
+				self.scene.uses_tonemapping(),
+			),
+		}
+		if let Some(clock_overlay) = &mut self.clock_overlay {
+			clock_overlay.render(&mut command_encoder, &self.queue, target_texture_view);
+		}
+		if let Some(system_stats_overlay) = &mut self.system_stats_overlay {
+			system_stats_overlay.render(&mut command_encoder, &self.queue, target_texture_view);
+		}
+		let captured_frame = frame_capture.map(|frame_capture| {
+			frame_capture.finish(
+				&self.device,
+				&mut command_encoder,
+				&output.texture,
+				&output_texture_view,
+			)
+		});
 		self.queue.submit(std::iter::once(command_encoder.finish()));
 		output.present();
+		if let Some(captured_frame) = captured_frame {
+			let pending_screenshots = std::mem::take(&mut self.pending_screenshots);
+			captured_frame.read_image_in_background(move |image| {
+				for (destination, control_request) in pending_screenshots {
+					let response = match &image {
+						Ok(image) => destination.deliver(image),
+						Err(error) => Err(format!("Could not capture the frame: {}", error)),
+					};
+					match (control_request, response) {
+						(Some(control_request), response) => control_request.respond(response),
+						(None, Ok(result)) => log::info!("Took a screenshot: {}", result),
+						(None, Err(error)) => log::warn!("{}", error),
+					}
+				}
+			});
+		}
 		Ok(())
 	}
 
+	/**
+	 * Whether captured frames are still waiting for the device to be polled before they can be read back.
+	 */
+	fn has_readbacks_in_flight(&self) -> bool {
+		self.frame_capture
+			.as_ref()
+			.is_some_and(|frame_capture| frame_capture.has_readbacks_in_flight())
+	}
+
+	/**
+	 * Get when the next frame has to be drawn to keep the overlays up to date, regardless of the frame rate.
+	 */
+	fn next_overlay_update_instant(&self) -> Option<std::time::Instant> {
+		self.clock_overlay
+			.as_ref()
+			.map(|clock_overlay| clock_overlay.next_update_instant())
+	}
+
 	/**
 	 * Consume the DemoWindow and EventLoop and run management on the window.
 	 * While the window is open, this function is blocking.
 	 */
 	pub fn run(mut self, event_loop: EventLoop<()>) {
-		let mut previous_frame_time = 0.0;
+		let mut previous_frame_start_instant: Option<std::time::Instant> = None;
+		let mut next_frame_instant = std::time::Instant::now();
 		event_loop.run(move |event, _, control_flow| {
 			*control_flow = ControlFlow::Wait;
 			match event {
@@ -144,12 +777,123 @@ impl DemoWindow {
 						scale_factor: _,
 						new_inner_size: new_size,
 					} => self.handle_resize(**new_size),
+					WindowEvent::CursorMoved { position, .. } => {
+						self.cursor_state.position = Some([position.x as f32, position.y as f32]);
+						self.scene.cursor_changed(&self.cursor_state);
+					}
+					WindowEvent::CursorLeft { .. } => {
+						self.cursor_state.position = None;
+						self.scene.cursor_changed(&self.cursor_state);
+					}
+					WindowEvent::MouseInput {
+						state,
+						button: MouseButton::Left,
+						..
+					} => {
+						self.cursor_state.is_pressed = *state == ElementState::Pressed;
+						self.scene.cursor_changed(&self.cursor_state);
+					}
+					WindowEvent::KeyboardInput {
+						input:
+							KeyboardInput {
+								state: ElementState::Pressed,
+								virtual_keycode: Some(SCREENSHOT_KEY),
+								..
+							},
+						..
+					} => self.queue_screenshot(
+						crate::screenshot::ScreenshotDestination::File(
+							crate::screenshot::default_path(),
+						),
+						None,
+					),
 					_ => (),
 				},
-				Event::MainEventsCleared => self.window.request_redraw(),
+				// Commands from the control socket wake the event loop up with a user event.
+				Event::UserEvent(()) => {
+					while let Ok(control_request) = self.control_request_receiver.try_recv() {
+						self.handle_control_request(control_request);
+					}
+					if self.is_shutting_down {
+						*control_flow = ControlFlow::Exit;
+					}
+				}
+				// The window, the control socket and everything else are dropped along with the event loop once this
+				// returns, which destroys the window and removes the socket before the process exits.
+				Event::LoopDestroyed => {
+					log::info!("Shutting down");
+					self.save_scene_state();
+					log::logger().flush();
+				}
+				Event::MainEventsCleared => {
+					// Captured frames are read back on other threads, which wait for the device to be polled.
+					if self.has_readbacks_in_flight() {
+						self.device.poll(wgpu::Maintain::Poll);
+					}
+					match self.minimum_frame_interval {
+						Some(minimum_frame_interval) => {
+							let now = std::time::Instant::now();
+							if now >= next_frame_instant {
+								next_frame_instant = now + minimum_frame_interval;
+								self.window.request_redraw();
+							} else if self
+								.next_overlay_update_instant()
+								.is_some_and(|overlay_update_instant| now >= overlay_update_instant)
+							{
+								// Draw an extra frame so that overlays such as the clock change when they should instead
+								// of at the next regular frame.
+								self.window.request_redraw();
+							}
+						}
+						None => self.window.request_redraw(),
+					}
+				}
+				// In low power mode, sleep until the next frame is due instead of drawing as fast as possible. This has
+				// to happen after the redraw since every event resets the control flow.
+				Event::RedrawEventsCleared if self.minimum_frame_interval.is_some() => {
+					let mut wake_instant = self
+						.next_overlay_update_instant()
+						.map_or(next_frame_instant, |overlay_update_instant| {
+							overlay_update_instant.min(next_frame_instant)
+						});
+					if self.has_readbacks_in_flight() {
+						wake_instant =
+							wake_instant.min(std::time::Instant::now() + READBACK_POLL_INTERVAL);
+					}
+					*control_flow = ControlFlow::WaitUntil(wake_instant);
+				}
 				Event::RedrawRequested(window_id) if window_id == self.window.id() => {
+					// Measure the time between the starts of frames rather than how long the last frame took to draw, so
+					// that scenes still move at the right speed when frames are spaced out in low power mode.
 					let frame_start_instant = std::time::Instant::now();
-					self.scene.update(previous_frame_time);
+					let dt =
+						previous_frame_start_instant.map_or(0.0, |previous_frame_start_instant| {
+							(frame_start_instant - previous_frame_start_instant).as_secs_f32()
+						});
+					previous_frame_start_instant = Some(frame_start_instant);
+					if dt > 0.0 {
+						self.average_frame_interval = if self.average_frame_interval > 0.0 {
+							0.9 * self.average_frame_interval + 0.1 * dt
+						} else {
+							dt
+						};
+					}
+					// Overlays keep going while the scene is paused so that the clock still shows the right time.
+					if !self.is_paused {
+						if let Some(audio_input) = &self.audio_input {
+							self.scene.audio_changed(&audio_input.features());
+						}
+						self.scene.update(dt);
+						if let Some(post_process_chain) = &mut self.post_process_chain {
+							post_process_chain.update(dt);
+						}
+					}
+					if let Some(clock_overlay) = &mut self.clock_overlay {
+						clock_overlay.update();
+					}
+					if let Some(system_stats_overlay) = &mut self.system_stats_overlay {
+						system_stats_overlay.update();
+					}
 					let frame_draw_result = self.draw_frame();
 					match frame_draw_result {
 						Ok(_) => (),
@@ -157,7 +901,22 @@ impl DemoWindow {
 						Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
 						Err(_) => (),
 					}
-					previous_frame_time = frame_start_instant.elapsed().as_secs_f32();
+					if !self.frame_timing_senders.is_empty() {
+						let frame_timing = crate::control::FrameTiming {
+							frame_interval: dt,
+							draw_duration: frame_start_instant.elapsed().as_secs_f32(),
+						};
+						// Clients that can't keep up miss timings, but the ones that have gone away are forgotten.
+						self.frame_timing_senders.retain(|frame_timing_sender| {
+							!matches!(
+								frame_timing_sender.try_send(frame_timing),
+								Err(std::sync::mpsc::TrySendError::Disconnected(_))
+							)
+						});
+					}
+					if self.is_state_save_due() {
+						self.save_scene_state();
+					}
 				}
 				_ => (),
 			}